@@ -60,6 +60,8 @@ fn get_env_variables() -> () {
         "cargo:rustc-env=AUTH_TOKEN={}{}",
         root_dir, "secrets/token.json"
     );
+    println!("cargo:rustc-env=SAVES_DIR={}{}", root_dir, "saves/");
+    println!("cargo:rustc-env=UCI_ENGINE={}{}", root_dir, "engines/uci");
     println!(
         "cargo:rustc-env=LICHESS_API_BASE={}",
         "https://lichess.org/api"
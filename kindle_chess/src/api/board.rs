@@ -1,15 +1,20 @@
-use crate::api::oauth::authenticated_request;
-use crate::app::game::player0_turn;
-use crate::models::bitboard::Bitboards;
+use crate::api::oauth::{authenticated_form_request, authenticated_request};
+use crate::api::stream::EventStream;
+use crate::app::game::{opponent_offer, player0_turn};
+use crate::models::bitboard::{Bitboards, Variant};
 use crate::models::board_api::{
-    BoardAPI, GameDataList, GameStateStreamEvent, Idle, InGame, PlayedBy, StreamEvent, Turn,
+    BoardAPI, ChatHistoryLine, GameDataList, GameStateStreamEvent, Idle, InGame, PlayedBy,
+    StreamEvent, Turn,
 };
+use crate::models::challenge::{ChallengeRequest, ChallengeTarget, CreatedChallenge, Seek};
+use crate::models::chat::{ChatMessage, ChatRoom};
+use crate::models::clock::GameClock;
 use crate::models::oauth::{HttpMethod, LichessUser, TokenInfo};
+use crate::models::stream::{StreamEnd, StreamFailure};
 use crate::ui::events::AppEvent;
-use futures::StreamExt;
 use log::{info, warn};
-use reqwest_streams::JsonStreamResponse;
 use std::sync::mpsc::Sender;
+use std::time::Duration;
 
 // State-agnostic operations: ongoing-games list and the account event stream
 // are valid in both Idle and InGame.
@@ -39,39 +44,73 @@ impl<S> BoardAPI<S> {
         Ok(data)
     }
 
-    pub async fn stream_event(&self) -> Result<(), Box<dyn std::error::Error>> {
+    /// Open the account's event stream and drive it until the connection
+    /// ends. Runs for the whole session once signed in, reopened by the
+    /// stream supervisor when it drops; what the app acts on is shipped as
+    /// an `AppEvent` through `tx`.
+    pub async fn stream_event(&self, tx: Sender<AppEvent>) -> Result<StreamEnd, StreamFailure> {
         let url = format!("{}/stream/event", env!("LICHESS_API_BASE"));
 
         info!("Getting event stream");
-        let mut response = authenticated_request(url, &self.token, HttpMethod::GET)
-            .await?
-            .json_nl_stream::<StreamEvent>(1024);
-
-        while let Some(result) = response.next().await {
-            match result {
-                Ok(event) => {
-                    info!("Received event: {:?}", event);
-                    self.handle_event(event).await?;
+        let mut stream = EventStream::<StreamEvent>::open(url, &self.token).await?;
+        while let Some(event) = stream.next().await {
+            info!("Received event: {:?}", event);
+            self.handle_event(event, &tx);
+        }
+        Ok(stream.dropped())
+    }
+
+    pub fn handle_event(&self, event: StreamEvent, tx: &Sender<AppEvent>) {
+        let event = match event {
+            StreamEvent::GameStart { game } => {
+                if !game.is_fresh() {
+                    info!("Game {} already under way — not opening it", game.game_id);
+                    return;
                 }
-                Err(e) => {
-                    if e.to_string().contains("EOF while parsing") {
-                        continue;
-                    }
-                    warn!("Error parsing event: {}", e);
+                AppEvent::GameStarted(game)
+            }
+            StreamEvent::GameFinish { game } => AppEvent::GameFinished(game.game_id),
+            StreamEvent::Challenge { challenge } => {
+                // Our own challenges are announced too.
+                if challenge.is_from(&self.user.id) {
+                    return;
                 }
+                AppEvent::ChallengeReceived(challenge)
             }
-        }
+            StreamEvent::ChallengeCanceled { challenge } => {
+                AppEvent::ChallengeCanceled(challenge.id)
+            }
+            StreamEvent::ChallengeDeclined { challenge } => AppEvent::ChallengeDeclined {
+                id: challenge.id,
+                reason: challenge.decline_reason,
+            },
+        };
+        let _ = tx.send(event);
+    }
 
-        Ok(())
+    pub async fn accept_challenge(&self, id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.post_challenge_action(id, "accept").await
     }
 
-    pub async fn handle_event(&self, event: StreamEvent) -> Result<(), Box<dyn std::error::Error>> {
-        match event {
-            StreamEvent::GameStart(_) => info!("Issa GameStartEvent"),
-            StreamEvent::GameFinish(_) => info!("Issa GameFinishEvent"),
-            StreamEvent::Challenge(_) => info!("Issa ChallengeEvent"),
-            StreamEvent::ChallengeDeclined(_) => info!("Issa ChallengeDeclinedEvent"),
+    pub async fn decline_challenge(&self, id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.post_challenge_action(id, "decline").await
+    }
+
+    // POST `/challenge/{id}/{action}`.
+    async fn post_challenge_action(
+        &self,
+        id: &str,
+        action: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let url = format!("{}/challenge/{}/{}", env!("LICHESS_API_BASE"), id, action);
+        let response = authenticated_request(url, &self.token, HttpMethod::POST).await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(error_reason(status, &body).into());
         }
+        info!("{} sent for challenge {}", action, id);
         Ok(())
     }
 }
@@ -85,6 +124,64 @@ impl BoardAPI<Idle> {
         }
     }
 
+    /// Send a challenge built on the new-challenge screen. A user's answer
+    /// comes back on the account event stream; the AI's game has already
+    /// started when this returns.
+    pub async fn create_challenge(
+        &self,
+        target: &ChallengeTarget,
+        request: &ChallengeRequest,
+    ) -> Result<CreatedChallenge, Box<dyn std::error::Error>> {
+        let url = format!(
+            "{}{}",
+            env!("LICHESS_API_BASE"),
+            ChallengeRequest::path(target)
+        );
+        let fields = request.form(target);
+        let form: Vec<(&str, &str)> = fields.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        let response = authenticated_form_request(url, &self.token, &form).await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(error_reason(status, &body).into());
+        }
+        // A user challenge comes back wrapped in `challenge`, an open one
+        // and the AI's game at the top level.
+        let body: serde_json::Value = response.json().await?;
+        let created = body.get("challenge").unwrap_or(&body).clone();
+        let created: CreatedChallenge = serde_json::from_value(created)?;
+        info!("Challenge {} created for {:?}", created.id, target);
+        Ok(created)
+    }
+
+    /// Post a seek and hold it open until Lichess pairs it, which it
+    /// announces as a `gameStart` on the account event stream. The seek
+    /// stays in the lobby only as long as this request does: abort the task
+    /// running it to cancel.
+    pub async fn seek(&self, seek: &Seek) -> Result<(), Box<dyn std::error::Error>> {
+        let url = format!("{}/board/seek", env!("LICHESS_API_BASE"));
+        let fields = seek.form();
+        let form: Vec<(&str, &str)> = fields.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        let mut response = authenticated_form_request(url, &self.token, &form).await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(error_reason(status, &body).into());
+        }
+        info!("Seeking {}", seek.label());
+        // Nothing but keep-alive newlines until it's closed.
+        while response.chunk().await?.is_some() {}
+        info!("Seek closed");
+        Ok(())
+    }
+
+    /// Withdraw a challenge we sent that's still unanswered.
+    pub async fn cancel_challenge(&self, id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.post_challenge_action(id, "cancel").await
+    }
+
     /// Consume the idle API and produce an in-game one scoped to `game_id`.
     /// `my_turn` is the snapshot from `GameData.is_my_turn` at attach time so
     /// the sidebar can render something before the game-state stream catches up.
@@ -105,6 +202,8 @@ impl BoardAPI<Idle> {
                 },
                 initial_board: starting.clone(),
                 board: starting,
+                moves: String::new(),
+                initial_time: None,
             },
         }
     }
@@ -134,7 +233,11 @@ impl BoardAPI<InGame> {
         let response = authenticated_request(url, &self.token, HttpMethod::POST).await?;
 
         if !response.status().is_success() {
-            return Err(format!("Failed to move piece: {}", response.status()).into());
+            // Lichess explains a refused move in the body ("Not your turn,
+            // or game already over"); the screen shows it to the player.
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(error_reason(status, &body).into());
         }
         info!("Piece moved successfully");
         Ok(())
@@ -172,6 +275,93 @@ impl BoardAPI<InGame> {
         Ok(())
     }
 
+    /// Offer a draw, or accept the opponent's offer, with `accept`; decline
+    /// it without.
+    pub async fn handle_draw(&self, accept: bool) -> Result<(), Box<dyn std::error::Error>> {
+        self.post_game_action(&format!("draw/{}", yes_no(accept)))
+            .await
+    }
+
+    /// Propose a takeback, or accept the opponent's, with `accept`; decline
+    /// it without.
+    pub async fn handle_takeback(&self, accept: bool) -> Result<(), Box<dyn std::error::Error>> {
+        self.post_game_action(&format!("takeback/{}", yes_no(accept)))
+            .await
+    }
+
+    /// Win the game once the opponent has been gone long enough (see
+    /// `AppEvent::OpponentGone`).
+    pub async fn claim_victory(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.post_game_action("claim-victory").await
+    }
+
+    /// Say something in one of the game's chat rooms.
+    pub async fn post_chat(
+        &self,
+        room: ChatRoom,
+        text: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let url = format!(
+            "{}/board/game/{}/chat",
+            env!("LICHESS_API_BASE"),
+            self.state.game_id
+        );
+        let form = [("room", room.as_str()), ("text", text)];
+        let response = authenticated_form_request(url, &self.token, &form).await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(error_reason(status, &body).into());
+        }
+        info!("Chat message sent to the {} room", room.as_str());
+        Ok(())
+    }
+
+    /// The player room's messages so far, oldest first. The stream only
+    /// carries lines said while it's open.
+    pub async fn get_chat(&self) -> Result<Vec<ChatMessage>, Box<dyn std::error::Error>> {
+        let url = format!(
+            "{}/board/game/{}/chat",
+            env!("LICHESS_API_BASE"),
+            self.state.game_id
+        );
+        let response = authenticated_request(url, &self.token, HttpMethod::GET).await?;
+
+        if !response.status().is_success() {
+            return Err(format!("Failed to fetch chat: {}", response.status()).into());
+        }
+        let lines: Vec<ChatHistoryLine> = response.json().await?;
+        Ok(lines
+            .into_iter()
+            .map(|line| ChatMessage {
+                room: ChatRoom::Player,
+                username: line.user,
+                text: line.text,
+            })
+            .collect())
+    }
+
+    // POST `/board/game/{id}/{action}`. The error carries Lichess' reason
+    // when it gives one.
+    async fn post_game_action(&self, action: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let url = format!(
+            "{}/board/game/{}/{}",
+            env!("LICHESS_API_BASE"),
+            self.state.game_id,
+            action
+        );
+        let response = authenticated_request(url, &self.token, HttpMethod::POST).await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(error_reason(status, &body).into());
+        }
+        info!("{} sent for game {}", action, self.state.game_id);
+        Ok(())
+    }
+
     /// Open the game-state stream and drive it to completion.
     ///
     /// This runs inside a tokio task that owns its own clone of the API, so
@@ -180,10 +370,14 @@ impl BoardAPI<InGame> {
     /// **not** propagate back to the screen. Every state change the screen
     /// cares about is shipped as an `AppEvent` through `tx`. The screen
     /// applies those events to *its own* `ChessApp` copy.
+    ///
+    /// Every connection starts with a `gameFull`, so after a reconnect the
+    /// bookkeeping and the screen resync from it. The stream is `Finished`
+    /// once it has ended on a finished game.
     pub async fn stream_game_event(
         &mut self,
         tx: Sender<AppEvent>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<StreamEnd, StreamFailure> {
         let url = format!(
             "{}/board/game/stream/{}",
             env!("LICHESS_API_BASE"),
@@ -191,28 +385,17 @@ impl BoardAPI<InGame> {
         );
         info!("Game-state stream started for {}", self.state.game_id);
 
-        let mut response = authenticated_request(url, &self.token, HttpMethod::STREAM)
-            .await?
-            .json_nl_stream::<GameStateStreamEvent>(1024);
-
-        while let Some(result) = response.next().await {
-            match result {
-                Ok(event) => {
-                    info!("Received event: {:?}", event);
-                    if let Err(e) = self.handle_game_event(event, &tx).await {
-                        warn!("Error while handling game event: {e}");
-                    }
-                }
-                Err(e) => {
-                    // Ignore the stream ping (= empty line)
-                    if e.to_string().contains("EOF while parsing") {
-                        continue;
-                    }
-                    warn!("Error parsing event: {}", e);
-                }
+        let mut stream = EventStream::<GameStateStreamEvent>::open(url, &self.token).await?;
+        while let Some(event) = stream.next().await {
+            info!("Received event: {:?}", event);
+            if let Err(e) = self.handle_game_event(event, &tx).await {
+                warn!("Error while handling game event: {e}");
             }
         }
-        Ok(())
+        Ok(match self.state.turn {
+            Turn::Over { .. } => StreamEnd::Finished,
+            _ => stream.dropped(),
+        })
     }
 
     async fn handle_game_event(
@@ -232,6 +415,7 @@ impl BoardAPI<InGame> {
                             Some(&full.white),
                             Some(&full.black),
                         ),
+                        reason: status_reason(&full.state.status),
                     }
                 } else if my_turn {
                     Turn::Playing
@@ -239,34 +423,47 @@ impl BoardAPI<InGame> {
                     Turn::Waiting
                 };
 
-                // Initial position from `initial_fen` (usually the standard
-                // start, but Lichess hands us the FEN explicitly so chess960
-                // and odds games work too). Replay the move list to catch up
-                // if we joined mid-game.
-                let initial_board = match Bitboards::from_fen(&full.initial_fen) {
-                    Ok(bb) => bb,
-                    Err(e) => {
-                        warn!(
-                            "Bad initial FEN '{}': {} — falling back to start",
-                            full.initial_fen, e
-                        );
-                        Bitboards::starting_position()
-                    }
+                // Initial position from `initial_fen` (usually "startpos",
+                // but Lichess hands us the FEN explicitly so chess960 and
+                // odds games work too). Replay the move list to catch up if
+                // we joined mid-game.
+                let variant = full.variant.variant();
+                let mut initial_board = match full.initial_fen.as_str() {
+                    "startpos" => Bitboards::variant_start(variant),
+                    fen => Bitboards::from_fen(fen).unwrap_or_else(|e| {
+                        warn!("Bad initial FEN '{}': {} — falling back to start", fen, e);
+                        Bitboards::variant_start(variant)
+                    }),
                 };
-                let mut board = initial_board.clone();
-                board.apply_uci_moves(&full.state.moves);
-                let last_move = last_move_mask(&initial_board, &full.state.moves);
-
+                // The FEN alone doesn't say which rules apply (and a 960
+                // start can look standard: position 518).
+                if variant != Variant::Standard {
+                    initial_board.set_variant(variant);
+                }
                 // Update local bookkeeping so subsequent GameState events can
-                // resolve whose-turn-it-is from `player0_white` and rebuild
-                // the position from `initial_board`.
+                // resolve whose-turn-it-is from `player0_white` and extend
+                // the position from here.
                 self.state.white = Some(full.white.clone());
                 self.state.black = Some(full.black.clone());
                 self.state.player0_white = player0_white;
                 self.state.turn = turn.clone();
-                self.state.initial_board = initial_board;
-                self.state.board = board.clone();
-
+                self.state.initial_board = initial_board.clone();
+                self.state.board = initial_board;
+                self.state.moves.clear();
+                self.state.initial_time = full
+                    .clock
+                    .as_ref()
+                    .map(|c| Duration::from_millis(c.initial));
+                self.sync_board(&full.state.moves);
+                let board = self.state.board.clone();
+                let last_move = board.last_move_mask();
+                let live = !matches!(turn, Turn::Over { .. });
+                let clock = self.game_clock(full.state.wtime, full.state.btime, live);
+
+                let _ = tx.send(AppEvent::OpponentOffer(opponent_offer(
+                    &full.state,
+                    player0_white,
+                )));
                 let _ = tx.send(AppEvent::GameFullReceived {
                     white: full.white,
                     black: full.black,
@@ -274,6 +471,7 @@ impl BoardAPI<InGame> {
                     turn,
                     board,
                     last_move,
+                    clock,
                 });
             }
             GameStateStreamEvent::GameState(state) => {
@@ -285,6 +483,7 @@ impl BoardAPI<InGame> {
                             self.state.white.as_ref(),
                             self.state.black.as_ref(),
                         ),
+                        reason: status_reason(&state.status),
                     }
                 } else if my_turn {
                     Turn::Playing
@@ -292,46 +491,112 @@ impl BoardAPI<InGame> {
                     Turn::Waiting
                 };
 
-                // Each GameState carries the full move list from move 1, so
-                // rebuild from `initial_board` rather than tracking deltas.
-                let mut board = self.state.initial_board.clone();
-                board.apply_uci_moves(&state.moves);
-                let last_move = last_move_mask(&self.state.initial_board, &state.moves);
+                self.sync_board(&state.moves);
+                let board = self.state.board.clone();
+                let last_move = board.last_move_mask();
+                let live = !matches!(turn, Turn::Over { .. });
+                let clock = self.game_clock(state.wtime, state.btime, live);
 
                 self.state.turn = turn.clone();
-                self.state.board = board.clone();
                 let _ = tx.send(AppEvent::TurnChanged {
                     turn,
                     board,
                     last_move,
+                    clock,
                 });
+                let offer = opponent_offer(&state, self.state.player0_white);
+                let _ = tx.send(AppEvent::OpponentOffer(offer));
             }
             GameStateStreamEvent::GameOver(over) => {
                 info!("Game is over. Winner is {}", over.winner);
                 let turn = Turn::Over {
                     winner: Some(over.winner),
+                    reason: status_reason(&over.status),
                 };
 
                 // Final position from the over event's own move list — the
                 // mating move can land in either GameState or GameOver, so we
-                // rebuild rather than trusting the last GameState we saw.
-                let mut board = self.state.initial_board.clone();
-                board.apply_uci_moves(&over.moves);
-
-                let last_move = last_move_mask(&self.state.initial_board, &over.moves);
+                // sync rather than trusting the last GameState we saw.
+                self.sync_board(&over.moves);
+                let board = self.state.board.clone();
+                let last_move = board.last_move_mask();
+                let clock = self.game_clock(over.wtime, over.btime, false);
                 self.state.turn = turn.clone();
-                self.state.board = board.clone();
                 let _ = tx.send(AppEvent::TurnChanged {
                     turn,
                     board,
                     last_move,
+                    clock,
                 });
             }
-            GameStateStreamEvent::ChatLine(_) => info!("Issa ChatlineEvent"),
-            GameStateStreamEvent::OpponentGone(_) => info!("Issa OpponentGoneEvent"),
+            GameStateStreamEvent::ChatLine(line) => {
+                info!("Chat in {}: {}: {}", line.room, line.username, line.text);
+                let Some(room) = ChatRoom::parse(&line.room) else {
+                    warn!("Chat line for unknown room '{}'", line.room);
+                    return Ok(());
+                };
+                let _ = tx.send(AppEvent::ChatReceived(ChatMessage {
+                    room,
+                    username: line.username,
+                    text: line.text,
+                }));
+            }
+            GameStateStreamEvent::OpponentGone(gone) => {
+                info!("Opponent gone: {:?}", gone);
+                let claim_in = gone
+                    .gone
+                    .then(|| Duration::from_secs(gone.claim_win_in_seconds.unwrap_or(0)));
+                let _ = tx.send(AppEvent::OpponentGone(claim_in));
+            }
         }
         Ok(())
     }
+
+    // The clocks as of an event's `wtime` / `btime` (milliseconds), read
+    // after `sync_board`. Lichess starts them once both sides have moved;
+    // from then on the side to move's time runs until the game is over.
+    fn game_clock(&self, wtime: u64, btime: u64, live: bool) -> Option<GameClock> {
+        let initial = self.state.initial_time?;
+        let board = &self.state.board;
+        let running = (live && board.ply_count() >= 2).then_some(board.side_to_move);
+        Some(GameClock::new(
+            Duration::from_millis(wtime),
+            Duration::from_millis(btime),
+            running,
+            initial,
+        ))
+    }
+
+    // Bring `state.board` up to date with the server's full move list. The
+    // usual case is one new move on the end of what we already have, which
+    // costs a single `make_move`; anything else (takeback, first event)
+    // replays from `initial_board`.
+    fn sync_board(&mut self, moves: &str) {
+        let applied = &self.state.moves;
+        let tail = moves
+            .strip_prefix(applied.as_str())
+            .filter(|rest| applied.is_empty() || rest.is_empty() || rest.starts_with(' '));
+        match tail {
+            Some(tail) => self.state.board.apply_uci_moves(tail),
+            None => {
+                self.state.board = self.state.initial_board.clone();
+                self.state.board.apply_uci_moves(moves);
+            }
+        }
+        self.state.moves = moves.to_string();
+    }
+}
+
+// The `error` message of a Lichess error body, or just the status.
+pub(crate) fn error_reason(status: reqwest::StatusCode, body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v["error"].as_str().map(str::to_string))
+        .unwrap_or_else(|| status.to_string())
+}
+
+fn yes_no(accept: bool) -> &'static str {
+    if accept { "yes" } else { "no" }
 }
 
 // Lichess marks an in-progress game as `created` (no moves yet) or `started`.
@@ -341,33 +606,22 @@ fn is_terminal_status(status: &str) -> bool {
     !matches!(status, "started" | "created")
 }
 
-// Bitmask of squares affected by the last UCI move. Computed by replaying
-// every move except the last, then diffing against the post-last-move
-// position — that catches castling rook squares and en-passant captured
-// pawns without special-casing UCI string shapes.
-fn last_move_mask(initial: &Bitboards, moves_str: &str) -> u64 {
-    let moves: Vec<&str> = moves_str.split_whitespace().collect();
-    if moves.is_empty() {
-        return 0;
-    }
-    let split = moves.len() - 1;
-
-    let mut prev = initial.clone();
-    for mv in &moves[..split] {
-        let _ = prev.apply_uci_move(mv);
-    }
-    let mut curr = prev.clone();
-    if curr.apply_uci_move(moves[split]).is_err() {
-        return 0;
-    }
-
-    let mut mask = 0u64;
-    for sq in 0..64u8 {
-        if prev.piece_at(sq) != curr.piece_at(sq) {
-            mask |= 1u64 << sq;
-        }
-    }
-    mask
+// Human-readable cause for a terminal Lichess status, shown next to the
+// winner in the sidebar. Unknown statuses fall back to a plain "Game over".
+fn status_reason(status: &str) -> Option<String> {
+    let reason = match status {
+        "mate" => "Checkmate",
+        "resign" => "Resignation",
+        "stalemate" => "Stalemate",
+        "timeout" => "Opponent left",
+        "draw" => "Draw",
+        "outoftime" => "Time out",
+        "aborted" => "Aborted",
+        "noStart" => "No start",
+        "variantEnd" => "Variant end",
+        _ => return None,
+    };
+    Some(reason.to_string())
 }
 
 // Lichess sends the winning *side* ("white" / "black") on a terminal
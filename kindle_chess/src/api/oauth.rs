@@ -450,6 +450,23 @@ pub async fn authenticated_request(
     Ok(response)
 }
 
+/// POST with the parameters url-encoded in the body, as the Lichess
+/// endpoints that take more than a path want them.
+pub async fn authenticated_form_request(
+    url: String,
+    token: &TokenInfo,
+    form: &[(&str, &str)],
+) -> Result<reqwest::Response, Box<dyn std::error::Error>> {
+    let url = Url::parse(&url)?;
+    let response = reqwest::Client::new()
+        .post(url)
+        .bearer_auth(&token.access_token)
+        .form(form)
+        .send()
+        .await?;
+    Ok(response)
+}
+
 pub fn logout() -> std::io::Result<()> {
     remove_file(env!("AUTH_TOKEN"))?;
     Ok(())
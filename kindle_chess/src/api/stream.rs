@@ -0,0 +1,289 @@
+use crate::api::board::error_reason;
+use crate::api::oauth::authenticated_request;
+use crate::app::stream::jitter;
+use crate::models::oauth::{HttpMethod, TokenInfo};
+use crate::models::stream::{Backoff, StreamEnd, StreamFailure, StreamKind, StreamState};
+use crate::ui::events::AppEvent;
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use log::{info, warn};
+use reqwest_streams::{JsonStreamResponse, StreamBodyResult};
+use serde::de::DeserializeOwned;
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+// Lichess sends an empty line every few seconds on an idle stream. This long
+// without even that and the connection is as good as gone (a Wi-Fi drop
+// doesn't always close the socket).
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+// A long game's `gameFull` carries its whole move list.
+const MAX_LINE_LEN: usize = 64 * 1024;
+
+/// A newline-delimited JSON stream from Lichess, read an event at a time.
+pub struct EventStream<T> {
+    lines: BoxStream<'static, StreamBodyResult<T>>,
+    idle_timeout: Duration,
+    // Whether `next` has returned an event yet; pings don't count.
+    received_any: bool,
+}
+
+impl<T: DeserializeOwned + Send + 'static> EventStream<T> {
+    pub async fn open(url: String, token: &TokenInfo) -> Result<Self, StreamFailure> {
+        let response = authenticated_request(url, token, HttpMethod::STREAM)
+            .await
+            .map_err(|e| StreamFailure::Failed(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(StreamFailure::from_status(
+                status.as_u16(),
+                error_reason(status, &body),
+            ));
+        }
+        Ok(Self {
+            lines: response.json_nl_stream::<T>(MAX_LINE_LEN).boxed(),
+            idle_timeout: IDLE_TIMEOUT,
+            received_any: false,
+        })
+    }
+
+    pub fn idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// How this connection ended, once `next` has returned `None`.
+    pub fn dropped(&self) -> StreamEnd {
+        StreamEnd::Dropped {
+            received_any: self.received_any,
+        }
+    }
+
+    /// The next event, or `None` once the connection has closed, failed or
+    /// gone quiet.
+    pub async fn next(&mut self) -> Option<T> {
+        loop {
+            match tokio::time::timeout(self.idle_timeout, self.lines.next()).await {
+                Err(_) => {
+                    warn!("Stream silent for {:?} — dropping it", self.idle_timeout);
+                    return None;
+                }
+                Ok(None) => return None,
+                Ok(Some(Ok(event))) => {
+                    self.received_any = true;
+                    return Some(event);
+                }
+                Ok(Some(Err(e))) => {
+                    // Ignore the stream ping (= empty line)
+                    if !e.to_string().contains("EOF while parsing") {
+                        warn!("Error reading stream: {}", e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Keep a stream up. Each call to `connect` is an attempt that opens the
+/// stream and reads it to the end; whenever one ends short of
+/// `StreamEnd::Finished`, report `Reconnecting` through `tx` and make
+/// another after `backoff`. Gives up only when Lichess refuses the stream
+/// or the app has gone. The account stream's state is only logged: it has
+/// no event to say it's back up, so nothing could clear it from screen.
+pub async fn supervise<F>(
+    kind: StreamKind,
+    tx: Sender<AppEvent>,
+    mut backoff: Backoff,
+    mut connect: impl FnMut() -> F,
+) where
+    F: Future<Output = Result<StreamEnd, StreamFailure>>,
+{
+    let report = kind == StreamKind::Game;
+    loop {
+        match connect().await {
+            Ok(StreamEnd::Finished) => {
+                info!("{:?} stream finished", kind);
+                return;
+            }
+            Ok(StreamEnd::Dropped { received_any }) => {
+                info!("{:?} stream dropped", kind);
+                if received_any {
+                    backoff.reset();
+                }
+            }
+            Err(StreamFailure::Refused(reason)) => {
+                warn!("{:?} stream refused: {}", kind, reason);
+                if report {
+                    let _ = tx.send(AppEvent::StreamStatus(kind, StreamState::Lost(reason)));
+                }
+                return;
+            }
+            Err(StreamFailure::Failed(reason)) => {
+                warn!("{:?} stream failed: {}", kind, reason);
+            }
+        }
+
+        let retry_in = backoff.next_delay(jitter());
+        let state = StreamState::Reconnecting {
+            attempt: backoff.attempt(),
+            retry_in,
+        };
+        if report && tx.send(AppEvent::StreamStatus(kind, state)).is_err() {
+            return;
+        }
+        tokio::time::sleep(retry_in).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::body::{Body, Bytes};
+    use axum::http::StatusCode;
+    use axum::response::{IntoResponse, Response};
+    use axum::routing::get;
+    use serde::Deserialize;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::mpsc;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Line {
+        n: u32,
+    }
+
+    // A local stand-in for Lichess, on a free port.
+    async fn serve(router: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{}", address)
+    }
+
+    fn token() -> TokenInfo {
+        TokenInfo {
+            access_token: "test".to_string(),
+            token_type: "Bearer".to_string(),
+            expires_in: None,
+            scope: None,
+        }
+    }
+
+    // `{"n":n}` (or just a ping), then the connection is cut mid-body. The
+    // cut waits a moment so the first line gets out.
+    fn dropped_after(n: Option<u32>) -> Response {
+        let line = match n {
+            Some(n) => format!("{{\"n\":{}}}\n\n", n),
+            None => "\n".to_string(),
+        };
+        let line = Ok(Bytes::from(line));
+        let cut = async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Err(std::io::Error::other("dropped on purpose"))
+        };
+        let chunks = futures::stream::iter([line]).chain(futures::stream::once(cut));
+        Body::from_stream(chunks).into_response()
+    }
+
+    async fn read_all(stream: &mut EventStream<Line>) -> Vec<u32> {
+        let mut seen = Vec::new();
+        while let Some(line) = stream.next().await {
+            seen.push(line.n);
+        }
+        seen
+    }
+
+    #[tokio::test]
+    async fn stream_ends_when_the_connection_drops() {
+        let base = serve(Router::new().route("/", get(async || dropped_after(Some(7))))).await;
+        let mut stream = EventStream::<Line>::open(base, &token()).await.unwrap();
+        assert_eq!(read_all(&mut stream).await, [7]);
+    }
+
+    #[tokio::test]
+    async fn stream_ends_when_it_falls_silent() {
+        let quiet = async || {
+            let first =
+                futures::stream::iter([Ok::<_, std::io::Error>(Bytes::from("{\"n\":1}\n"))]);
+            Body::from_stream(first.chain(futures::stream::pending()))
+        };
+        let base = serve(Router::new().route("/", get(quiet))).await;
+        let mut stream = EventStream::<Line>::open(base, &token())
+            .await
+            .unwrap()
+            .idle_timeout(Duration::from_millis(200));
+        assert_eq!(read_all(&mut stream).await, [1]);
+    }
+
+    #[tokio::test]
+    async fn supervisor_backs_off_and_reconnects() {
+        // Two failed opens, a stream dropped before its first event, one
+        // dropped after an event, then one that finishes.
+        let hits = Arc::new(AtomicU32::new(0));
+        let counter = hits.clone();
+        let flaky = async move || match counter.fetch_add(1, Ordering::SeqCst) + 1 {
+            n @ 1..=2 => (StatusCode::SERVICE_UNAVAILABLE, format!("busy {}", n)).into_response(),
+            3 => dropped_after(None),
+            n => dropped_after(Some(n)),
+        };
+        let base = serve(Router::new().route("/", get(flaky))).await;
+
+        let (tx, rx) = mpsc::channel();
+        let backoff = Backoff::new(Duration::from_millis(2), Duration::from_millis(8));
+        supervise(StreamKind::Game, tx, backoff, || {
+            let base = base.clone();
+            async move {
+                let mut stream = EventStream::<Line>::open(base, &token()).await?;
+                Ok(match read_all(&mut stream).await.as_slice() {
+                    [5] => StreamEnd::Finished,
+                    _ => stream.dropped(),
+                })
+            }
+        })
+        .await;
+
+        assert_eq!(hits.load(Ordering::SeqCst), 5);
+        let attempts: Vec<u32> = rx
+            .try_iter()
+            .map(|event| match event {
+                AppEvent::StreamStatus(
+                    StreamKind::Game,
+                    StreamState::Reconnecting { attempt, .. },
+                ) => attempt,
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        // A connection dropped before any event keeps backing off; one that
+        // delivered an event starts the backoff over.
+        assert_eq!(attempts, [1, 2, 3, 1]);
+    }
+
+    #[tokio::test]
+    async fn supervisor_gives_up_when_refused() {
+        let unauthorized = async || (StatusCode::UNAUTHORIZED, "{\"error\":\"No such token\"}");
+        let base = serve(Router::new().route("/", get(unauthorized))).await;
+
+        let refused = async |kind| {
+            let (tx, rx) = mpsc::channel();
+            supervise(kind, tx, Backoff::default(), || {
+                let base = base.clone();
+                async move {
+                    let stream = EventStream::<Line>::open(base, &token()).await?;
+                    Ok(stream.dropped())
+                }
+            })
+            .await;
+            rx.try_iter().collect::<Vec<_>>()
+        };
+
+        assert!(matches!(
+            refused(StreamKind::Game).await.as_slice(),
+            [AppEvent::StreamStatus(StreamKind::Game, StreamState::Lost(reason))]
+                if reason == "No such token"
+        ));
+        // Nothing shows the account stream's state.
+        assert!(refused(StreamKind::Account).await.is_empty());
+    }
+}
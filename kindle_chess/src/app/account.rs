@@ -0,0 +1,95 @@
+use crate::models::board_api::{ChallengeEvent, GameStartEvent, PlayedBy, TimeControl};
+
+impl GameStartEvent {
+    /// A game nobody has moved in yet that this app can play. The event
+    /// stream also announces every game already under way when it connects;
+    /// those are left to the ongoing-games list.
+    pub fn is_fresh(&self) -> bool {
+        self.last_move.is_empty() && self.compat.as_ref().is_none_or(|c| c.board)
+    }
+}
+
+impl ChallengeEvent {
+    /// Sent by `user_id` rather than to them.
+    pub fn is_from(&self, user_id: &str) -> bool {
+        matches!(&self.challenger, PlayedBy::User(p) if p.id == user_id)
+    }
+
+    /// One line for the challenge screen, e.g. "Rated blitz 3+2, Standard".
+    pub fn summary(&self) -> String {
+        format!(
+            "{} {} {}, {}",
+            if self.rated { "Rated" } else { "Casual" },
+            self.speed,
+            time_control_label(&self.time_control),
+            self.variant.name
+        )
+    }
+
+    /// The colour we'd play by accepting: the other one from what the
+    /// challenger asked for.
+    pub fn our_color(&self) -> &'static str {
+        match self.color.as_str() {
+            "white" => "black",
+            "black" => "white",
+            _ => "random",
+        }
+    }
+}
+
+fn time_control_label(tc: &TimeControl) -> String {
+    if let Some(show) = &tc.show {
+        return show.clone();
+    }
+    match (tc.limit, tc.increment, tc.days_per_turn) {
+        (Some(limit), Some(increment), _) => format!("{}+{}", limit / 60, increment),
+        (_, _, Some(1)) => "1 day".to_string(),
+        (_, _, Some(days)) => format!("{} days", days),
+        _ => "unlimited".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::board_api::StreamEvent;
+
+    #[test]
+    fn challenges_parse_and_describe() {
+        let line = r#"{"type":"challenge","challenge":{"id":"H9fIRZUk","url":"https://lichess.org/H9fIRZUk",
+            "status":"created","challenger":{"id":"bobby","name":"Bobby","rating":1635,"title":"FM"},
+            "destUser":{"id":"kindle","name":"Kindle","rating":1500},
+            "variant":{"key":"standard","name":"Standard","short":"Std"},"rated":true,"speed":"blitz",
+            "timeControl":{"type":"clock","limit":180,"increment":2,"show":"3+2"},
+            "color":"white","finalColor":"white","perf":{"icon":"","name":"Blitz"}},
+            "compat":{"bot":false,"board":true}}"#;
+        let StreamEvent::Challenge { challenge } = serde_json::from_str(line).unwrap() else {
+            panic!("expected a challenge");
+        };
+        assert!(challenge.is_from("bobby"));
+        assert!(!challenge.is_from("kindle"));
+        assert_eq!(challenge.summary(), "Rated blitz 3+2, Standard");
+        assert_eq!(challenge.our_color(), "black");
+    }
+
+    #[test]
+    fn only_unplayed_games_are_fresh() {
+        let start = |last_move: &str| {
+            format!(
+                r#"{{"type":"gameStart","game":{{"fullId":"abcdefgh1234","gameId":"abcdefgh",
+                "fen":"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1","color":"white",
+                "lastMove":"{}","source":"friend","status":{{"id":20,"name":"started"}},
+                "variant":{{"key":"standard","name":"Standard"}},"speed":"correspondence",
+                "perf":"correspondence","rated":false,"hasMoved":false,
+                "opponent":{{"id":"bobby","username":"Bobby","rating":1635}},"isMyTurn":true,
+                "compat":{{"bot":false,"board":true}}}}}}"#,
+                last_move
+            )
+        };
+        let fresh = |json: String| match serde_json::from_str(&json).unwrap() {
+            StreamEvent::GameStart { game } => game.is_fresh(),
+            _ => panic!("expected a game start"),
+        };
+        assert!(fresh(start("")));
+        assert!(!fresh(start("e2e4")));
+    }
+}
@@ -1,13 +1,16 @@
 use std::thread;
 use std::time::{Duration, Instant};
 
-use log::{error, info};
+use log::{error, info, warn};
 use x11rb::{connection::Connection, protocol::Event as X11Event};
 
 use crate::{
+    api::stream::supervise,
     models::{
         app::App,
-        ui::{Display, HomeScreen, Screen, Transition},
+        chess::ChessApp,
+        stream::{Backoff, StreamKind},
+        ui::{ChallengeScreen, ChessGameScreen, Display, HomeScreen, Screen, Transition},
     },
     ui::events::{AppEvent, TouchEvent, TouchKind},
 };
@@ -23,6 +26,8 @@ impl App {
         Ok(Self {
             display,
             screen_stack: vec![home],
+            account: None,
+            held: Vec::new(),
         })
     }
 
@@ -97,19 +102,23 @@ impl App {
                 }
             }
 
-            // Delegate to the screen on top of the stack
-            let transition = match self.screen_stack.last_mut() {
-                Some(screen) => match screen.handle_event(event, &mut self.display) {
-                    Ok(t) => t,
-                    Err(e) => {
-                        error!("Screen event error: {}", e);
-                        Transition::Stay
+            // Account events the app acts on itself, whatever is on top;
+            // everything else is delegated to the screen on top of the stack
+            let transition = match self.account_transition(&event) {
+                Some(t) => t,
+                None => match self.screen_stack.last_mut() {
+                    Some(screen) => match screen.handle_event(event, &mut self.display) {
+                        Ok(t) => t,
+                        Err(e) => {
+                            error!("Screen event error: {}", e);
+                            Transition::Stay
+                        }
+                    },
+                    None => {
+                        // Empty stack — nothing left to show
+                        break;
                     }
                 },
-                None => {
-                    // Empty stack — nothing left to show
-                    break;
-                }
             };
 
             match transition {
@@ -132,6 +141,16 @@ impl App {
                     }
                 }
 
+                Transition::Replace(new_screen) => {
+                    self.screen_stack.pop();
+                    self.screen_stack.push(new_screen);
+                    if let Some(screen) = self.screen_stack.last_mut() {
+                        if let Err(e) = screen.render(&mut self.display) {
+                            error!("Render error after replace: {}", e);
+                        }
+                    }
+                }
+
                 Transition::Pop => {
                     self.screen_stack.pop();
                     if self.screen_stack.is_empty() {
@@ -139,10 +158,14 @@ impl App {
                         break;
                     }
                     if let Some(screen) = self.screen_stack.last_mut() {
+                        // It was drawn over: let it repaint in full, not
+                        // just what it thinks changed.
+                        let _ = screen.handle_event(AppEvent::Expose, &mut self.display);
                         if let Err(e) = screen.render(&mut self.display) {
                             error!("Render error after pop: {}", e);
                         }
                     }
+                    self.release_held();
                 }
 
                 Transition::Quit => {
@@ -155,6 +178,79 @@ impl App {
         info!("App shutting down");
     }
 
+    /// Account-level routing, ahead of the top screen. Signing in starts the
+    /// account event stream (the event still goes on to the screen). A
+    /// challenge to us pushes its screen, and so does a new game, unless a
+    /// screen already claims it: its own game screen, or the challenge that
+    /// is starting it, which gets the event instead. During a game neither
+    /// is pushed: the game screen notes it and it's held until the game is
+    /// left, unless it's cancelled or over by then.
+    fn account_transition(&mut self, event: &AppEvent) -> Option<Transition> {
+        match event {
+            AppEvent::ChessReady(app) => {
+                if self.account.is_none() {
+                    self.account = Some(app.clone());
+                    kick_account_stream(app, self.display.event_tx.clone());
+                }
+                None
+            }
+            AppEvent::ChallengeReceived(challenge) => {
+                match arrival(&self.screen_stack, |s| s.claims_game(&challenge.id)) {
+                    Arrival::Claimed => return Some(Transition::Stay),
+                    Arrival::Held => {
+                        info!("Challenge {} received during a game — held", challenge.id);
+                        self.held.push(event.clone());
+                        return None;
+                    }
+                    Arrival::Open => {}
+                }
+                let account = self.account.clone()?;
+                info!("Challenge {} received", challenge.id);
+                Some(Transition::Push(Box::new(ChallengeScreen::new(
+                    account,
+                    challenge.clone(),
+                ))))
+            }
+            AppEvent::GameStarted(game) => {
+                match arrival(&self.screen_stack, |s| s.claims_start(game)) {
+                    Arrival::Claimed => return None,
+                    Arrival::Held => {
+                        info!("Game {} started during another — held", game.game_id);
+                        self.held.push(event.clone());
+                        return None;
+                    }
+                    Arrival::Open => {}
+                }
+                let account = self.account.clone()?;
+                info!("Game {} started — opening it", game.game_id);
+                let app = account.attach_game(game.game_id.clone(), game.is_my_turn);
+                Some(Transition::Push(Box::new(ChessGameScreen::new(app))))
+            }
+            AppEvent::ChallengeCanceled(id) => {
+                self.held
+                    .retain(|e| !matches!(e, AppEvent::ChallengeReceived(c) if &c.id == id));
+                None
+            }
+            AppEvent::GameFinished(id) => {
+                self.held
+                    .retain(|e| !matches!(e, AppEvent::GameStarted(g) if &g.game_id == id));
+                None
+            }
+            _ => None,
+        }
+    }
+
+    // Once no game is left on the stack, send what was held back round the
+    // loop. A second new game is held again behind the first.
+    fn release_held(&mut self) {
+        if self.screen_stack.iter().any(|s| s.plays_game()) {
+            return;
+        }
+        for event in self.held.drain(..) {
+            let _ = self.display.event_tx.send(event);
+        }
+    }
+
     /// Returns true if the given touch completes a triple-tap gesture.
     /// Resets the counter whenever taps drift more than 50 px apart or the
     /// 500 ms window expires.
@@ -241,3 +337,86 @@ impl App {
         });
     }
 }
+
+// The account's event stream, for the rest of the session, reconnected
+// whenever it drops.
+fn kick_account_stream(app: &ChessApp, tx: std::sync::mpsc::Sender<AppEvent>) {
+    let Some(api) = app.online_idle_api() else {
+        warn!("ChessReady without an online account — no event stream");
+        return;
+    };
+    let events = tx.clone();
+    let connect = move || {
+        let api = api.clone();
+        let tx = events.clone();
+        async move { api.stream_event(tx).await }
+    };
+    tokio::spawn(async move {
+        supervise(StreamKind::Account, tx, Backoff::default(), connect).await;
+        info!("Account event stream ended");
+    });
+}
+
+// Where a game or challenge announced on the account stream goes.
+#[derive(Debug, PartialEq, Eq)]
+enum Arrival {
+    // A screen on the stack is waiting for it.
+    Claimed,
+    // It gets a screen of its own, on top.
+    Open,
+    // A game is in progress: kept until it's left.
+    Held,
+}
+
+fn arrival(stack: &[Box<dyn Screen>], claims: impl Fn(&dyn Screen) -> bool) -> Arrival {
+    if stack.iter().any(|s| claims(s.as_ref())) {
+        Arrival::Claimed
+    } else if stack.iter().any(|s| s.plays_game()) {
+        Arrival::Held
+    } else {
+        Arrival::Open
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Just enough of a screen to route to: a menu, or the game `game`.
+    struct Stub {
+        game: Option<&'static str>,
+    }
+
+    impl Screen for Stub {
+        fn render(&mut self, _display: &mut Display) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+
+        fn handle_event(
+            &mut self,
+            _event: AppEvent,
+            _display: &mut Display,
+        ) -> Result<Transition, Box<dyn std::error::Error>> {
+            Ok(Transition::Stay)
+        }
+
+        fn claims_game(&self, game_id: &str) -> bool {
+            self.game == Some(game_id)
+        }
+
+        fn plays_game(&self) -> bool {
+            self.game.is_some()
+        }
+    }
+
+    #[test]
+    fn nothing_is_pushed_over_a_game() {
+        let route = |stack: &[Box<dyn Screen>], id: &str| arrival(stack, |s| s.claims_game(id));
+        let mut stack: Vec<Box<dyn Screen>> = vec![Box::new(Stub { game: None })];
+        assert_eq!(route(&stack, "B"), Arrival::Open);
+
+        stack.push(Box::new(Stub { game: Some("A") }));
+        assert_eq!(route(&stack, "A"), Arrival::Claimed);
+        assert_eq!(route(&stack, "B"), Arrival::Held);
+    }
+}
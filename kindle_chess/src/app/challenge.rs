@@ -0,0 +1,372 @@
+use crate::models::bitboard::Variant;
+use crate::models::board_api::GameStartEvent;
+use crate::models::challenge::{
+    ChallengeColor, ChallengeRequest, ChallengeTarget, ChallengeTime, CreatedChallenge, Seek,
+};
+
+/// Time controls offered on the new-challenge screen. No bullet: the
+/// board API doesn't play it.
+pub const TIME_PRESETS: [ChallengeTime; 11] = [
+    ChallengeTime::RealTime {
+        limit: 180,
+        increment: 2,
+    },
+    ChallengeTime::RealTime {
+        limit: 300,
+        increment: 3,
+    },
+    ChallengeTime::RealTime {
+        limit: 600,
+        increment: 0,
+    },
+    ChallengeTime::RealTime {
+        limit: 600,
+        increment: 5,
+    },
+    ChallengeTime::RealTime {
+        limit: 900,
+        increment: 10,
+    },
+    ChallengeTime::RealTime {
+        limit: 1800,
+        increment: 0,
+    },
+    ChallengeTime::Correspondence { days: 1 },
+    ChallengeTime::Correspondence { days: 3 },
+    ChallengeTime::Correspondence { days: 7 },
+    ChallengeTime::Correspondence { days: 14 },
+    ChallengeTime::Unlimited,
+];
+
+/// Variants offered; From Position would need a FEN to start from.
+pub const VARIANTS: [Variant; 9] = [
+    Variant::Standard,
+    Variant::Chess960,
+    Variant::Crazyhouse,
+    Variant::ThreeCheck,
+    Variant::KingOfTheHill,
+    Variant::Atomic,
+    Variant::Antichess,
+    Variant::Horde,
+    Variant::RacingKings,
+];
+
+pub const MAX_AI_LEVEL: u8 = 8;
+
+/// Minutes and increment offered for a seek. The board API only seeks
+/// rapid and slower.
+pub const SEEK_PRESETS: [(u32, u32); 6] = [(10, 0), (10, 5), (15, 10), (30, 0), (30, 20), (60, 30)];
+
+impl Default for ChallengeRequest {
+    fn default() -> Self {
+        Self {
+            variant: Variant::Standard,
+            time: TIME_PRESETS[3],
+            color: ChallengeColor::Random,
+            rated: false,
+        }
+    }
+}
+
+impl ChallengeRequest {
+    /// Path under the API base that `target` is challenged at.
+    pub fn path(target: &ChallengeTarget) -> String {
+        match target {
+            ChallengeTarget::User(name) => format!("/challenge/{}", name),
+            ChallengeTarget::Open => "/challenge/open".to_string(),
+            ChallengeTarget::Ai(_) => "/challenge/ai".to_string(),
+        }
+    }
+
+    /// The form fields for challenging `target`. Open challenges can't pick
+    /// a colour (the link decides) and the AI can't play rated.
+    pub fn form(&self, target: &ChallengeTarget) -> Vec<(&'static str, String)> {
+        let mut form = vec![("variant", self.variant.key().to_string())];
+        match self.time {
+            ChallengeTime::RealTime { limit, increment } => {
+                form.push(("clock.limit", limit.to_string()));
+                form.push(("clock.increment", increment.to_string()));
+            }
+            ChallengeTime::Correspondence { days } => form.push(("days", days.to_string())),
+            ChallengeTime::Unlimited => {}
+        }
+        match target {
+            ChallengeTarget::Ai(level) => form.push(("level", level.to_string())),
+            _ => form.push(("rated", self.rated.to_string())),
+        }
+        if *target != ChallengeTarget::Open {
+            form.push(("color", self.color.as_str().to_string()));
+        }
+        form
+    }
+
+    pub fn next_time(&mut self) {
+        let at = TIME_PRESETS.iter().position(|&t| t == self.time);
+        self.time = TIME_PRESETS[at.map_or(0, |i| (i + 1) % TIME_PRESETS.len())];
+    }
+
+    pub fn next_variant(&mut self) {
+        let at = VARIANTS.iter().position(|&v| v == self.variant);
+        self.variant = VARIANTS[at.map_or(0, |i| (i + 1) % VARIANTS.len())];
+    }
+}
+
+impl Default for Seek {
+    fn default() -> Self {
+        Self {
+            minutes: SEEK_PRESETS[0].0,
+            increment: SEEK_PRESETS[0].1,
+            rated: false,
+        }
+    }
+}
+
+impl Seek {
+    /// The form fields for `POST /board/seek`.
+    pub fn form(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("time", self.minutes.to_string()),
+            ("increment", self.increment.to_string()),
+            ("rated", self.rated.to_string()),
+            ("variant", "standard".to_string()),
+        ]
+    }
+
+    pub fn next_time(&mut self) {
+        let at = SEEK_PRESETS
+            .iter()
+            .position(|&p| p == (self.minutes, self.increment));
+        let (minutes, increment) = SEEK_PRESETS[at.map_or(0, |i| (i + 1) % SEEK_PRESETS.len())];
+        self.minutes = minutes;
+        self.increment = increment;
+    }
+
+    /// Lichess's speed category for this clock, from the estimated game
+    /// length: the initial time plus 40 increments.
+    pub fn speed(&self) -> &'static str {
+        match self.minutes * 60 + self.increment * 40 {
+            0..30 => "ultraBullet",
+            30..180 => "bullet",
+            180..480 => "blitz",
+            480..1500 => "rapid",
+            1500..21600 => "classical",
+            _ => "correspondence",
+        }
+    }
+
+    /// Whether a starting game looks like this seek's pairing: a standard
+    /// lobby game at the seek's speed, rated or not like it. The
+    /// `gameStart` doesn't say which seek it came from, so that's as close
+    /// as it gets; a friend's challenge is never taken for it.
+    pub fn pairs(&self, game: &GameStartEvent) -> bool {
+        matches!(game.source.as_str(), "lobby" | "pool")
+            && game.variant.variant() == Variant::Standard
+            && game.speed == self.speed()
+            && game.rated == self.rated
+    }
+
+    /// "10+5 casual".
+    pub fn label(&self) -> String {
+        format!(
+            "{}+{} {}",
+            self.minutes,
+            self.increment,
+            if self.rated { "rated" } else { "casual" }
+        )
+    }
+}
+
+impl ChallengeTarget {
+    /// Friend, then open, then the AI, and round again.
+    pub fn cycle(&self) -> ChallengeTarget {
+        match self {
+            ChallengeTarget::User(_) => ChallengeTarget::Open,
+            ChallengeTarget::Open => ChallengeTarget::Ai(1),
+            ChallengeTarget::Ai(_) => ChallengeTarget::User(String::new()),
+        }
+    }
+
+    /// The next AI level, back to 1 after the strongest.
+    pub fn next_level(&mut self) {
+        if let ChallengeTarget::Ai(level) = self {
+            *level = *level % MAX_AI_LEVEL + 1;
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChallengeTarget::User(_) => "a friend",
+            ChallengeTarget::Open => "open challenge",
+            ChallengeTarget::Ai(_) => "Lichess AI",
+        }
+    }
+}
+
+impl ChallengeTime {
+    pub fn label(self) -> String {
+        match self {
+            ChallengeTime::RealTime { limit, increment } => {
+                format!("{}+{}", limit / 60, increment)
+            }
+            ChallengeTime::Correspondence { days: 1 } => "1 day per move".to_string(),
+            ChallengeTime::Correspondence { days } => format!("{} days per move", days),
+            ChallengeTime::Unlimited => "unlimited".to_string(),
+        }
+    }
+}
+
+impl ChallengeColor {
+    /// The colour's name in the Lichess API.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChallengeColor::Random => "random",
+            ChallengeColor::White => "white",
+            ChallengeColor::Black => "black",
+        }
+    }
+
+    pub fn cycle(self) -> ChallengeColor {
+        match self {
+            ChallengeColor::Random => ChallengeColor::White,
+            ChallengeColor::White => ChallengeColor::Black,
+            ChallengeColor::Black => ChallengeColor::Random,
+        }
+    }
+}
+
+impl CreatedChallenge {
+    /// The link to hand a friend for an open challenge: the one that gives
+    /// them the colour we didn't pick.
+    pub fn link_for(&self, our_color: ChallengeColor) -> Option<&str> {
+        let seated = match our_color {
+            ChallengeColor::White => &self.url_black,
+            ChallengeColor::Black => &self.url_white,
+            ChallengeColor::Random => &None,
+        };
+        seated.as_deref().or(self.url.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field<'a>(form: &'a [(&'static str, String)], key: &str) -> Option<&'a str> {
+        form.iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn game_start(source: &str, speed: &str, rated: bool) -> GameStartEvent {
+        let line = format!(
+            r#"{{"fullId":"abcdefgh1234","gameId":"abcdefgh",
+            "fen":"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1","color":"white",
+            "source":"{}","status":{{"id":20,"name":"started"}},
+            "variant":{{"key":"standard","name":"Standard"}},"speed":"{}","perf":"{}",
+            "rated":{},"hasMoved":false,"opponent":{{"id":"bobby","username":"Bobby"}},
+            "isMyTurn":true}}"#,
+            source, speed, speed, rated
+        );
+        serde_json::from_str(&line).unwrap()
+    }
+
+    #[test]
+    fn seeks_pair_with_lobby_games_like_them() {
+        let seek = Seek::default();
+        assert_eq!(seek.speed(), "rapid");
+        assert!(seek.pairs(&game_start("lobby", "rapid", false)));
+        assert!(!seek.pairs(&game_start("friend", "rapid", false)));
+        assert!(!seek.pairs(&game_start("lobby", "rapid", true)));
+        assert!(!seek.pairs(&game_start("lobby", "blitz", false)));
+
+        let long = Seek {
+            minutes: 30,
+            increment: 20,
+            rated: true,
+        };
+        assert_eq!(long.speed(), "classical");
+        assert!(long.pairs(&game_start("lobby", "classical", true)));
+    }
+    #[test]
+    fn form_fields_per_target() {
+        let request = ChallengeRequest {
+            variant: Variant::Crazyhouse,
+            time: ChallengeTime::RealTime {
+                limit: 300,
+                increment: 3,
+            },
+            color: ChallengeColor::White,
+            rated: true,
+        };
+
+        let user = request.form(&ChallengeTarget::User("bobby".to_string()));
+        assert_eq!(field(&user, "variant"), Some("crazyhouse"));
+        assert_eq!(field(&user, "clock.limit"), Some("300"));
+        assert_eq!(field(&user, "clock.increment"), Some("3"));
+        assert_eq!(field(&user, "rated"), Some("true"));
+        assert_eq!(field(&user, "color"), Some("white"));
+
+        let open = request.form(&ChallengeTarget::Open);
+        assert_eq!(field(&open, "color"), None);
+
+        let ai = request.form(&ChallengeTarget::Ai(5));
+        assert_eq!(field(&ai, "level"), Some("5"));
+        assert_eq!(field(&ai, "rated"), None);
+
+        let correspondence = ChallengeRequest {
+            time: ChallengeTime::Correspondence { days: 3 },
+            ..request
+        };
+        let form = correspondence.form(&ChallengeTarget::Open);
+        assert_eq!(field(&form, "days"), Some("3"));
+        assert_eq!(field(&form, "clock.limit"), None);
+    }
+
+    #[test]
+    fn presets_cycle_round() {
+        let mut request = ChallengeRequest::default();
+        let start = request.time;
+        for _ in 0..TIME_PRESETS.len() {
+            request.next_time();
+        }
+        assert_eq!(request.time, start);
+        assert_eq!(ChallengeTime::Unlimited.label(), "unlimited");
+        assert_eq!(TIME_PRESETS[0].label(), "3+2");
+    }
+
+    #[test]
+    fn seeks_cycle_their_presets() {
+        let mut seek = Seek::default();
+        seek.next_time();
+        assert_eq!((seek.minutes, seek.increment), SEEK_PRESETS[1]);
+        for _ in 1..SEEK_PRESETS.len() {
+            seek.next_time();
+        }
+        assert_eq!(seek, Seek::default());
+
+        seek.rated = true;
+        let form = seek.form();
+        assert_eq!(field(&form, "time"), Some("10"));
+        assert_eq!(field(&form, "increment"), Some("0"));
+        assert_eq!(field(&form, "rated"), Some("true"));
+        assert_eq!(seek.label(), "10+0 rated");
+    }
+
+    #[test]
+    fn open_links_seat_the_friend_opposite_us() {
+        let created: CreatedChallenge = serde_json::from_str(
+            r#"{"id":"abc","url":"https://lichess.org/abc",
+                "urlWhite":"https://lichess.org/abc?color=white",
+                "urlBlack":"https://lichess.org/abc?color=black"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            created.link_for(ChallengeColor::White),
+            Some("https://lichess.org/abc?color=black")
+        );
+        assert_eq!(
+            created.link_for(ChallengeColor::Random),
+            Some("https://lichess.org/abc")
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use crate::models::chat::{ChatLog, ChatMessage, ChatRoom};
+
+/// Replies offered in the chat overlay, since there's no keyboard to type
+/// with.
+pub const QUICK_REPLIES: [&str; 8] = [
+    "Hello!",
+    "Good luck!",
+    "Have fun!",
+    "You too!",
+    "Thanks!",
+    "Good game!",
+    "Well played!",
+    "Sorry, I have to go",
+];
+
+// Older messages are dropped past this, per room.
+const MAX_MESSAGES: usize = 100;
+
+impl ChatRoom {
+    /// The room's name in the Lichess API.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChatRoom::Player => "player",
+            ChatRoom::Spectator => "spectator",
+        }
+    }
+
+    pub fn parse(room: &str) -> Option<ChatRoom> {
+        match room {
+            "player" => Some(ChatRoom::Player),
+            "spectator" => Some(ChatRoom::Spectator),
+            _ => None,
+        }
+    }
+}
+
+impl ChatLog {
+    pub fn push(&mut self, message: ChatMessage) {
+        let room = self.room_mut(message.room);
+        room.push(message);
+        if room.len() > MAX_MESSAGES {
+            room.remove(0);
+        }
+    }
+
+    /// Replace a room's messages with its history, as fetched when the game
+    /// is opened. Anything that arrived meanwhile is kept after it.
+    pub fn load(&mut self, room: ChatRoom, history: Vec<ChatMessage>) {
+        let arrived = std::mem::take(self.room_mut(room));
+        for message in history.into_iter().chain(arrived) {
+            self.push(message);
+        }
+    }
+
+    pub fn room(&self, room: ChatRoom) -> &[ChatMessage] {
+        match room {
+            ChatRoom::Player => &self.player,
+            ChatRoom::Spectator => &self.spectator,
+        }
+    }
+
+    fn room_mut(&mut self, room: ChatRoom) -> &mut Vec<ChatMessage> {
+        match room {
+            ChatRoom::Player => &mut self.player,
+            ChatRoom::Spectator => &mut self.spectator,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(room: ChatRoom, text: &str) -> ChatMessage {
+        ChatMessage {
+            room,
+            username: "someone".to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn rooms_are_kept_apart_and_capped() {
+        let mut log = ChatLog::default();
+        log.push(message(ChatRoom::Player, "hi"));
+        log.push(message(ChatRoom::Spectator, "nice"));
+        assert_eq!(
+            log.room(ChatRoom::Player),
+            [message(ChatRoom::Player, "hi")]
+        );
+        assert_eq!(log.room(ChatRoom::Spectator).len(), 1);
+
+        for i in 0..MAX_MESSAGES {
+            log.push(message(ChatRoom::Player, &i.to_string()));
+        }
+        let player = log.room(ChatRoom::Player);
+        assert_eq!(player.len(), MAX_MESSAGES);
+        assert_eq!(player[0].text, "0");
+    }
+
+    #[test]
+    fn history_goes_before_live_messages() {
+        let mut log = ChatLog::default();
+        log.push(message(ChatRoom::Player, "new"));
+        log.load(
+            ChatRoom::Player,
+            vec![
+                message(ChatRoom::Player, "old"),
+                message(ChatRoom::Player, "then"),
+            ],
+        );
+        let texts: Vec<_> = log
+            .room(ChatRoom::Player)
+            .iter()
+            .map(|m| m.text.as_str())
+            .collect();
+        assert_eq!(texts, ["old", "then", "new"]);
+    }
+
+    #[test]
+    fn room_names() {
+        for room in [ChatRoom::Player, ChatRoom::Spectator] {
+            assert_eq!(ChatRoom::parse(room.as_str()), Some(room));
+        }
+        assert_eq!(ChatRoom::parse("lobby"), None);
+    }
+}
@@ -2,6 +2,7 @@ use crate::models::{
     board_api::{BoardAPI, Idle, InGame, PlayedBy, Turn},
     board_local::BoardLocal,
     chess::{ChessApp, ChessBackend},
+    engine::Strength,
     oauth::{LichessUser, TokenInfo},
 };
 use log::warn;
@@ -15,8 +16,24 @@ impl ChessApp {
         }
     }
 
-    pub fn new_offline() -> ChessApp {
-        let board_local = BoardLocal::new(String::new());
+    /// A game against the built-in engine at `strength`, player0 as white.
+    /// Autosaved under `ROOT_DIR/saves/`.
+    pub fn new_offline(strength: Strength) -> ChessApp {
+        let mut board_local = BoardLocal::vs_engine(true, strength);
+        board_local.save_path = Some(BoardLocal::new_save_path());
+        Self::resume_offline(board_local)
+    }
+
+    /// A two-player game on this device, no engine and no account.
+    /// Autosaved like `new_offline`.
+    pub fn new_pass_and_play(auto_flip: bool) -> ChessApp {
+        let mut board_local = BoardLocal::pass_and_play(auto_flip);
+        board_local.save_path = Some(BoardLocal::new_save_path());
+        Self::resume_offline(board_local)
+    }
+
+    /// Continue an offline game, e.g. one loaded from its savegame.
+    pub fn resume_offline(board_local: BoardLocal) -> ChessApp {
         Self {
             backend: ChessBackend::Offline(board_local),
         }
@@ -57,9 +74,32 @@ impl ChessApp {
         }
     }
 
+    /// The Lichess game this backend is scoped to, if any.
+    pub fn game_id(&self) -> Option<&str> {
+        match &self.backend {
+            ChessBackend::OnlineInGame(api) => Some(api.game_id()),
+            _ => None,
+        }
+    }
+
+    pub fn offline(&self) -> Option<&BoardLocal> {
+        match &self.backend {
+            ChessBackend::Offline(local) => Some(local),
+            _ => None,
+        }
+    }
+
+    pub fn offline_mut(&mut self) -> Option<&mut BoardLocal> {
+        match &mut self.backend {
+            ChessBackend::Offline(local) => Some(local),
+            _ => None,
+        }
+    }
+
     pub fn turn(&self) -> Option<&Turn> {
         match &self.backend {
             ChessBackend::OnlineInGame(api) => Some(&api.state.turn),
+            ChessBackend::Offline(local) => Some(&local.turn),
             _ => None,
         }
     }
@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use crate::models::bitboard::Color;
+use crate::models::clock::GameClock;
+
+impl GameClock {
+    /// Clocks from a server update. `initial` is the game's starting time,
+    /// which sets the low-time threshold: a tenth of it, kept between 10 s
+    /// and a minute.
+    pub fn new(
+        white: Duration,
+        black: Duration,
+        running: Option<Color>,
+        initial: Duration,
+    ) -> Self {
+        GameClock {
+            white,
+            black,
+            running,
+            low_time: (initial / 10).clamp(Duration::from_secs(10), Duration::from_secs(60)),
+        }
+    }
+
+    pub fn remaining(&self, color: Color) -> Duration {
+        match color {
+            Color::White => self.white,
+            Color::Black => self.black,
+        }
+    }
+
+    pub fn is_low(&self, color: Color) -> bool {
+        self.remaining(color) < self.low_time
+    }
+
+    /// Count `elapsed` off the running side. Stops at zero; the server
+    /// decides when a flag falls.
+    pub fn tick(&mut self, elapsed: Duration) {
+        match self.running {
+            Some(Color::White) => self.white = self.white.saturating_sub(elapsed),
+            Some(Color::Black) => self.black = self.black.saturating_sub(elapsed),
+            None => {}
+        }
+    }
+}
+
+/// Whole seconds, "m:ss", or "h:mm:ss" from an hour up. Rounded down, like
+/// a chess clock.
+pub fn format_clock(time: Duration) -> String {
+    let secs = time.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks_only_the_running_side() {
+        let mut clock = GameClock::new(
+            Duration::from_secs(180),
+            Duration::from_secs(175),
+            Some(Color::White),
+            Duration::from_secs(180),
+        );
+        clock.tick(Duration::from_millis(1500));
+        assert_eq!(clock.white, Duration::from_millis(178_500));
+        assert_eq!(clock.black, Duration::from_secs(175));
+
+        clock.running = Some(Color::Black);
+        clock.tick(Duration::from_secs(500));
+        assert_eq!(clock.black, Duration::ZERO);
+
+        clock.running = None;
+        clock.tick(Duration::from_secs(1));
+        assert_eq!(clock.white, Duration::from_millis(178_500));
+    }
+
+    #[test]
+    fn low_time_scales_with_the_time_control() {
+        let secs = Duration::from_secs;
+        // 3+0: a tenth is 18 s.
+        let clock = GameClock::new(secs(17), secs(19), None, secs(180));
+        assert!(clock.is_low(Color::White));
+        assert!(!clock.is_low(Color::Black));
+        // Bullet never goes under 10 s, classical never over a minute.
+        assert_eq!(
+            GameClock::new(secs(60), secs(60), None, secs(60)).low_time,
+            secs(10)
+        );
+        assert_eq!(
+            GameClock::new(secs(60), secs(60), None, secs(1800)).low_time,
+            secs(60)
+        );
+    }
+
+    #[test]
+    fn formats_whole_seconds() {
+        assert_eq!(format_clock(Duration::from_millis(272_900)), "4:32");
+        assert_eq!(format_clock(Duration::from_millis(999)), "0:00");
+        assert_eq!(format_clock(Duration::from_secs(3_725)), "1:02:05");
+    }
+}
@@ -1,5 +1,8 @@
 use std::io;
 
+use crate::models::bitboard::{Bitboards, Move};
+use crate::models::board_api::{GameStateEvent, Offer, PendingMove, Reconciled, Turn};
+
 pub fn player0_turn(moves: String, player0_white: bool) -> bool {
     // println!("Move-Count: {}", moves.split_whitespace().count());
     let white_turn = moves.split_whitespace().count() % 2 == 0;
@@ -12,6 +15,23 @@ pub fn player0_turn(moves: String, player0_white: bool) -> bool {
     }
 }
 
+/// The offer the opponent has open, if any. Our own offers show up in the
+/// same fields and aren't anything to answer.
+pub fn opponent_offer(state: &GameStateEvent, player0_white: bool) -> Option<Offer> {
+    let (draw, takeback) = if player0_white {
+        (state.bdraw, state.btakeback)
+    } else {
+        (state.wdraw, state.wtakeback)
+    };
+    if draw == Some(true) {
+        Some(Offer::Draw)
+    } else if takeback == Some(true) {
+        Some(Offer::Takeback)
+    } else {
+        None
+    }
+}
+
 pub async fn get_turn_input() -> String {
     let mut buffer = String::new();
     println!("Enter move to play!");
@@ -25,3 +45,96 @@ pub async fn get_turn_input() -> String {
         }
     }
 }
+
+impl PendingMove {
+    /// Play `mv` on a copy of `before`, returning the pending record and the
+    /// position to show until the server answers.
+    pub fn play(before: &Bitboards, mv: Move, turn: Turn) -> (PendingMove, Bitboards) {
+        let mut after = before.clone();
+        after.make_move(mv);
+        let pending = PendingMove {
+            mv,
+            uci: before.move_to_uci(mv),
+            ply: before.ply_count(),
+            before: before.clone(),
+            turn,
+        };
+        (pending, after)
+    }
+
+    /// Compare against a position built from the server's full move list.
+    pub fn reconcile(&self, server: &Bitboards) -> Reconciled {
+        // `before` was built from an earlier server list, so a shorter one
+        // is a takeback rather than a stale echo.
+        let moves = server.moves();
+        let history = self.before.moves();
+        match moves.get(self.ply) {
+            Some(&mv) if mv == self.mv && moves[..self.ply] == history[..] => Reconciled::Confirmed,
+            None if moves == history => Reconciled::Pending,
+            _ => Reconciled::Diverged,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn after(moves: &str) -> Bitboards {
+        let mut board = Bitboards::starting_position();
+        board.apply_uci_moves(moves);
+        board
+    }
+
+    fn pending(before: &str, uci: &str) -> PendingMove {
+        let before = after(before);
+        let mv = before.parse_uci(uci).unwrap();
+        PendingMove::play(&before, mv, Turn::Playing).0
+    }
+
+    #[test]
+    fn only_the_opponents_offers_count() {
+        let state: GameStateEvent = serde_json::from_str(
+            r#"{"moves":"e2e4","wtime":1,"btime":1,"winc":0,"binc":0,
+                "wdraw":true,"btakeback":true,"status":"started"}"#,
+        )
+        .unwrap();
+        assert_eq!(opponent_offer(&state, true), Some(Offer::Takeback));
+        assert_eq!(opponent_offer(&state, false), Some(Offer::Draw));
+
+        let quiet: GameStateEvent = serde_json::from_str(
+            r#"{"moves":"","wtime":1,"btime":1,"winc":0,"binc":0,"status":"started"}"#,
+        )
+        .unwrap();
+        assert_eq!(opponent_offer(&quiet, true), None);
+    }
+
+    #[test]
+    fn play_shows_the_move_and_remembers_the_position() {
+        let before = after("e2e4 e7e5");
+        let mv = before.parse_uci("g1f3").unwrap();
+        let (pending, shown) = PendingMove::play(&before, mv, Turn::Playing);
+        assert_eq!(pending.uci, "g1f3");
+        assert_eq!(pending.ply, 2);
+        assert_eq!(shown.ply_count(), 3);
+        assert_eq!(shown.last_move(), Some(mv));
+        assert_eq!(pending.before.diff(&before), 0);
+    }
+
+    #[test]
+    fn reconcile_against_server_lists() {
+        let p = pending("e2e4 e7e5", "g1f3");
+        // An echo of the position we moved from (a clock or draw-offer
+        // update) leaves the move pending.
+        assert_eq!(p.reconcile(&after("e2e4 e7e5")), Reconciled::Pending);
+        assert_eq!(p.reconcile(&after("e2e4 e7e5 g1f3")), Reconciled::Confirmed);
+        assert_eq!(
+            p.reconcile(&after("e2e4 e7e5 g1f3 b8c6")),
+            Reconciled::Confirmed
+        );
+        assert_eq!(p.reconcile(&after("e2e4 e7e5 f1c4")), Reconciled::Diverged);
+        // A takeback before our move.
+        assert_eq!(p.reconcile(&after("e2e4")), Reconciled::Diverged);
+        assert_eq!(p.reconcile(&after("d2d4 e7e5 g1f3")), Reconciled::Diverged);
+    }
+}
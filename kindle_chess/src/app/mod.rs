@@ -1,3 +1,8 @@
+pub mod account;
 pub mod app;
+pub mod challenge;
+pub mod chat;
 pub mod chess;
+pub mod clock;
 pub mod game;
+pub mod stream;
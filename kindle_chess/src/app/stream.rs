@@ -0,0 +1,132 @@
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::models::stream::{Backoff, StreamFailure, StreamState};
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff::new(Duration::from_secs(1), Duration::from_secs(60))
+    }
+}
+
+impl Backoff {
+    pub fn new(base: Duration, max: Duration) -> Self {
+        Self {
+            base,
+            max,
+            attempt: 0,
+        }
+    }
+
+    /// Reconnects tried since the stream was last up.
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// The stream was up: the next drop starts again from `base`.
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+
+    /// The wait before the next reconnect. `jitter` in `0.0..=1.0` picks
+    /// where in the upper half of the step the delay falls, so clients
+    /// dropped together don't all come back at once.
+    pub fn next_delay(&mut self, jitter: f64) -> Duration {
+        let step = self
+            .base
+            .saturating_mul(1 << self.attempt.min(16))
+            .min(self.max);
+        self.attempt += 1;
+        step / 2 + (step / 2).mul_f64(jitter.clamp(0.0, 1.0))
+    }
+}
+
+/// A number in `0.0..1.0` that differs from call to call. Good enough for
+/// jitter; std's hasher keys are randomly seeded.
+pub fn jitter() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u8(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+impl StreamFailure {
+    /// Sort an HTTP error status: client errors other than rate limiting
+    /// won't go away by asking again.
+    pub fn from_status(status: u16, reason: String) -> Self {
+        if (400..500).contains(&status) && status != 429 {
+            StreamFailure::Refused(reason)
+        } else {
+            StreamFailure::Failed(reason)
+        }
+    }
+}
+
+impl fmt::Display for StreamFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamFailure::Refused(reason) => write!(f, "refused: {}", reason),
+            StreamFailure::Failed(reason) => write!(f, "failed: {}", reason),
+        }
+    }
+}
+
+impl StreamState {
+    /// The sidebar's status line for it.
+    pub fn label(&self) -> String {
+        match self {
+            StreamState::Reconnecting { attempt: 1, .. } => "Reconnecting…".to_string(),
+            StreamState::Reconnecting { attempt, .. } => format!("Reconnecting… ({})", attempt),
+            StreamState::Lost(_) => "Connection lost".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delays_double_up_to_the_cap() {
+        let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(10));
+        let delays: Vec<_> = (0..6).map(|_| backoff.next_delay(1.0)).collect();
+        let secs = |s| Duration::from_secs(s);
+        assert_eq!(
+            delays,
+            [secs(1), secs(2), secs(4), secs(8), secs(10), secs(10)]
+        );
+        assert_eq!(backoff.attempt(), 6);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(0.0), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn jitter_stays_in_the_upper_half() {
+        let mut backoff = Backoff::default();
+        for _ in 0..100 {
+            let j = jitter();
+            assert!((0.0..1.0).contains(&j));
+        }
+        for _ in 0..40 {
+            let delay = backoff.next_delay(jitter());
+            assert!(delay <= backoff.max);
+            assert!(delay >= Duration::from_millis(500));
+        }
+    }
+
+    #[test]
+    fn client_errors_are_not_retried() {
+        let refused = |s| {
+            matches!(
+                StreamFailure::from_status(s, String::new()),
+                StreamFailure::Refused(_)
+            )
+        };
+        assert!(refused(401));
+        assert!(refused(404));
+        assert!(!refused(429));
+        assert!(!refused(503));
+    }
+}
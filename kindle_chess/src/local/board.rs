@@ -1,24 +1,207 @@
-use log::info;
-
-use crate::models::board_local::BoardLocal;
+use crate::local::save::today;
+use crate::models::{
+    bitboard::{Bitboards, Color, Move},
+    board_api::Turn,
+    board_local::{BoardLocal, LocalOpponent},
+    engine::Strength,
+    uci::UciHandle,
+};
 
 impl BoardLocal {
-    pub fn new(game_id: String) -> BoardLocal {
-        println!(
-            "Would start local game {}... If it were implemented",
-            game_id,
-        );
+    /// New game from the standard starting position against the engine at
+    /// `strength`. If player0 is black the engine moves first. An external
+    /// engine installed under `ROOT_DIR/engines/uci` is used when present.
+    pub fn vs_engine(player0_white: bool, strength: Strength) -> BoardLocal {
+        Self::start(
+            player0_white,
+            LocalOpponent::Engine {
+                strength,
+                uci: UciHandle::installed(),
+            },
+            false,
+        )
+    }
+
+    /// New game between two people sharing the device, from the standard
+    /// starting position. With `auto_flip` the board turns to face
+    /// whoever is to move.
+    pub fn pass_and_play(auto_flip: bool) -> BoardLocal {
+        Self::start(true, LocalOpponent::Human, auto_flip)
+    }
 
-        let turn = true; // TODO: get turn from local savegame
-        let white = true; // TODO: get player0 color from local savegame
+    fn start(player0_white: bool, opponent: LocalOpponent, auto_flip: bool) -> BoardLocal {
+        let mut local = Self {
+            board: Bitboards::starting_position(),
+            player0_white,
+            turn: Turn::Waiting,
+            opponent,
+            auto_flip,
+            save_path: None,
+            date: today(),
+        };
+        local.turn = local.compute_turn();
+        local
+    }
 
-        Self {
-            // game_id: game_id,
-            player0_turn: turn,
-            player0_white: white,
+    pub fn engine_to_move(&self) -> bool {
+        matches!(self.opponent, LocalOpponent::Engine { .. }) && matches!(self.turn, Turn::Waiting)
+    }
+
+    /// The colour the person at the Kindle plays; `None` in pass-and-play,
+    /// where both sides are theirs.
+    pub fn player_color(&self) -> Option<Color> {
+        match self.opponent {
+            LocalOpponent::Engine { .. } if self.player0_white => Some(Color::White),
+            LocalOpponent::Engine { .. } => Some(Color::Black),
+            LocalOpponent::Human => None,
         }
     }
-    pub async fn get_ongoing_games() {
-        info!("Not implemented")
+
+    /// Whether the board should be drawn from black's side: player0's
+    /// colour against the engine, the side to move with `auto_flip`.
+    pub fn flipped(&self) -> bool {
+        match self.opponent {
+            LocalOpponent::Engine { .. } => !self.player0_white,
+            LocalOpponent::Human => self.auto_flip && self.board.side_to_move == Color::Black,
+        }
+    }
+
+    /// Sidebar line for a pass-and-play game in progress, which `Turn`
+    /// can't express (both players are "you"). `None` otherwise.
+    pub fn status(&self) -> Option<String> {
+        match (&self.opponent, &self.turn) {
+            (LocalOpponent::Human, Turn::Playing) => {
+                let side = match self.board.side_to_move {
+                    Color::White => "White",
+                    Color::Black => "Black",
+                };
+                let check = if self.board.in_check() {
+                    " (check)"
+                } else {
+                    ""
+                };
+                Some(format!("{} to move{}", side, check))
+            }
+            _ => None,
+        }
+    }
+
+    /// Play a move from the touch screen, given in UCI: player0's against
+    /// the engine, either side's in pass-and-play. Rejected when it isn't
+    /// a person's turn or the move is illegal.
+    pub fn play_user_move(&mut self, uci: &str) -> Result<Move, String> {
+        if !matches!(self.turn, Turn::Playing) {
+            return Err("not your turn".to_string());
+        }
+        let mv = self
+            .board
+            .find_legal(uci)
+            .ok_or_else(|| format!("illegal move '{}'", uci))?;
+        self.play(mv);
+        Ok(mv)
+    }
+
+    /// Play a move for whoever is to move (the engine's reply, normally).
+    /// The move must be legal in the current position.
+    pub fn play(&mut self, mv: Move) {
+        self.board.make_move(mv);
+        self.turn = self.compute_turn();
+    }
+
+    pub fn is_over(&self) -> bool {
+        matches!(self.turn, Turn::Over { .. })
+    }
+
+    /// Names for white and black, as shown when the game ends and written
+    /// to the savegame.
+    pub fn player_names(&self) -> (String, String) {
+        let engine = match &self.opponent {
+            LocalOpponent::Human => return ("White".to_string(), "Black".to_string()),
+            LocalOpponent::Engine { strength, .. } => format!("Kindle level {}", strength.level()),
+        };
+        if self.player0_white {
+            ("You".to_string(), engine)
+        } else {
+            (engine, "You".to_string())
+        }
+    }
+
+    pub(crate) fn compute_turn(&self) -> Turn {
+        if let Some(outcome) = self.board.outcome() {
+            let (white, black) = self.player_names();
+            return Turn::from_outcome(outcome, &white, &black);
+        }
+        if matches!(self.opponent, LocalOpponent::Human) {
+            return Turn::Playing;
+        }
+        let player0 = if self.player0_white {
+            Color::White
+        } else {
+            Color::Black
+        };
+        if self.board.side_to_move == player0 {
+            Turn::Playing
+        } else {
+            Turn::Waiting
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pass_and_play_alternates_and_flips() {
+        let mut local = BoardLocal::pass_and_play(true);
+        assert!(matches!(local.turn, Turn::Playing));
+        assert!(!local.flipped());
+        assert_eq!(local.status().as_deref(), Some("White to move"));
+        assert_eq!(local.player_color(), None);
+        let engine = BoardLocal::vs_engine(false, Strength::MIN);
+        assert_eq!(engine.player_color(), Some(Color::Black));
+
+        local.play_user_move("e2e4").unwrap();
+        assert!(matches!(local.turn, Turn::Playing));
+        assert!(!local.engine_to_move());
+        assert!(local.flipped());
+        assert_eq!(local.status().as_deref(), Some("Black to move"));
+
+        // White can't move twice in a row.
+        assert!(local.play_user_move("d2d4").is_err());
+        local.play_user_move("e7e5").unwrap();
+        assert!(!local.flipped());
+
+        let mut fixed = BoardLocal::pass_and_play(false);
+        fixed.play_user_move("e2e4").unwrap();
+        assert!(!fixed.flipped());
+    }
+
+    #[test]
+    fn pass_and_play_detects_game_end() {
+        let mut local = BoardLocal::pass_and_play(false);
+        for uci in ["f2f3", "e7e5", "g2g4", "d8h4"] {
+            local.play_user_move(uci).unwrap();
+        }
+        match &local.turn {
+            Turn::Over { winner, .. } => assert_eq!(winner.as_deref(), Some("Black")),
+            other => panic!("expected game over, got {:?}", other),
+        }
+        assert!(local.status().is_none());
+        assert!(local.play_user_move("a2a3").is_err());
+    }
+
+    #[test]
+    fn engine_game_waits_for_engine() {
+        let mut local = BoardLocal::vs_engine(true, Strength::default());
+        assert!(!local.engine_to_move());
+        local.play_user_move("e2e4").unwrap();
+        assert!(local.engine_to_move());
+        assert!(local.play_user_move("e7e5").is_err());
+        assert!(local.status().is_none());
+
+        let black = BoardLocal::vs_engine(false, Strength::default());
+        assert!(black.engine_to_move());
+        assert!(black.flipped());
     }
 }
@@ -0,0 +1,675 @@
+// Built-in engine for offline play: iterative-deepening negamax with
+// alpha-beta pruning, a quiescence search over captures so it doesn't stop
+// counting in the middle of an exchange, and a transposition table keyed by
+// the board's Zobrist hash. Moves are ordered TT move first, then captures
+// (most valuable victim, least valuable attacker), promotions, and two
+// killer moves per ply.
+//
+// Evaluation is material plus piece-square tables (Michniewski's "simplified
+// evaluation function"), with the king table blended from middlegame to
+// endgame as pieces come off. It is tuned for standard chess; variant games
+// are searched with the right move rules but standard judgement.
+//
+// Weaker levels search shallower and, at the root, pick at random among the
+// moves scoring within `Strength::tolerance` of the best. To get a real score
+// for each of those moves, the root is then searched with a full window.
+
+use std::cmp::Reverse;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::models::bitboard::movegen::squares;
+use crate::models::bitboard::{Bitboards, Color, Move, MoveKind, Piece};
+use crate::models::engine::{
+    Bound, Engine, MATE_SCORE, MAX_DEPTH, SearchLimits, SearchResult, Strength, TtEntry,
+};
+
+// 2^16 slots of 24 bytes: about 1.5 MB, plenty for the node counts a Kindle
+// gets through in a few seconds.
+const TT_SIZE: usize = 1 << 16;
+const INFINITY: i32 = MATE_SCORE + 1;
+// Scores past this are mates. The TT stores them relative to the node they
+// were found at, not the root, so they stay right when reached another way.
+const MATE_BOUND: i32 = MATE_SCORE - 256;
+const MAX_PLY: usize = 64;
+// Nodes between looks at the clock and node budget.
+const CHECK_EVERY: u64 = 1024;
+
+impl Engine {
+    pub fn new(strength: Strength) -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Self::with_seed(strength, seed)
+    }
+
+    /// Like `new`, with a fixed seed for the weaker levels' random picks.
+    pub fn with_seed(strength: Strength, seed: u64) -> Self {
+        Self {
+            strength,
+            tt: vec![TtEntry::default(); TT_SIZE],
+            killers: vec![[None; 2]; MAX_PLY],
+            nodes: 0,
+            node_limit: None,
+            deadline: None,
+            stopped: false,
+            // xorshift state must never be zero.
+            rng: seed | 1,
+        }
+    }
+
+    /// Pick a move for the side to move at this engine's strength.
+    pub fn search(&mut self, board: &Bitboards) -> SearchResult {
+        self.search_with(board, self.strength.limits())
+    }
+
+    /// Search `board` within `limits`. Works on a copy of the board; the
+    /// copy keeps the move history, so repeating an earlier position of the
+    /// game is scored as a draw.
+    pub fn search_with(&mut self, board: &Bitboards, limits: SearchLimits) -> SearchResult {
+        let start = Instant::now();
+        let deadline = limits.time.map(|t| start + t);
+        self.nodes = 0;
+        self.stopped = false;
+        self.killers.fill([None; 2]);
+
+        let mut bb = board.clone();
+        let mut root: Vec<(Move, i32)> = bb.legal_moves().into_iter().map(|m| (m, 0)).collect();
+        if root.is_empty() {
+            return SearchResult {
+                best_move: None,
+                score: if bb.in_check() { -MATE_SCORE } else { 0 },
+                depth: 0,
+                nodes: 0,
+                elapsed: start.elapsed(),
+                pv: Vec::new(),
+            };
+        }
+
+        let tolerance = self.strength.tolerance();
+        let mut depth_done = 0;
+        for depth in 1..=limits.depth.clamp(1, MAX_DEPTH) {
+            // Depth 1 always runs to completion so there is a move to play.
+            self.node_limit = limits.nodes.filter(|_| depth > 1);
+            self.deadline = deadline.filter(|_| depth > 1);
+            let scored = self.search_root(&mut bb, &root, depth, tolerance > 0);
+            if self.stopped {
+                break;
+            }
+            root = scored;
+            depth_done = depth;
+            if root[0].1.abs() >= MATE_BOUND {
+                break;
+            }
+        }
+
+        // Only the full-window root search gives every move a true score;
+        // otherwise the runners-up are just bounds and may tie the best.
+        let best = root[0].1;
+        let candidates = match tolerance {
+            0 => 1,
+            _ => root
+                .iter()
+                .take_while(|(_, s)| *s >= best - tolerance)
+                .count(),
+        };
+        let (best_move, score) = root[self.next_random() as usize % candidates];
+        let pv = self.principal_variation(&mut bb, best_move, depth_done);
+        SearchResult {
+            best_move: Some(best_move),
+            score,
+            depth: depth_done,
+            nodes: self.nodes,
+            elapsed: start.elapsed(),
+            pv,
+        }
+    }
+
+    // One iteration over the root moves, best first from the previous
+    // iteration. With `all_scores` every move gets an exact score rather
+    // than just "worse than the best so far". Returns the moves re-sorted.
+    fn search_root(
+        &mut self,
+        bb: &mut Bitboards,
+        root: &[(Move, i32)],
+        depth: u8,
+        all_scores: bool,
+    ) -> Vec<(Move, i32)> {
+        let mut alpha = -INFINITY;
+        let mut scored = Vec::with_capacity(root.len());
+        for &(mv, _) in root {
+            bb.make_move(mv);
+            let score = -self.negamax(bb, depth as i32 - 1, -INFINITY, -alpha, 1);
+            bb.unmake_move();
+            if self.stopped {
+                break;
+            }
+            if !all_scores {
+                alpha = alpha.max(score);
+            }
+            scored.push((mv, score));
+        }
+        // Stable, so equal scores keep the previous iteration's order.
+        scored.sort_by_key(|&(_, score)| Reverse(score));
+        scored
+    }
+
+    fn negamax(
+        &mut self,
+        bb: &mut Bitboards,
+        depth: i32,
+        mut alpha: i32,
+        beta: i32,
+        ply: usize,
+    ) -> i32 {
+        if self.should_stop() {
+            return 0;
+        }
+        if bb.halfmove_clock >= 100 || bb.repetitions() > 0 || bb.has_insufficient_material() {
+            return 0;
+        }
+        if depth <= 0 || ply >= MAX_PLY {
+            return self.quiescence(bb, alpha, beta, ply);
+        }
+        self.nodes += 1;
+
+        let slot = slot(bb.hash);
+        let entry = self.tt[slot];
+        let tt_move = if entry.key == bb.hash {
+            if entry.depth as i32 >= depth {
+                let score = score_from_tt(entry.score, ply);
+                match entry.bound {
+                    Bound::Exact => return score,
+                    Bound::Lower if score >= beta => return score,
+                    Bound::Upper if score <= alpha => return score,
+                    _ => {}
+                }
+            }
+            entry.best
+        } else {
+            None
+        };
+
+        let mut moves = bb.legal_moves();
+        if moves.is_empty() {
+            return if bb.in_check() { mated_in(ply) } else { 0 };
+        }
+        self.order(bb, &mut moves, tt_move, ply);
+
+        let original_alpha = alpha;
+        let mut best = -INFINITY;
+        let mut best_move = None;
+        for mv in moves {
+            let quiet = is_quiet(bb, mv);
+            bb.make_move(mv);
+            // Check extension: don't let the horizon cut a forcing line short.
+            let extension = i32::from(bb.in_check());
+            let score = -self.negamax(bb, depth - 1 + extension, -beta, -alpha, ply + 1);
+            bb.unmake_move();
+            if self.stopped {
+                return 0;
+            }
+            if score > best {
+                best = score;
+                best_move = Some(mv);
+                if score > alpha {
+                    alpha = score;
+                }
+                if alpha >= beta {
+                    if quiet {
+                        self.add_killer(mv, ply);
+                    }
+                    break;
+                }
+            }
+        }
+
+        let bound = if best >= beta {
+            Bound::Lower
+        } else if best > original_alpha {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+        self.tt[slot] = TtEntry {
+            key: bb.hash,
+            best: best_move,
+            score: score_to_tt(best, ply),
+            depth: depth as i8,
+            bound,
+        };
+        best
+    }
+
+    // Captures and promotions only, until the position is quiet. The side to
+    // move may "stand pat" on the static evaluation instead of capturing,
+    // except in check, where every evasion is searched.
+    fn quiescence(&mut self, bb: &mut Bitboards, mut alpha: i32, beta: i32, ply: usize) -> i32 {
+        if self.should_stop() {
+            return 0;
+        }
+        self.nodes += 1;
+
+        let in_check = bb.in_check();
+        let mut moves = bb.legal_moves();
+        if moves.is_empty() {
+            return if in_check { mated_in(ply) } else { 0 };
+        }
+        let mut best = -INFINITY;
+        if !in_check || ply >= MAX_PLY {
+            let stand_pat = evaluate(bb);
+            if stand_pat >= beta || ply >= MAX_PLY {
+                return stand_pat;
+            }
+            alpha = alpha.max(stand_pat);
+            best = stand_pat;
+            moves.retain(|&mv| !is_quiet(bb, mv));
+        }
+        self.order(bb, &mut moves, None, ply);
+
+        for mv in moves {
+            bb.make_move(mv);
+            let score = -self.quiescence(bb, -beta, -alpha, ply + 1);
+            bb.unmake_move();
+            if self.stopped {
+                return 0;
+            }
+            if score > best {
+                best = score;
+                alpha = alpha.max(score);
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    fn order(&self, bb: &Bitboards, moves: &mut [Move], tt_move: Option<Move>, ply: usize) {
+        let killers = self.killers.get(ply).copied().unwrap_or([None; 2]);
+        moves.sort_by_cached_key(|&mv| {
+            let promotion = mv.promotion.map_or(0, value);
+            let key = if Some(mv) == tt_move {
+                1_000_000
+            } else if let Some(victim) = captured_piece(bb, mv) {
+                let attacker = bb.piece_at(mv.from).map_or(0, |(_, p)| value(p));
+                100_000 + 10 * value(victim) - attacker + promotion
+            } else if promotion > 0 {
+                90_000 + promotion
+            } else if killers.contains(&Some(mv)) {
+                80_000
+            } else {
+                0
+            };
+            Reverse(key)
+        });
+    }
+
+    fn add_killer(&mut self, mv: Move, ply: usize) {
+        if let Some(killers) = self.killers.get_mut(ply)
+            && killers[0] != Some(mv)
+        {
+            killers[1] = killers[0];
+            killers[0] = Some(mv);
+        }
+    }
+
+    fn should_stop(&mut self) -> bool {
+        if !self.stopped && self.nodes.is_multiple_of(CHECK_EVERY) {
+            self.stopped = self.node_limit.is_some_and(|limit| self.nodes >= limit)
+                || self
+                    .deadline
+                    .is_some_and(|deadline| Instant::now() >= deadline);
+        }
+        self.stopped
+    }
+
+    // Follow the TT's best moves from `first`, as far as they stay legal.
+    fn principal_variation(&self, bb: &mut Bitboards, first: Move, depth: u8) -> Vec<Move> {
+        let mut pv = Vec::new();
+        let mut next = Some(first);
+        while let Some(mv) = next {
+            if pv.len() >= depth as usize || !bb.is_legal(mv) {
+                break;
+            }
+            bb.make_move(mv);
+            pv.push(mv);
+            let entry = self.tt[slot(bb.hash)];
+            next = entry.best.filter(|_| entry.key == bb.hash);
+        }
+        for _ in &pv {
+            bb.unmake_move();
+        }
+        pv
+    }
+
+    // xorshift64; only used to choose between near-equal moves.
+    fn next_random(&mut self) -> u64 {
+        let mut x = self.rng;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.rng = x;
+        x
+    }
+}
+
+/// Convenience for callers that just want a reply: a fresh engine at
+/// `strength`, searched once.
+pub fn best_move(board: &Bitboards, strength: Strength) -> Option<Move> {
+    Engine::new(strength).search(board).best_move
+}
+
+fn slot(hash: u64) -> usize {
+    (hash as usize) & (TT_SIZE - 1)
+}
+
+fn mated_in(ply: usize) -> i32 {
+    -MATE_SCORE + ply as i32
+}
+
+fn score_to_tt(score: i32, ply: usize) -> i32 {
+    match score {
+        s if s >= MATE_BOUND => s + ply as i32,
+        s if s <= -MATE_BOUND => s - ply as i32,
+        s => s,
+    }
+}
+
+fn score_from_tt(score: i32, ply: usize) -> i32 {
+    match score {
+        s if s >= MATE_BOUND => s - ply as i32,
+        s if s <= -MATE_BOUND => s + ply as i32,
+        s => s,
+    }
+}
+
+fn captured_piece(bb: &Bitboards, mv: Move) -> Option<Piece> {
+    match mv.kind {
+        MoveKind::EnPassant => Some(Piece::Pawn),
+        MoveKind::Castle(_) | MoveKind::Drop(_) => None,
+        _ => match bb.piece_at(mv.to) {
+            Some((color, piece)) if color != bb.side_to_move => Some(piece),
+            _ => None,
+        },
+    }
+}
+
+fn is_quiet(bb: &Bitboards, mv: Move) -> bool {
+    mv.promotion.is_none() && captured_piece(bb, mv).is_none()
+}
+
+fn value(piece: Piece) -> i32 {
+    match piece {
+        Piece::Pawn => 100,
+        Piece::Knight => 320,
+        Piece::Bishop => 330,
+        Piece::Rook => 500,
+        Piece::Queen => 900,
+        Piece::King => 0,
+    }
+}
+
+// Piece-square tables, laid out as a board diagram from White's side: the
+// first row is rank 8. A white piece on LERF square `sq` reads entry
+// `sq ^ 56`; a black piece reads `sq`, which mirrors the board vertically.
+#[rustfmt::skip]
+const PAWN_TABLE: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+    50, 50, 50, 50, 50, 50, 50, 50,
+    10, 10, 20, 30, 30, 20, 10, 10,
+     5,  5, 10, 25, 25, 10,  5,  5,
+     0,  0,  0, 20, 20,  0,  0,  0,
+     5, -5,-10,  0,  0,-10, -5,  5,
+     5, 10, 10,-20,-20, 10, 10,  5,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_TABLE: [i32; 64] = [
+    -50,-40,-30,-30,-30,-30,-40,-50,
+    -40,-20,  0,  0,  0,  0,-20,-40,
+    -30,  0, 10, 15, 15, 10,  0,-30,
+    -30,  5, 15, 20, 20, 15,  5,-30,
+    -30,  0, 15, 20, 20, 15,  0,-30,
+    -30,  5, 10, 15, 15, 10,  5,-30,
+    -40,-20,  0,  5,  5,  0,-20,-40,
+    -50,-40,-30,-30,-30,-30,-40,-50,
+];
+
+#[rustfmt::skip]
+const BISHOP_TABLE: [i32; 64] = [
+    -20,-10,-10,-10,-10,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5, 10, 10,  5,  0,-10,
+    -10,  5,  5, 10, 10,  5,  5,-10,
+    -10,  0, 10, 10, 10, 10,  0,-10,
+    -10, 10, 10, 10, 10, 10, 10,-10,
+    -10,  5,  0,  0,  0,  0,  5,-10,
+    -20,-10,-10,-10,-10,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const ROOK_TABLE: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+     5, 10, 10, 10, 10, 10, 10,  5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+     0,  0,  0,  5,  5,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const QUEEN_TABLE: [i32; 64] = [
+    -20,-10,-10, -5, -5,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5,  5,  5,  5,  0,-10,
+     -5,  0,  5,  5,  5,  5,  0, -5,
+      0,  0,  5,  5,  5,  5,  0, -5,
+    -10,  5,  5,  5,  5,  5,  0,-10,
+    -10,  0,  5,  0,  0,  0,  0,-10,
+    -20,-10,-10, -5, -5,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const KING_MIDDLEGAME_TABLE: [i32; 64] = [
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -20,-30,-30,-40,-40,-30,-30,-20,
+    -10,-20,-20,-20,-20,-20,-20,-10,
+     20, 20,  0,  0,  0,  0, 20, 20,
+     20, 30, 10,  0,  0, 10, 30, 20,
+];
+
+#[rustfmt::skip]
+const KING_ENDGAME_TABLE: [i32; 64] = [
+    -50,-40,-30,-20,-20,-30,-40,-50,
+    -30,-20,-10,  0,  0,-10,-20,-30,
+    -30,-10, 20, 30, 30, 20,-10,-30,
+    -30,-10, 30, 40, 40, 30,-10,-30,
+    -30,-10, 30, 40, 40, 30,-10,-30,
+    -30,-10, 20, 30, 30, 20,-10,-30,
+    -30,-30,  0,  0,  0,  0,-30,-30,
+    -50,-30,-30,-30,-30,-30,-30,-50,
+];
+
+// Game phase: 24 with all minor and major pieces on the board, 0 with none.
+fn phase(bb: &Bitboards) -> i32 {
+    let count =
+        |piece| (bb.board(Color::White, piece) | bb.board(Color::Black, piece)).count_ones() as i32;
+    let phase = count(Piece::Knight)
+        + count(Piece::Bishop)
+        + 2 * count(Piece::Rook)
+        + 4 * count(Piece::Queen);
+    phase.min(24)
+}
+
+/// Static evaluation in centipawns from the side to move's point of view.
+pub fn evaluate(bb: &Bitboards) -> i32 {
+    let phase = phase(bb);
+    let mut score = 0;
+    for color in [Color::White, Color::Black] {
+        let sign = if color == Color::White { 1 } else { -1 };
+        let flip = if color == Color::White { 56 } else { 0 };
+        for piece in Piece::ALL {
+            for sq in squares(bb.board(color, piece)) {
+                let i = (sq ^ flip) as usize;
+                let positional = match piece {
+                    Piece::Pawn => PAWN_TABLE[i],
+                    Piece::Knight => KNIGHT_TABLE[i],
+                    Piece::Bishop => BISHOP_TABLE[i],
+                    Piece::Rook => ROOK_TABLE[i],
+                    Piece::Queen => QUEEN_TABLE[i],
+                    Piece::King => {
+                        (KING_MIDDLEGAME_TABLE[i] * phase + KING_ENDGAME_TABLE[i] * (24 - phase))
+                            / 24
+                    }
+                };
+                score += sign * (value(piece) + positional);
+            }
+        }
+        if bb.board(color, Piece::Bishop).count_ones() >= 2 {
+            score += sign * 30;
+        }
+    }
+    match bb.side_to_move {
+        Color::White => score,
+        Color::Black => -score,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::bitboard::bit;
+
+    fn limits(depth: u8) -> SearchLimits {
+        SearchLimits {
+            depth,
+            nodes: None,
+            time: None,
+        }
+    }
+
+    fn best_uci(fen: &str, depth: u8) -> String {
+        let bb = Bitboards::from_fen(fen).unwrap();
+        let result = Engine::with_seed(Strength::MAX, 1).search_with(&bb, limits(depth));
+        result.best_move.unwrap().to_uci()
+    }
+
+    #[test]
+    fn finds_mate_in_one() {
+        assert_eq!(best_uci("6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1", 3), "d1d8");
+    }
+
+    #[test]
+    fn finds_mate_in_two() {
+        // Any waiting rook move, ...Kb8 (forced), then mate on the back rank.
+        let bb = Bitboards::from_fen("k7/8/1K6/8/8/8/8/1R6 w - - 0 1").unwrap();
+        let result = Engine::with_seed(Strength::MAX, 1).search_with(&bb, limits(6));
+        assert_eq!(result.score, MATE_SCORE - 3);
+        assert_eq!(result.pv.len(), 3, "pv {:?}", result.pv);
+        let mut end = bb.clone();
+        for &mv in &result.pv {
+            end.make_move(mv);
+        }
+        assert!(end.is_checkmate());
+    }
+
+    #[test]
+    fn takes_a_hanging_queen() {
+        assert_eq!(best_uci("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1", 2), "d2d5");
+    }
+
+    #[test]
+    fn does_not_grab_a_defended_pawn_with_the_queen() {
+        // Qxd5?? exd5.
+        let uci = best_uci("4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1", 3);
+        assert_ne!(uci, "d1d5");
+    }
+
+    #[test]
+    fn no_move_when_mated() {
+        let bb =
+            Bitboards::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3")
+                .unwrap();
+        let result = Engine::new(Strength::default()).search(&bb);
+        assert_eq!(result.best_move, None);
+        assert_eq!(result.score, -MATE_SCORE);
+    }
+
+    #[test]
+    fn respects_node_limit() {
+        let bb = Bitboards::starting_position();
+        let result = Engine::with_seed(Strength::MAX, 1).search_with(
+            &bb,
+            SearchLimits {
+                depth: MAX_DEPTH,
+                nodes: Some(20_000),
+                time: None,
+            },
+        );
+        let mv = result.best_move.unwrap();
+        assert!(bb.is_legal(mv));
+        assert!(bb.occupancy(Color::White) & bit(mv.from) != 0);
+        assert!(
+            result.nodes < 20_000 + 2 * CHECK_EVERY,
+            "{} nodes",
+            result.nodes
+        );
+        assert!(result.depth >= 2 && result.depth < MAX_DEPTH);
+        assert_eq!(result.pv.first(), Some(&mv));
+    }
+
+    #[test]
+    fn every_level_plays_legal_moves() {
+        let mut bb = Bitboards::starting_position();
+        bb.apply_uci_moves("e2e4 e7e5 g1f3 b8c6");
+        for level in 1..=8 {
+            let mut engine = Engine::with_seed(Strength::new(level), level as u64);
+            let result = engine.search_with(&bb, limits(2));
+            assert!(bb.is_legal(result.best_move.unwrap()), "level {}", level);
+        }
+    }
+
+    #[test]
+    fn weak_levels_vary_strong_levels_do_not() {
+        let bb = Bitboards::starting_position();
+        let picks = |strength: Strength| {
+            (0..8)
+                .map(|seed| {
+                    let mut engine = Engine::with_seed(strength, seed);
+                    engine.search_with(&bb, limits(1)).best_move.unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+        let weak = picks(Strength::MIN);
+        assert!(weak.iter().any(|&mv| mv != weak[0]));
+        let strong = picks(Strength::MAX);
+        assert!(strong.iter().all(|&mv| mv == strong[0]));
+    }
+
+    #[test]
+    fn evaluation_is_symmetric() {
+        let white =
+            Bitboards::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3")
+                .unwrap();
+        let black =
+            Bitboards::from_fen("rnbqkb1r/pppp1ppp/5n2/4p3/4P3/2N5/PPPP1PPP/R1BQKBNR b KQkq - 2 3")
+                .unwrap();
+        assert_eq!(evaluate(&white), evaluate(&black));
+        assert_eq!(evaluate(&Bitboards::starting_position()), 0);
+    }
+
+    #[test]
+    fn strength_levels() {
+        assert_eq!(Strength::new(0), Strength::MIN);
+        assert_eq!(Strength::new(20), Strength::MAX);
+        assert_eq!(Strength::MAX.cycle(), Strength::MIN);
+        assert_eq!(Strength::new(3).cycle().level(), 4);
+        assert!(Strength::MIN.limits().time < Strength::MAX.limits().time);
+    }
+}
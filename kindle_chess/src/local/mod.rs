@@ -1 +1,4 @@
 pub mod board;
+pub mod engine;
+pub mod save;
+pub mod uci;
@@ -0,0 +1,401 @@
+// Savegames for offline games: one PGN file per game under ROOT_DIR/saves/,
+// rewritten after every move so a game survives the app being closed (or
+// the Kindle going to sleep and never coming back).
+//
+// PGN already covers the starting position (SetUp/FEN), the moves, the
+// players and the result, and any chess program can open the files. Offline
+// games are untimed, so there are no clocks to save: the files say so with
+// PGN's `[TimeControl "-"]`. What only this app needs to resume a game goes
+// in extra tags:
+//
+//   [KindleOpponent "engine 4"]  or "human" for pass-and-play
+//   [KindlePlayer0 "white"]      the device owner's colour
+//   [KindleAutoFlip "1"]         pass-and-play board turning
+//   [PlyCount "37"]              standard PGN; with the FEN's side to move,
+//                                says whose turn it is without the moves
+//
+// A file without the Kindle tags (a PGN copied over by hand) loads as a
+// pass-and-play game.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::warn;
+
+use crate::models::{
+    board_api::Turn,
+    board_local::{BoardLocal, LocalOpponent, SaveSummary},
+    engine::Strength,
+    pgn::{PgnGame, load_pgn_file, read_pgn_tags, result_for},
+    uci::UciHandle,
+};
+
+const OPPONENT_TAG: &str = "KindleOpponent";
+const PLAYER0_TAG: &str = "KindlePlayer0";
+const AUTO_FLIP_TAG: &str = "KindleAutoFlip";
+
+impl BoardLocal {
+    /// Where a game started now should be saved.
+    pub fn new_save_path() -> PathBuf {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        Path::new(env!("SAVES_DIR")).join(format!("{}.pgn", millis))
+    }
+
+    /// Write the game to `save_path`, if it has one. The new save goes to a
+    /// temporary file first and is renamed over the old one, so a crash
+    /// mid-write can't lose the game.
+    pub fn autosave(&self) -> Result<(), String> {
+        let Some(path) = &self.save_path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        let tmp = path.with_extension("pgn.tmp");
+        fs::write(&tmp, self.to_pgn_game().to_pgn())
+            .map_err(|e| format!("{}: {}", tmp.display(), e))?;
+        fs::rename(&tmp, path).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Resume a saved game; it keeps saving to the same file.
+    pub fn load(path: &Path) -> Result<BoardLocal, String> {
+        let game = load_pgn_file(path)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("{}: no game", path.display()))?;
+        let mut local = Self::from_pgn_game(&game)?;
+        local.save_path = Some(path.to_path_buf());
+        Ok(local)
+    }
+
+    /// The unfinished savegames in `dir`, most recently played first. Only
+    /// the tags are read; files that can't be are skipped. Blocks on the
+    /// file system, so the UI calls it off its thread.
+    pub fn list_saves(dir: &Path) -> Vec<SaveSummary> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut saves: Vec<SaveSummary> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "pgn"))
+            .filter_map(|path| match SaveSummary::read(&path) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    warn!("Skipping unreadable savegame: {}", e);
+                    None
+                }
+            })
+            .collect();
+        saves.sort_by_key(|save| std::cmp::Reverse(save.modified));
+        saves
+    }
+
+    /// Saved offline games that haven't finished, most recent first.
+    pub fn get_ongoing_games() -> Vec<SaveSummary> {
+        Self::list_saves(Path::new(env!("SAVES_DIR")))
+    }
+
+    pub fn to_pgn_game(&self) -> PgnGame {
+        let board = &self.board;
+        let mut game = PgnGame::from_position(board.root_position());
+        let (white, black) = self.player_names();
+        game.set_tag("Event", "Offline game");
+        game.set_tag("Site", "Kindle");
+        game.set_tag("Date", &self.date);
+        game.set_tag("White", &white);
+        game.set_tag("Black", &black);
+        game.set_tag("Result", self.result());
+        game.set_tag("TimeControl", "-");
+
+        let opponent = match &self.opponent {
+            LocalOpponent::Engine { strength, .. } => format!("engine {}", strength.level()),
+            LocalOpponent::Human => "human".to_string(),
+        };
+        game.set_tag(OPPONENT_TAG, &opponent);
+        game.set_tag(
+            PLAYER0_TAG,
+            if self.player0_white { "white" } else { "black" },
+        );
+        game.set_tag(AUTO_FLIP_TAG, if self.auto_flip { "1" } else { "0" });
+        game.set_tag("PlyCount", &board.ply_count().to_string());
+
+        let mut position = game.start.clone();
+        let mut node = PgnGame::ROOT;
+        for mv in board.moves() {
+            node = game.add_move(node, &position, mv);
+            position.make_move(mv);
+        }
+        game
+    }
+
+    pub fn from_pgn_game(game: &PgnGame) -> Result<BoardLocal, String> {
+        let opponent = match game.tag(OPPONENT_TAG) {
+            None | Some("human") => LocalOpponent::Human,
+            Some(engine) => {
+                let level = engine_level(engine)
+                    .ok_or_else(|| format!("bad {} tag '{}'", OPPONENT_TAG, engine))?;
+                LocalOpponent::Engine {
+                    strength: Strength::new(level),
+                    uci: UciHandle::installed(),
+                }
+            }
+        };
+
+        let mut board = game.start.clone();
+        for mv in game.mainline_moves() {
+            board.make_move(mv);
+        }
+        let mut local = BoardLocal {
+            board,
+            player0_white: game.tag(PLAYER0_TAG) != Some("black"),
+            turn: Turn::Playing,
+            opponent,
+            auto_flip: game.tag(AUTO_FLIP_TAG) == Some("1"),
+            save_path: None,
+            date: game.tag("Date").unwrap_or(&today()).to_string(),
+        };
+        local.turn = local.compute_turn();
+
+        // A result the board doesn't show by itself (nothing sets one yet,
+        // but a hand-edited file might).
+        if !local.is_over() && game.result() != "*" {
+            let (white, black) = local.player_names();
+            local.turn = Turn::Over {
+                winner: match game.result() {
+                    "1-0" => Some(white),
+                    "0-1" => Some(black),
+                    _ => None,
+                },
+                reason: None,
+            };
+        }
+        Ok(local)
+    }
+
+    // PGN Result for the game as it stands.
+    fn result(&self) -> &'static str {
+        if let Some(outcome) = self.board.outcome() {
+            return result_for(outcome);
+        }
+        match &self.turn {
+            Turn::Over {
+                winner: Some(winner),
+                ..
+            } => {
+                if *winner == self.player_names().0 {
+                    "1-0"
+                } else {
+                    "0-1"
+                }
+            }
+            Turn::Over { winner: None, .. } => "1/2-1/2",
+            _ => "*",
+        }
+    }
+}
+
+impl SaveSummary {
+    /// `path`'s summary from its tags, or `None` when the game there is
+    /// over (it stays on disk, but isn't listed).
+    pub fn read(path: &Path) -> Result<Option<SaveSummary>, String> {
+        let tags = read_pgn_tags(path)?;
+        let tag = |name: &str| {
+            tags.iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.as_str())
+        };
+        if tag("Result").is_some_and(|result| result != "*") {
+            return Ok(None);
+        }
+        let engine_level = match tag(OPPONENT_TAG) {
+            None | Some("human") => None,
+            Some(engine) => Some(
+                engine_level(engine)
+                    .ok_or_else(|| format!("bad {} tag '{}'", OPPONENT_TAG, engine))?,
+            ),
+        };
+        // Side to move at the start, flipped once per ply.
+        let black_started = tag("FEN")
+            .and_then(|fen| fen.split_whitespace().nth(1))
+            .is_some_and(|side| side == "b");
+        let white_to_move = tag("PlyCount")
+            .and_then(|plies| plies.parse::<usize>().ok())
+            .map(|plies| (plies % 2 == 1) == black_started);
+        let player0_white = tag(PLAYER0_TAG) != Some("black");
+        let modified = fs::metadata(path)
+            .and_then(|m| m.modified())
+            .unwrap_or(UNIX_EPOCH);
+        Ok(Some(SaveSummary {
+            path: path.to_path_buf(),
+            engine_level,
+            my_turn: white_to_move == Some(player0_white),
+            modified,
+        }))
+    }
+}
+
+// The level in a KindleOpponent tag's "engine N".
+fn engine_level(tag: &str) -> Option<u8> {
+    tag.strip_prefix("engine ")?.parse().ok()
+}
+
+/// Today's date as a PGN Date tag, "YYYY.MM.DD" (UTC).
+pub fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() / 86_400);
+    let (year, month, day) = civil_from_days(days as i64);
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
+
+// Gregorian date from days since 1970-01-01 (Howard Hinnant's algorithm).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "kindle_chess_saves_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn engine_game_round_trips() {
+        let mut local = BoardLocal::vs_engine(false, Strength::new(6));
+        for uci in ["e2e4", "c7c5", "g1f3"] {
+            let mv = local.board.find_legal(uci).unwrap();
+            local.play(mv);
+        }
+        let pgn = local.to_pgn_game().to_pgn();
+        assert!(pgn.contains("[White \"Kindle level 6\"]"));
+        assert!(pgn.contains("[Black \"You\"]"));
+        assert!(pgn.contains("[KindleOpponent \"engine 6\"]"));
+        assert!(pgn.contains("[TimeControl \"-\"]"));
+        assert!(pgn.contains("1. e4 c5 2. Nf3 *"));
+
+        let game = crate::models::pgn::parse_pgn(&pgn).unwrap().remove(0);
+        let back = BoardLocal::from_pgn_game(&game).unwrap();
+        assert_eq!(back.board.to_fen(), local.board.to_fen());
+        assert_eq!(back.board.ply_count(), 3);
+        assert!(!back.player0_white);
+        assert!(matches!(back.turn, Turn::Playing));
+        assert_eq!(back.date, local.date);
+        match back.opponent {
+            LocalOpponent::Engine { strength, .. } => assert_eq!(strength.level(), 6),
+            LocalOpponent::Human => panic!("expected an engine game"),
+        }
+    }
+
+    #[test]
+    fn finished_game_keeps_result() {
+        let mut local = BoardLocal::pass_and_play(true);
+        for uci in ["f2f3", "e7e5", "g2g4", "d8h4"] {
+            local.play_user_move(uci).unwrap();
+        }
+        let game = local.to_pgn_game();
+        assert_eq!(game.result(), "0-1");
+        let back = BoardLocal::from_pgn_game(&game).unwrap();
+        assert!(back.is_over());
+        assert!(back.auto_flip);
+        assert!(matches!(back.opponent, LocalOpponent::Human));
+    }
+
+    #[test]
+    fn plain_pgn_loads_as_pass_and_play() {
+        let game = crate::models::pgn::parse_pgn(
+            "[Event \"Casual\"]\n[Result \"1-0\"]\n\n1. e4 e5 2. Qh5 Nc6 1-0\n",
+        )
+        .unwrap()
+        .remove(0);
+        let local = BoardLocal::from_pgn_game(&game).unwrap();
+        assert!(matches!(local.opponent, LocalOpponent::Human));
+        assert!(local.player0_white);
+        // Decided by the tag, not the position.
+        match local.turn {
+            Turn::Over { winner, .. } => assert_eq!(winner.as_deref(), Some("White")),
+            other => panic!("expected game over, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn autosave_and_list() {
+        let dir = scratch_dir("list");
+        let mut first = BoardLocal::pass_and_play(false);
+        first.save_path = Some(dir.join("1.pgn"));
+        first.play_user_move("d2d4").unwrap();
+        first.autosave().unwrap();
+
+        let mut second = BoardLocal::vs_engine(true, Strength::default());
+        second.save_path = Some(dir.join("2.pgn"));
+        second.autosave().unwrap();
+        // Touch the first again so it's the most recently played.
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        first.play_user_move("d7d5").unwrap();
+        first.autosave().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(dir.join("junk.pgn"), "1. e4 Ke7?? Qxx9").unwrap();
+        fs::write(dir.join("notes.txt"), "not a game").unwrap();
+
+        // A finished game stays on disk but isn't listed.
+        let mut over = BoardLocal::pass_and_play(true);
+        over.save_path = Some(dir.join("3.pgn"));
+        for uci in ["f2f3", "e7e5", "g2g4", "d8h4"] {
+            over.play_user_move(uci).unwrap();
+        }
+        over.autosave().unwrap();
+
+        // Only the tags are read, so the junk file is listed (it's the
+        // newest) and only fails once it's loaded.
+        let saves = BoardLocal::list_saves(&dir);
+        assert_eq!(saves.len(), 3);
+        assert!(BoardLocal::load(&saves[0].path).is_err());
+        assert_eq!(Some(&saves[1].path), first.save_path.as_ref());
+        assert_eq!(saves[1].engine_level, None);
+        assert_eq!(
+            BoardLocal::load(&saves[1].path).unwrap().board.ply_count(),
+            2
+        );
+        assert_eq!(Some(&saves[2].path), second.save_path.as_ref());
+        assert_eq!(saves[2].engine_level, Some(Strength::default().level()));
+        // Playing white with nothing moved yet: our turn.
+        assert!(saves[2].my_turn);
+        assert!(dir.join("3.pgn").exists());
+        assert!(!dir.join("1.pgn.tmp").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(BoardLocal::list_saves(&dir).is_empty());
+    }
+
+    #[test]
+    fn no_save_path_is_not_saved() {
+        assert!(BoardLocal::pass_and_play(false).autosave().is_ok());
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20_744), (2026, 10, 18));
+    }
+}
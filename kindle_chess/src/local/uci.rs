@@ -0,0 +1,575 @@
+// Client for external UCI engines. The engine binary runs as a child
+// process; commands go to its stdin one per line and replies are read back
+// from its stdout. A search streams its `info` lines to the UI as
+// `AppEvent::EngineInfo` while it runs and ends at `bestmove`.
+//
+// Every read has a deadline: an engine that hangs or dies is dropped (which
+// kills the process) and reported as an error, so the caller can fall back
+// to the built-in engine.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use log::{debug, info};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use tokio::time::{Instant, timeout_at};
+
+use crate::models::bitboard::{Bitboards, Color, Move, Variant, to_san};
+use crate::models::engine::{MAX_DEPTH, SearchLimits, Strength};
+use crate::models::uci::{UciEngine, UciHandle, UciInfo, UciScore};
+use crate::ui::events::AppEvent;
+
+// Moves of the PV shown in the sidebar; more wouldn't fit next to the turn.
+const SUMMARY_PV_MOVES: usize = 4;
+
+// How long the engine gets to answer `uci` and `isready`. Loading a large
+// network file from the Kindle's flash can take a while.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+// Slack on top of the search's own time limit before we send `stop`, and
+// again after `stop` before giving up on the engine.
+const SEARCH_GRACE: Duration = Duration::from_secs(2);
+// For searches with no time limit of their own.
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+impl UciEngine {
+    /// Start the engine at `path` and run the `uci`/`isready` handshake.
+    pub async fn spawn(path: &Path) -> Result<UciEngine, String> {
+        let mut child = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("failed to start {}: {}", path.display(), e))?;
+        let stdin = child.stdin.take().ok_or("engine stdin not piped")?;
+        let stdout = child.stdout.take().ok_or("engine stdout not piped")?;
+        let mut engine = UciEngine {
+            name: String::new(),
+            options: Vec::new(),
+            child,
+            stdin,
+            lines: BufReader::new(stdout).lines(),
+        };
+
+        engine.send("uci").await?;
+        let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+        loop {
+            let line = engine.read_line(deadline).await?;
+            if line == "uciok" {
+                break;
+            } else if let Some(name) = line.strip_prefix("id name ") {
+                engine.name = name.trim().to_string();
+            } else if let Some(option) = line.strip_prefix("option name ") {
+                let name = option.split(" type ").next().unwrap_or(option);
+                engine.options.push(name.trim().to_string());
+            }
+        }
+        engine.ready().await?;
+        Ok(engine)
+    }
+
+    /// Whether the engine advertised option `name`. UCI option names are
+    /// case-insensitive.
+    pub fn has_option(&self, name: &str) -> bool {
+        self.options.iter().any(|o| o.eq_ignore_ascii_case(name))
+    }
+
+    pub async fn set_option(&mut self, name: &str, value: &str) -> Result<(), String> {
+        self.send(&format!("setoption name {} value {}", name, value))
+            .await
+    }
+
+    /// Search `board` within `limits`. Each `info` line with a depth or
+    /// score is sent on `tx` as it arrives. Returns the engine's move, or
+    /// `None` if it reports that there is none.
+    pub async fn search(
+        &mut self,
+        board: &Bitboards,
+        limits: &SearchLimits,
+        tx: &Sender<AppEvent>,
+    ) -> Result<Option<Move>, String> {
+        self.send(&position_command(board)).await?;
+        self.send(&go_command(limits)).await?;
+
+        let mut deadline = Instant::now() + limits.time.unwrap_or(SEARCH_TIMEOUT) + SEARCH_GRACE;
+        let mut stop_sent = false;
+        loop {
+            let line = match self.read_line(deadline).await {
+                // Over time: ask for the move found so far, once.
+                Err(_) if !stop_sent && Instant::now() >= deadline => {
+                    self.send("stop").await?;
+                    stop_sent = true;
+                    deadline = Instant::now() + SEARCH_GRACE;
+                    continue;
+                }
+                other => other?,
+            };
+            if line.starts_with("info ") {
+                if let Some(info) = parse_info(&line, board) {
+                    let _ = tx.send(AppEvent::EngineInfo(info));
+                }
+            } else if let Some(rest) = line.strip_prefix("bestmove") {
+                return match rest.split_whitespace().next() {
+                    None | Some("(none)") | Some("0000") => Ok(None),
+                    Some(uci) => board
+                        .find_legal(uci)
+                        .map(Some)
+                        .ok_or_else(|| format!("engine played illegal move '{}'", uci)),
+                };
+            }
+        }
+    }
+
+    async fn ready(&mut self) -> Result<(), String> {
+        self.send("isready").await?;
+        let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+        while self.read_line(deadline).await? != "readyok" {}
+        Ok(())
+    }
+
+    async fn send(&mut self, command: &str) -> Result<(), String> {
+        debug!("uci > {}", command);
+        self.stdin
+            .write_all(format!("{}\n", command).as_bytes())
+            .await
+            .map_err(|e| format!("write to engine failed: {}", e))?;
+        self.stdin
+            .flush()
+            .await
+            .map_err(|e| format!("write to engine failed: {}", e))
+    }
+
+    async fn read_line(&mut self, deadline: Instant) -> Result<String, String> {
+        match timeout_at(deadline, self.lines.next_line()).await {
+            Ok(Ok(Some(line))) => {
+                debug!("uci < {}", line);
+                Ok(line.trim().to_string())
+            }
+            Ok(Ok(None)) => Err(match self.child.try_wait() {
+                Ok(Some(status)) => format!("engine exited ({})", status),
+                _ => "engine closed its output".to_string(),
+            }),
+            Ok(Err(e)) => Err(format!("read from engine failed: {}", e)),
+            Err(_) => Err("engine timed out".to_string()),
+        }
+    }
+}
+
+impl UciHandle {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            engine: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// The engine installed at `ROOT_DIR/engines/uci`, if there is one.
+    pub fn installed() -> Option<UciHandle> {
+        let path = PathBuf::from(env!("UCI_ENGINE"));
+        path.is_file().then(|| Self::new(path))
+    }
+
+    /// Pick a move for the side to move, starting the engine first if it
+    /// isn't running. `strength` sets the engine's `Skill Level` (when it
+    /// has one) and the thinking time. After an error the process is
+    /// dropped, and the next call starts a fresh one.
+    pub async fn best_move(
+        &self,
+        board: &Bitboards,
+        strength: Strength,
+        tx: &Sender<AppEvent>,
+    ) -> Result<Option<Move>, String> {
+        if !matches!(
+            board.variant,
+            Variant::Standard | Variant::Chess960 | Variant::FromPosition
+        ) {
+            return Err(format!(
+                "{} is not supported over UCI",
+                board.variant.name()
+            ));
+        }
+
+        let mut slot = self.engine.lock().await;
+        let engine = match slot.take() {
+            Some(engine) => engine,
+            None => {
+                let engine = UciEngine::spawn(&self.path).await?;
+                info!(
+                    "Started UCI engine '{}' ({})",
+                    engine.name,
+                    self.path.display()
+                );
+                engine
+            }
+        };
+        let engine = slot.insert(engine);
+
+        let result = async {
+            if board.chess960 || engine.has_option("UCI_Chess960") {
+                if !engine.has_option("UCI_Chess960") {
+                    return Err("engine does not play Chess960".to_string());
+                }
+                let chess960 = if board.chess960 { "true" } else { "false" };
+                engine.set_option("UCI_Chess960", chess960).await?;
+            }
+            if engine.has_option("Skill Level") {
+                engine
+                    .set_option("Skill Level", &skill_level(strength).to_string())
+                    .await?;
+            }
+            // Only the time cap carries over: depth limits sized for the
+            // built-in engine would cripple a strong one.
+            let limits = SearchLimits {
+                depth: MAX_DEPTH,
+                nodes: None,
+                time: strength.limits().time,
+            };
+            engine.search(board, &limits, tx).await
+        }
+        .await;
+        if result.is_err() {
+            *slot = None;
+        }
+        result
+    }
+}
+
+// Stockfish's `Skill Level` runs 0..=20; spread our 1..=8 across it.
+fn skill_level(strength: Strength) -> u8 {
+    (strength.level() - 1) * 20 / 7
+}
+
+// The game so far as a `position` command: the FEN of the position before
+// the first recorded move, then the moves, so the engine sees repetitions.
+fn position_command(board: &Bitboards) -> String {
+    let mut root = board.root_position();
+    let mut command = format!("position fen {}", root.to_fen());
+    let moves = board.moves();
+    if !moves.is_empty() {
+        command.push_str(" moves");
+        for mv in moves {
+            command.push(' ');
+            command.push_str(&root.move_to_uci(mv));
+            root.make_move(mv);
+        }
+    }
+    command
+}
+
+fn go_command(limits: &SearchLimits) -> String {
+    let mut command = format!("go depth {}", limits.depth);
+    if let Some(nodes) = limits.nodes {
+        command.push_str(&format!(" nodes {}", nodes));
+    }
+    if let Some(time) = limits.time {
+        command.push_str(&format!(" movetime {}", time.as_millis()));
+    }
+    command
+}
+
+// Parses an `info` line. The PV is read as moves from `board`, stopping at
+// the first one that isn't legal.
+fn parse_info(line: &str, board: &Bitboards) -> Option<UciInfo> {
+    let mut info = UciInfo {
+        depth: 0,
+        seldepth: None,
+        multipv: None,
+        score: None,
+        nodes: None,
+        nps: None,
+        time: None,
+        pv: Vec::new(),
+    };
+    let mut has_depth = false;
+    let mut tokens = line.split_whitespace().skip(1);
+    while let Some(token) = tokens.next() {
+        match token {
+            "depth" => {
+                info.depth = tokens.next()?.parse().ok()?;
+                has_depth = true;
+            }
+            "seldepth" => info.seldepth = tokens.next()?.parse().ok(),
+            "multipv" => info.multipv = tokens.next()?.parse().ok(),
+            "nodes" => info.nodes = tokens.next()?.parse().ok(),
+            "nps" => info.nps = tokens.next()?.parse().ok(),
+            "time" => info.time = tokens.next()?.parse().ok().map(Duration::from_millis),
+            "score" => {
+                let kind = tokens.next()?;
+                let value = tokens.next()?.parse().ok()?;
+                info.score = match kind {
+                    "cp" => Some(UciScore::Centipawns(value)),
+                    "mate" => Some(UciScore::Mate(value)),
+                    _ => None,
+                };
+            }
+            "pv" => {
+                let mut position = board.clone();
+                for uci in tokens.by_ref() {
+                    let Some(mv) = position.find_legal(uci) else {
+                        break;
+                    };
+                    info.pv.push(mv);
+                    position.make_move(mv);
+                }
+            }
+            // Free text to the end of the line.
+            "string" => break,
+            _ => {}
+        }
+    }
+    (has_depth || info.score.is_some()).then_some(info)
+}
+
+impl UciInfo {
+    /// One line for the sidebar: depth, score from White's side and the
+    /// start of the PV in SAN, e.g. "d18 -0.35 d4 Nf6 c4". `board` is the
+    /// position that was searched.
+    pub fn summary(&self, board: &Bitboards) -> String {
+        let sign = if board.side_to_move == Color::White {
+            1
+        } else {
+            -1
+        };
+        let mut parts = vec![format!("d{}", self.depth)];
+        match self.score {
+            Some(UciScore::Centipawns(cp)) => {
+                parts.push(format!("{:+.2}", f64::from(sign * cp) / 100.0));
+            }
+            Some(UciScore::Mate(moves)) => parts.push(format!("#{}", sign * moves)),
+            None => {}
+        }
+        let mut position = board.clone();
+        for &mv in self.pv.iter().take(SUMMARY_PV_MOVES) {
+            parts.push(to_san(&position, mv));
+            position.make_move(mv);
+        }
+        parts.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use std::sync::OnceLock;
+    use std::sync::mpsc::channel;
+
+    // A scripted stand-in for a real engine. It answers the handshake,
+    // reports its `Skill Level` back as the depth-1 score, and always plays
+    // e2e4. On `go` in a position with moves, `crashing` exits instead.
+    const FAKE_ENGINE: &str = r#"#!/bin/sh
+skill=20
+while read -r cmd args; do
+    case "$cmd" in
+        uci)
+            echo "id name Fakefish 1.0"
+            echo "option name Skill Level type spin default 20 min 0 max 20"
+            echo "option name Hash type spin default 16 min 1 max 1024"
+            echo "uciok" ;;
+        isready) echo "readyok" ;;
+        setoption) skill=${args##* } ;;
+        position) position=$args ;;
+        go)
+            case "$0:$position" in
+                *crashing:*moves*) exit 3 ;;
+            esac
+            echo "info string searching"
+            echo "info depth 1 seldepth 1 score cp $skill nodes 20 nps 2000 time 10 pv e2e4"
+            echo "info depth 2 score mate 2 lowerbound nodes 120 time 15 pv e2e4 e7e5 g1f3"
+            echo "bestmove e2e4 ponder e7e5" ;;
+        quit) exit 0 ;;
+    esac
+done
+"#;
+
+    // Both scripts are written once, before any test spawns a process, so
+    // no forked child can be holding a write handle when one is executed.
+    fn fake_engines() -> &'static (PathBuf, PathBuf) {
+        static ENGINES: OnceLock<(PathBuf, PathBuf)> = OnceLock::new();
+        ENGINES.get_or_init(|| {
+            let dir = std::env::temp_dir().join(format!("kindle_chess_uci_{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let write = |name: &str| {
+                let path = dir.join(name);
+                std::fs::write(&path, FAKE_ENGINE).unwrap();
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+                path
+            };
+            (write("fakefish"), write("crashing"))
+        })
+    }
+
+    fn reported(rx: &std::sync::mpsc::Receiver<AppEvent>) -> Vec<UciInfo> {
+        rx.try_iter()
+            .filter_map(|event| match event {
+                AppEvent::EngineInfo(info) => Some(info),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn handshake_reads_name_and_options() {
+        let engine = UciEngine::spawn(&fake_engines().0).await.unwrap();
+        assert_eq!(engine.name, "Fakefish 1.0");
+        assert_eq!(engine.options, ["Skill Level", "Hash"]);
+        assert!(engine.has_option("skill level"));
+        assert!(!engine.has_option("UCI_Chess960"));
+    }
+
+    #[tokio::test]
+    async fn search_streams_info_and_returns_bestmove() {
+        let handle = UciHandle::new(fake_engines().0.clone());
+        let (tx, rx) = channel();
+        let board = Bitboards::starting_position();
+        let mv = handle
+            .best_move(&board, Strength::new(4), &tx)
+            .await
+            .unwrap();
+        assert_eq!(mv, board.find_legal("e2e4"));
+
+        let infos = reported(&rx);
+        assert_eq!(infos.len(), 2);
+        // Level 4 is skill 8, echoed back by the fake engine.
+        assert_eq!(infos[0].score, Some(UciScore::Centipawns(8)));
+        assert_eq!(infos[0].nps, Some(2000));
+        assert_eq!(infos[1].depth, 2);
+        assert_eq!(infos[1].score, Some(UciScore::Mate(2)));
+        assert_eq!(infos[1].time, Some(Duration::from_millis(15)));
+        assert_eq!(infos[1].pv.len(), 3);
+
+        // The process stays up for the next search.
+        let mv = handle.best_move(&board, Strength::MAX, &tx).await.unwrap();
+        assert!(mv.is_some());
+        assert_eq!(reported(&rx)[0].score, Some(UciScore::Centipawns(20)));
+    }
+
+    #[tokio::test]
+    async fn engine_that_dies_is_an_error_and_restarts() {
+        let handle = UciHandle::new(fake_engines().1.clone());
+        let (tx, _rx) = channel();
+        let mut board = Bitboards::starting_position();
+        assert!(
+            handle
+                .best_move(&board, Strength::default(), &tx)
+                .await
+                .is_ok()
+        );
+
+        board.apply_uci_move("e2e4").unwrap();
+        assert!(
+            handle
+                .best_move(&board, Strength::default(), &tx)
+                .await
+                .is_err()
+        );
+        assert!(handle.engine.lock().await.is_none());
+
+        board.unmake_move();
+        assert!(
+            handle
+                .best_move(&board, Strength::default(), &tx)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_binary_and_variants_are_errors() {
+        let (tx, _rx) = channel();
+        let handle = UciHandle::new(PathBuf::from("/nonexistent/engine"));
+        let board = Bitboards::starting_position();
+        assert!(
+            handle
+                .best_move(&board, Strength::default(), &tx)
+                .await
+                .is_err()
+        );
+
+        let handle = UciHandle::new(fake_engines().0.clone());
+        let board = Bitboards::variant_start(Variant::Atomic);
+        assert!(
+            handle
+                .best_move(&board, Strength::default(), &tx)
+                .await
+                .is_err()
+        );
+        // Refused before the engine was ever started.
+        assert!(handle.engine.lock().await.is_none());
+    }
+
+    #[test]
+    fn position_includes_move_history() {
+        let mut board = Bitboards::starting_position();
+        board.apply_uci_moves("e2e4 e7e5 g1f3");
+        assert_eq!(
+            position_command(&board),
+            "position fen rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 \
+             moves e2e4 e7e5 g1f3"
+        );
+        assert_eq!(
+            position_command(&Bitboards::starting_position()),
+            "position fen rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+        );
+    }
+
+    #[test]
+    fn go_command_limits() {
+        let limits = SearchLimits {
+            depth: 12,
+            nodes: Some(5000),
+            time: Some(Duration::from_millis(1500)),
+        };
+        assert_eq!(go_command(&limits), "go depth 12 nodes 5000 movetime 1500");
+    }
+
+    #[test]
+    fn parse_info_lines() {
+        let board = Bitboards::starting_position();
+        let info = parse_info(
+            "info depth 18 seldepth 24 multipv 1 score cp -35 upperbound nodes 912345 \
+             nps 456000 time 2001 pv d2d4 g8f6 e2e5",
+            &board,
+        )
+        .unwrap();
+        assert_eq!(info.depth, 18);
+        assert_eq!(info.seldepth, Some(24));
+        assert_eq!(info.multipv, Some(1));
+        assert_eq!(info.score, Some(UciScore::Centipawns(-35)));
+        assert_eq!(info.nodes, Some(912345));
+        assert_eq!(info.time, Some(Duration::from_millis(2001)));
+        // e2e5 is illegal after d4 Nf6, so the PV stops before it.
+        assert_eq!(info.pv.len(), 2);
+
+        let mate = parse_info("info score mate -3", &board).unwrap();
+        assert_eq!(mate.score, Some(UciScore::Mate(-3)));
+        assert!(parse_info("info string depth 3", &board).is_none());
+        assert!(parse_info("info currmove e2e4 currmovenumber 1", &board).is_none());
+    }
+
+    #[test]
+    fn summaries_score_from_whites_side() {
+        let mut board = Bitboards::starting_position();
+        board.apply_uci_move("e2e4").unwrap();
+        let info = parse_info(
+            "info depth 12 score cp 40 pv c7c5 g1f3 d7d6 d2d4 c5d4",
+            &board,
+        )
+        .unwrap();
+        assert_eq!(info.summary(&board), "d12 -0.40 c5 Nf3 d6 d4");
+
+        let mate = parse_info("info depth 5 score mate -2", &board).unwrap();
+        assert_eq!(mate.summary(&board), "d5 #2");
+    }
+
+    #[test]
+    fn skill_levels_span_range() {
+        assert_eq!(skill_level(Strength::MIN), 0);
+        assert_eq!(skill_level(Strength::MAX), 20);
+    }
+}
@@ -12,6 +12,7 @@ pub mod api {
     pub mod board;
     pub mod github;
     pub mod oauth;
+    pub mod stream;
     pub mod update;
 }
 pub mod app;
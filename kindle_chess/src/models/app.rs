@@ -1,6 +1,15 @@
+use crate::models::chess::ChessApp;
 use crate::models::ui::{Display, Screen};
+use crate::ui::events::AppEvent;
 
 pub struct App {
     pub display: Display,
     pub screen_stack: Vec<Box<dyn Screen>>,
+    // The signed-in account, from the first `ChessReady`. Its event stream
+    // runs from then on; games and challenges it announces are opened with
+    // this.
+    pub account: Option<ChessApp>,
+    // New games and challenges that came in during a game, replayed once
+    // the game is left (see `Screen::plays_game`).
+    pub held: Vec<AppEvent>,
 }
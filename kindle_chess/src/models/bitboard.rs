@@ -5,6 +5,18 @@
 // `s` is `1u64 << s`. This is the "Little-Endian Rank-File" mapping; it lets us
 // shift north/south by ±8 and east/west by ±1.
 
+pub mod movegen;
+pub mod outcome;
+pub mod perft;
+pub mod san;
+pub mod variant;
+pub mod zobrist;
+
+pub use movegen::{Move, MoveKind};
+pub use outcome::Outcome;
+pub use san::{parse_san, to_san};
+pub use variant::Variant;
+
 use log::warn;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -32,12 +44,170 @@ pub enum Piece {
     King,
 }
 
+impl Piece {
+    /// All piece types in `idx_piece` order.
+    pub const ALL: [Piece; 6] = [
+        Piece::Pawn,
+        Piece::Knight,
+        Piece::Bishop,
+        Piece::Rook,
+        Piece::Queen,
+        Piece::King,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CastleSide {
+    King,
+    Queen,
+}
+
+// Castling availability as four flag bits (white O-O, white O-O-O, black O-O,
+// black O-O-O), plus the file each right's rook starts on. The files are the
+// standard h/a unless a Chess960 FEN says otherwise. Rights are only ever
+// removed during a game, never regained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CastlingRights {
+    flags: u8,
+    rook_files: [u8; 4],
+}
+
+impl Default for CastlingRights {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+impl CastlingRights {
+    pub const NONE: CastlingRights = CastlingRights {
+        flags: 0,
+        rook_files: [7, 0, 7, 0],
+    };
+    pub const ALL: CastlingRights = CastlingRights {
+        flags: 0b1111,
+        rook_files: [7, 0, 7, 0],
+    };
+
+    // Bit position of a right within `flags`, also its `rook_files` index.
+    const fn index(color: Color, side: CastleSide) -> usize {
+        match (color, side) {
+            (Color::White, CastleSide::King) => 0,
+            (Color::White, CastleSide::Queen) => 1,
+            (Color::Black, CastleSide::King) => 2,
+            (Color::Black, CastleSide::Queen) => 3,
+        }
+    }
+
+    const fn flag(color: Color, side: CastleSide) -> u8 {
+        1 << Self::index(color, side)
+    }
+
+    pub fn has(self, color: Color, side: CastleSide) -> bool {
+        self.flags & Self::flag(color, side) != 0
+    }
+
+    pub fn add(&mut self, color: Color, side: CastleSide) {
+        self.flags |= Self::flag(color, side);
+    }
+
+    /// Grant a right whose rook starts on `file` (Chess960).
+    pub fn add_with_rook(&mut self, color: Color, side: CastleSide, file: u8) {
+        self.add(color, side);
+        self.rook_files[Self::index(color, side)] = file;
+    }
+
+    pub fn remove(&mut self, color: Color, side: CastleSide) {
+        self.flags &= !Self::flag(color, side);
+    }
+
+    pub fn remove_color(&mut self, color: Color) {
+        self.remove(color, CastleSide::King);
+        self.remove(color, CastleSide::Queen);
+    }
+
+    /// The flag bits alone; the rook files never change within a game, so
+    /// this is all the hash needs.
+    pub fn bits(self) -> u8 {
+        self.flags
+    }
+
+    /// Square the rook starts on for the given right.
+    pub fn rook_square(self, color: Color, side: CastleSide) -> u8 {
+        let rank = match color {
+            Color::White => 0,
+            Color::Black => 7,
+        };
+        square(self.rook_files[Self::index(color, side)], rank)
+    }
+
+    /// Drop any right whose rook starts on `sq`. Called for both the origin
+    /// and destination of every move, so a rook moving away and a rook being
+    /// captured on its home square are handled the same way.
+    fn discard_square(&mut self, sq: u8) {
+        for color in [Color::White, Color::Black] {
+            for side in [CastleSide::King, CastleSide::Queen] {
+                if self.has(color, side) && self.rook_square(color, side) == sq {
+                    self.remove(color, side);
+                }
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Bitboards {
     // [Color][Piece] → bitboard. Indexed via `idx_color`/`idx_piece` so we keep
     // a flat [[u64; 6]; 2] without bringing in a hashmap.
     pub boards: [[u64; 6]; 2],
     pub side_to_move: Color,
+    pub castling: CastlingRights,
+    /// Chess960 rules for move notation: castling is written king-takes-rook
+    /// in UCI ("e1h1") rather than as a two-file king move. The castling
+    /// itself works the same either way. Set by `from_fen` when the castling
+    /// rights can't be standard; callers that know the variant set it too.
+    pub chess960: bool,
+    /// Which rules apply beyond standard chess — see variant.rs. `from_fen`
+    /// picks up Crazyhouse and Three-check from their extra FEN fields;
+    /// everything else has to be set with `set_variant`.
+    pub variant: Variant,
+    /// Crazyhouse pockets, [Color][Piece] for pawn..queen.
+    pub pockets: [[u8; 5]; 2],
+    /// Crazyhouse: squares holding promoted pieces, which go back to the
+    /// capturer's pocket as pawns (the `~` suffix in FEN).
+    pub promoted: u64,
+    /// Three-check: checks given so far by each side.
+    pub checks_given: [u8; 2],
+    /// Square a pawn skipped over on the previous ply (the FEN en-passant
+    /// target), if any. Cleared on every move that isn't a double push.
+    pub ep_square: Option<u8>,
+    /// Plies since the last capture or pawn move (fifty-move rule counter).
+    pub halfmove_clock: u32,
+    /// Starts at 1 and increments after every Black move, as in FEN.
+    pub fullmove_number: u32,
+    /// Zobrist hash of the position, kept current by `set`/`clear` and
+    /// `make_move` — see zobrist.rs.
+    pub hash: u64,
+    /// One entry per move made on this board, newest last. Lets
+    /// `unmake_move` step back without replaying from the start, and doubles
+    /// as the hash history for repetition detection.
+    undo_stack: Vec<Undo>,
+}
+
+// Everything `make_move` overwrites that can't be recomputed from the move
+// itself.
+#[derive(Debug, Clone)]
+struct Undo {
+    mv: Move,
+    captured: Option<Piece>,
+    castling: CastlingRights,
+    ep_square: Option<u8>,
+    halfmove_clock: u32,
+    hash: u64,
+    pockets: [[u8; 5]; 2],
+    promoted: u64,
+    checks_given: [u8; 2],
+    // Atomic: pieces removed by the explosion, besides the captured one.
+    exploded: Vec<(Color, Piece, u8)>,
 }
 
 const fn idx_color(c: Color) -> usize {
@@ -62,6 +232,17 @@ pub fn square(file: u8, rank: u8) -> u8 {
     rank * 8 + file
 }
 
+// Classical piece values, for the material count shown next to the players.
+fn material_value(piece: Piece) -> i32 {
+    match piece {
+        Piece::Pawn => 1,
+        Piece::Knight | Piece::Bishop => 3,
+        Piece::Rook => 5,
+        Piece::Queen => 9,
+        Piece::King => 0,
+    }
+}
+
 pub fn bit(sq: u8) -> u64 {
     1u64 << sq
 }
@@ -71,12 +252,23 @@ impl Bitboards {
         Self {
             boards: [[0u64; 6]; 2],
             side_to_move: Color::White,
+            castling: CastlingRights::NONE,
+            chess960: false,
+            variant: Variant::Standard,
+            pockets: [[0; 5]; 2],
+            promoted: 0,
+            checks_given: [0; 2],
+            ep_square: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            hash: 0,
+            undo_stack: Vec::new(),
         }
     }
 
     pub fn starting_position() -> Self {
         // Standard FEN — `from_fen` is the source of truth so we don't drift.
-        Self::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1")
+        Self::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")
             .expect("starting FEN must parse")
     }
 
@@ -84,14 +276,7 @@ impl Bitboards {
     pub fn piece_at(&self, sq: u8) -> Option<(Color, Piece)> {
         let mask = bit(sq);
         for &color in &[Color::White, Color::Black] {
-            for &piece in &[
-                Piece::Pawn,
-                Piece::Knight,
-                Piece::Bishop,
-                Piece::Rook,
-                Piece::Queen,
-                Piece::King,
-            ] {
+            for &piece in &Piece::ALL {
                 if self.boards[idx_color(color)][idx_piece(piece)] & mask != 0 {
                     return Some((color, piece));
                 }
@@ -104,34 +289,76 @@ impl Bitboards {
         self.boards[idx_color(color)][idx_piece(piece)]
     }
 
+    // `set` and `clear` keep `hash` in sync, and only touch it when the bit
+    // actually changes so a stray call on malformed server input can't
+    // desynchronise the two.
     fn set(&mut self, color: Color, piece: Piece, sq: u8) {
-        self.boards[idx_color(color)][idx_piece(piece)] |= bit(sq);
+        let board = &mut self.boards[idx_color(color)][idx_piece(piece)];
+        if *board & bit(sq) == 0 {
+            *board |= bit(sq);
+            self.hash ^= zobrist::piece_key(color, piece, sq);
+        }
     }
 
     fn clear(&mut self, color: Color, piece: Piece, sq: u8) {
-        self.boards[idx_color(color)][idx_piece(piece)] &= !bit(sq);
+        let board = &mut self.boards[idx_color(color)][idx_piece(piece)];
+        if *board & bit(sq) != 0 {
+            *board &= !bit(sq);
+            self.hash ^= zobrist::piece_key(color, piece, sq);
+        }
     }
 
     /// Clear whatever piece happens to occupy `sq` from any board. Used by
     /// captures where we don't know the captured piece type up front.
     fn clear_any(&mut self, sq: u8) {
-        let mask = !bit(sq);
+        if let Some((color, piece)) = self.piece_at(sq) {
+            self.clear(color, piece, sq);
+        }
+    }
+
+    /// Squares whose contents differ between `self` and `other`.
+    pub fn diff(&self, other: &Bitboards) -> u64 {
+        if self.hash == other.hash {
+            return 0;
+        }
+        let mut changed = 0u64;
         for c in 0..2 {
             for p in 0..6 {
-                self.boards[c][p] &= mask;
+                changed |= self.boards[c][p] ^ other.boards[c][p];
             }
         }
+        changed
     }
 
-    /// Parse the position field of a FEN string. Only the position + side-to-move
-    /// fields are honoured — castling rights / en passant / clocks are ignored
-    /// because the board widget only needs the layout. `from_fen` always sets
-    /// `side_to_move` from the FEN (defaulting to White if absent) so that
-    /// `apply_uci_moves` knows whose pawn is moving on the first ply.
+    /// Parse all six FEN fields. Missing trailing fields fall back to White
+    /// to move, no castling, no en-passant square and clocks of `0 1`, so
+    /// bare placement strings (and Lichess' four-field EPD-style FENs) still
+    /// load. Crazyhouse pockets (`[Qp]` or a ninth rank, with `~` marking
+    /// promoted pieces) and three-check counters (`2+3` checks remaining
+    /// after the en-passant field, or `+1+0` checks given at the end) are
+    /// understood too, and switch the variant on. `to_fen` is the inverse.
     pub fn from_fen(fen: &str) -> Result<Self, String> {
-        let mut parts = fen.split_whitespace();
-        let position = parts.next().ok_or_else(|| "empty FEN".to_string())?;
+        let mut fields: Vec<&str> = fen.split_whitespace().collect();
+        let checks = match fields.iter().skip(1).position(|f| f.contains('+')) {
+            Some(i) => Some(parse_checks(fields.remove(i + 1))?),
+            None => None,
+        };
+        let mut parts = fields.into_iter();
+        let placement = parts.next().ok_or_else(|| "empty FEN".to_string())?;
         let side = parts.next().unwrap_or("w");
+        let castling = parts.next().unwrap_or("-");
+        let ep = parts.next().unwrap_or("-");
+        let halfmove = parts.next().unwrap_or("0");
+        let fullmove = parts.next().unwrap_or("1");
+
+        let (position, pocket) = match placement.split_once('[') {
+            Some((position, pocket)) => (position, Some(pocket.trim_end_matches(']'))),
+            None if placement.matches('/').count() == 8 => {
+                let (position, pocket) = placement.rsplit_once('/').unwrap_or_default();
+                (position, Some(pocket))
+            }
+            None => (placement, None),
+        };
 
         let mut bb = Bitboards::empty();
         let ranks: Vec<&str> = position.split('/').collect();
@@ -147,6 +374,10 @@ impl Bitboards {
                     file += skip as u8;
                     continue;
                 }
+                if ch == '~' && file > 0 {
+                    bb.promoted |= bit(square(file - 1, rank));
+                    continue;
+                }
                 if file >= 8 {
                     return Err(format!("FEN rank '{}' overflows 8 files", rank_str));
                 }
@@ -156,6 +387,21 @@ impl Bitboards {
                 file += 1;
             }
         }
+        if let Some(pocket) = pocket {
+            bb.variant = Variant::Crazyhouse;
+            for ch in pocket.chars() {
+                match char_to_piece(ch) {
+                    Some((color, piece)) if piece != Piece::King => {
+                        bb.pockets[idx_color(color)][idx_piece(piece)] += 1;
+                    }
+                    _ => return Err(format!("FEN: bad pocket piece '{}'", ch)),
+                }
+            }
+        }
+        if let Some(checks) = checks {
+            bb.variant = Variant::ThreeCheck;
+            bb.checks_given = checks;
+        }
 
         bb.side_to_move = match side {
             "w" => Color::White,
@@ -163,9 +409,115 @@ impl Bitboards {
             other => return Err(format!("FEN: bad side-to-move '{}'", other)),
         };
 
+        if castling != "-" {
+            for ch in castling.chars() {
+                bb.add_fen_castling(ch)?;
+            }
+        }
+
+        bb.ep_square = match ep {
+            "-" => None,
+            sq => Some(parse_square(sq.as_bytes()).map_err(|e| format!("FEN: {}", e))?),
+        };
+
+        bb.halfmove_clock = halfmove
+            .parse()
+            .map_err(|_| format!("FEN: bad halfmove clock '{}'", halfmove))?;
+        bb.fullmove_number = fullmove
+            .parse()
+            .map_err(|_| format!("FEN: bad fullmove number '{}'", fullmove))?;
+
+        bb.hash = bb.compute_hash();
         Ok(bb)
     }
 
+    /// Serialise the position as a six-field FEN string.
+    pub fn to_fen(&self) -> String {
+        let mut fen = String::with_capacity(90);
+        for rank in (0..8u8).rev() {
+            let mut empty = 0;
+            for file in 0..8u8 {
+                match self.piece_at(square(file, rank)) {
+                    Some((color, piece)) => {
+                        if empty > 0 {
+                            fen.push((b'0' + empty) as char);
+                            empty = 0;
+                        }
+                        fen.push(piece_to_char(color, piece));
+                        if self.promoted & bit(square(file, rank)) != 0 {
+                            fen.push('~');
+                        }
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                fen.push((b'0' + empty) as char);
+            }
+            if rank > 0 {
+                fen.push('/');
+            }
+        }
+        if self.variant == Variant::Crazyhouse {
+            fen.push('[');
+            for color in [Color::White, Color::Black] {
+                for piece in variant::POCKET_PIECES.iter().rev() {
+                    for _ in 0..self.pocket(color, *piece) {
+                        fen.push(piece_to_char(color, *piece));
+                    }
+                }
+            }
+            fen.push(']');
+        }
+
+        fen.push_str(match self.side_to_move {
+            Color::White => " w ",
+            Color::Black => " b ",
+        });
+
+        let mut castling = String::new();
+        for (color, side, ch) in [
+            (Color::White, CastleSide::King, 'K'),
+            (Color::White, CastleSide::Queen, 'Q'),
+            (Color::Black, CastleSide::King, 'k'),
+            (Color::Black, CastleSide::Queen, 'q'),
+        ] {
+            if !self.castling.has(color, side) {
+                continue;
+            }
+            // X-FEN: in Chess960, K/Q name the outermost rook on that side
+            // and any other rook is named by its file, Shredder-style.
+            let rook = self.castling.rook_square(color, side);
+            if !self.chess960 || self.outermost_rook(color, side) == Some(rook) {
+                castling.push(ch);
+            } else {
+                let file = (b'a' + rook % 8) as char;
+                castling.push(match color {
+                    Color::White => file.to_ascii_uppercase(),
+                    Color::Black => file,
+                });
+            }
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+        fen.push_str(&castling);
+
+        fen.push(' ');
+        match self.ep_square {
+            Some(sq) => fen.push_str(&square_name(sq)),
+            None => fen.push('-'),
+        }
+        if self.variant == Variant::ThreeCheck {
+            let [white, black] = self.checks_given;
+            let remaining = |given: u8| 3u8.saturating_sub(given);
+            fen.push_str(&format!(" {}+{}", remaining(white), remaining(black)));
+        }
+
+        fen.push_str(&format!(" {} {}", self.halfmove_clock, self.fullmove_number));
+        fen
+    }
+
     /// Apply each space-separated UCI move to `self`. Moves the side-to-move
     /// after each ply. Unknown / malformed moves are logged and skipped — the
     /// board may briefly be wrong but the next streamed snapshot will reset us
@@ -179,69 +531,434 @@ impl Bitboards {
     }
 
     pub fn apply_uci_move(&mut self, mv: &str) -> Result<(), String> {
+        let mv = self.parse_uci(mv)?;
+        self.make_move(mv);
+        Ok(())
+    }
+
+    /// Turn a UCI string into a `Move` against the current position. The move
+    /// kind is inferred from the board, not checked for legality — this is
+    /// what lets us replay whatever the server sends. Use `legal_moves` (or
+    /// `find_legal`) when the move comes from the user.
+    pub fn parse_uci(&self, mv: &str) -> Result<Move, String> {
         let bytes = mv.as_bytes();
+        // Crazyhouse drop: "N@f3".
+        if bytes.len() == 4 && bytes[1] == b'@' {
+            let (_, piece) = char_to_piece(bytes[0] as char)
+                .filter(|(_, p)| *p != Piece::King)
+                .ok_or_else(|| format!("bad drop piece in '{}'", mv))?;
+            let to = parse_square(&bytes[2..4])?;
+            return Ok(Move {
+                from: to,
+                to,
+                promotion: None,
+                kind: MoveKind::Drop(piece),
+            });
+        }
         if bytes.len() < 4 {
             return Err(format!("UCI move too short: '{}'", mv));
         }
         let from = parse_square(&bytes[0..2])?;
         let to = parse_square(&bytes[2..4])?;
-        let promo = if bytes.len() >= 5 {
+        let promotion = if bytes.len() >= 5 {
             Some(promotion_piece(bytes[4])?)
         } else {
             None
         };
 
-        let (color, piece) = self
+        let (_, piece) = self
             .piece_at(from)
             .ok_or_else(|| format!("no piece on {}", square_name(from)))?;
 
-        // Castling: detect by the king moving exactly two files. Move the rook
-        // alongside; the regular from/to update below handles the king itself.
-        if piece == Piece::King {
-            let from_file = from % 8;
-            let to_file = to % 8;
-            let rank = from / 8;
-            if to_file as i8 - from_file as i8 == 2 {
-                // O-O: rook h→f
-                let rook_from = square(7, rank);
-                let rook_to = square(5, rank);
-                self.clear(color, Piece::Rook, rook_from);
-                self.set(color, Piece::Rook, rook_to);
-            } else if to_file as i8 - from_file as i8 == -2 {
-                // O-O-O: rook a→d
-                let rook_from = square(0, rank);
-                let rook_to = square(3, rank);
+        let file_delta = (to % 8) as i8 - (from % 8) as i8;
+        let rank_delta = (to / 8) as i8 - (from / 8) as i8;
+        let color = self.side_to_move;
+        let kind = match piece {
+            // Castling written king-takes-own-rook, as Lichess sends it for
+            // Chess960. The move's `to` becomes the king's real destination.
+            Piece::King if self.piece_at(to) == Some((color, Piece::Rook)) => {
+                let side = if file_delta > 0 {
+                    CastleSide::King
+                } else {
+                    CastleSide::Queen
+                };
+                return Ok(Move {
+                    from,
+                    to: castle_king_square(color, side),
+                    promotion: None,
+                    kind: MoveKind::Castle(side),
+                });
+            }
+            // Standard castling: the king moves exactly two files. Chess960
+            // only uses the king-takes-rook form above.
+            Piece::King if !self.chess960 && file_delta == 2 => MoveKind::Castle(CastleSide::King),
+            Piece::King if !self.chess960 && file_delta == -2 => {
+                MoveKind::Castle(CastleSide::Queen)
+            }
+            // En passant: a pawn moves diagonally onto an empty square.
+            Piece::Pawn if file_delta != 0 && self.piece_at(to).is_none() => MoveKind::EnPassant,
+            Piece::Pawn if rank_delta.abs() == 2 => MoveKind::DoublePush,
+            _ => MoveKind::Normal,
+        };
+
+        Ok(Move {
+            from,
+            to,
+            promotion,
+            kind,
+        })
+    }
+
+    /// UCI for `mv` in this position (before it is made). Same as
+    /// `Move::to_uci` except for castling in Chess960, which is written as
+    /// the king capturing its own rook.
+    pub fn move_to_uci(&self, mv: Move) -> String {
+        match mv.kind {
+            MoveKind::Castle(side) if self.chess960 => {
+                let rook = self.castling.rook_square(self.side_to_move, side);
+                format!("{}{}", square_name(mv.from), square_name(rook))
+            }
+            _ => mv.to_uci(),
+        }
+    }
+
+    // One character of the FEN castling field: KQkq (X-FEN: outermost rook
+    // on that side) or a rook file letter (Shredder-FEN). Switches on
+    // `chess960` when the result can't be a standard setup.
+    fn add_fen_castling(&mut self, ch: char) -> Result<(), String> {
+        let color = if ch.is_ascii_uppercase() {
+            Color::White
+        } else {
+            Color::Black
+        };
+        let back_rank = match color {
+            Color::White => 0,
+            Color::Black => 7,
+        };
+        let king_file = self
+            .king_square(color)
+            .filter(|k| k / 8 == back_rank)
+            .map_or(4, |k| k % 8);
+        let (side, rook_file) = match ch.to_ascii_lowercase() {
+            'k' => (CastleSide::King, None),
+            'q' => (CastleSide::Queen, None),
+            f @ 'a'..='h' => {
+                let file = f as u8 - b'a';
+                let side = if file > king_file {
+                    CastleSide::King
+                } else {
+                    CastleSide::Queen
+                };
+                (side, Some(file))
+            }
+            _ => return Err(format!("FEN: bad castling char '{}'", ch)),
+        };
+        let rook_file = rook_file
+            .or_else(|| self.outermost_rook(color, side).map(|sq| sq % 8))
+            .unwrap_or(match side {
+                CastleSide::King => 7,
+                CastleSide::Queen => 0,
+            });
+        self.castling.add_with_rook(color, side, rook_file);
+
+        let standard_rook = match side {
+            CastleSide::King => 7,
+            CastleSide::Queen => 0,
+        };
+        if king_file != 4 || rook_file != standard_rook {
+            self.chess960 = true;
+        }
+        Ok(())
+    }
+
+    // The rook of `color` on its back rank furthest from the king towards
+    // `side`, if any.
+    fn outermost_rook(&self, color: Color, side: CastleSide) -> Option<u8> {
+        let back_rank = match color {
+            Color::White => 0,
+            Color::Black => 7,
+        };
+        let king = self.king_square(color).filter(|k| k / 8 == back_rank)?;
+        let rooks = self.board(color, Piece::Rook);
+        let is_rook = |f: &u8| rooks & bit(square(*f, back_rank)) != 0;
+        let file = match side {
+            CastleSide::King => (king % 8 + 1..8).rev().find(is_rook),
+            CastleSide::Queen => (0..king % 8).find(is_rook),
+        };
+        file.map(|f| square(f, back_rank))
+    }
+
+    /// Apply `mv` to the position, updating castling rights, the en-passant
+    /// square, both move clocks, the side to move and the hash, and push an
+    /// undo record so `unmake_move` can take it back. The move is trusted; a
+    /// move whose origin square is empty is ignored.
+    pub fn make_move(&mut self, mv: Move) {
+        let (color, piece) = match (mv.kind, self.piece_at(mv.from)) {
+            (MoveKind::Drop(piece), _) => (self.side_to_move, piece),
+            (_, Some(found)) => found,
+            (_, None) => {
+                warn!("make_move: no piece on {}", square_name(mv.from));
+                return;
+            }
+        };
+        let captured = match mv.kind {
+            MoveKind::EnPassant => Some(Piece::Pawn),
+            MoveKind::Castle(_) | MoveKind::Drop(_) => None,
+            _ => self.piece_at(mv.to).map(|(_, p)| p),
+        };
+        self.undo_stack.push(Undo {
+            mv,
+            captured,
+            castling: self.castling,
+            ep_square: self.ep_square,
+            halfmove_clock: self.halfmove_clock,
+            hash: self.hash,
+            pockets: self.pockets,
+            promoted: self.promoted,
+            checks_given: self.checks_given,
+            exploded: Vec::new(),
+        });
+        // Swap the side/castling/en-passant part of the hash out now and the
+        // new one back in at the end; pieces are handled by set/clear.
+        self.hash ^= self.state_key();
+
+        if self.variant == Variant::Crazyhouse {
+            self.crazyhouse_move(mv, color, captured);
+        }
+
+        match mv.kind {
+            // Move the rook alongside; the regular from/to update below
+            // handles the king itself.
+            MoveKind::Castle(side) => {
+                let (rook_from, rook_to) = castle_rook_squares(self.castling, color, side);
                 self.clear(color, Piece::Rook, rook_from);
                 self.set(color, Piece::Rook, rook_to);
             }
+            MoveKind::EnPassant => {
+                self.clear(color.flip(), Piece::Pawn, en_passant_victim(mv));
+            }
+            MoveKind::Normal | MoveKind::DoublePush | MoveKind::Drop(_) => {}
         }
 
-        // En passant: a pawn moves diagonally onto an empty square. The captured
-        // pawn is on the same file as `to` but one rank back (relative to the
-        // moving side). Detected before we clear `to` so we don't mistake a
-        // normal capture for EP.
-        if piece == Piece::Pawn && (from % 8) != (to % 8) && self.piece_at(to).is_none() {
-            let captured_rank = if color == Color::White {
-                to / 8 - 1
-            } else {
-                to / 8 + 1
-            };
-            let captured_sq = square(to % 8, captured_rank);
-            self.clear(color.flip(), Piece::Pawn, captured_sq);
+        // Normal move/capture: vacate `from`, blank `to` (handles all
+        // captures), then place the piece (or its promotion). A drop has
+        // `from == to` on an empty square, so only the `set` does anything.
+        self.clear(color, piece, mv.from);
+        self.clear_any(mv.to);
+        self.set(color, mv.promotion.unwrap_or(piece), mv.to);
+        if self.variant == Variant::Atomic && captured.is_some() {
+            let exploded = self.explode(mv.to);
+            if let Some(undo) = self.undo_stack.last_mut() {
+                undo.exploded = exploded;
+            }
+        }
+
+        if piece == Piece::King {
+            self.castling.remove_color(color);
         }
+        self.castling.discard_square(mv.from);
+        self.castling.discard_square(mv.to);
 
-        // Normal move/capture: vacate `from`, blank `to` (handles all captures
-        // including EP-mismatch above), then place the piece (or its promotion).
-        self.clear(color, piece, from);
-        self.clear_any(to);
-        let placed = promo.unwrap_or(piece);
-        self.set(color, placed, to);
+        self.ep_square = match mv.kind {
+            MoveKind::DoublePush => Some((mv.from + mv.to) / 2),
+            _ => None,
+        };
+
+        if piece == Piece::Pawn || captured.is_some() {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if color == Color::Black {
+            self.fullmove_number += 1;
+        }
 
         self.side_to_move = self.side_to_move.flip();
-        Ok(())
+        if self.variant == Variant::ThreeCheck && self.in_check() {
+            self.checks_given[idx_color(color)] += 1;
+        }
+        self.hash ^= self.state_key();
+    }
+
+    /// Take back the most recent `make_move`, restoring the position exactly
+    /// (hash included). Returns the move that was undone, or `None` when
+    /// there is nothing left to undo.
+    pub fn unmake_move(&mut self) -> Option<Move> {
+        let undo = self.undo_stack.pop()?;
+        let mv = undo.mv;
+        self.side_to_move = self.side_to_move.flip();
+        let color = self.side_to_move;
+        if color == Color::Black {
+            self.fullmove_number -= 1;
+        }
+        self.castling = undo.castling;
+        self.ep_square = undo.ep_square;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.pockets = undo.pockets;
+        self.promoted = undo.promoted;
+        self.checks_given = undo.checks_given;
+
+        // Atomic: rebuild the blast first, which also puts the capturing
+        // piece back on `to` for the regular undo below.
+        for &(owner, piece, sq) in &undo.exploded {
+            self.set(owner, piece, sq);
+        }
+        match mv.kind {
+            MoveKind::Drop(piece) => self.clear(color, piece, mv.to),
+            _ => {
+                if let Some((_, placed)) = self.piece_at(mv.to) {
+                    let piece = if mv.promotion.is_some() {
+                        Piece::Pawn
+                    } else {
+                        placed
+                    };
+                    self.clear(color, placed, mv.to);
+                    self.set(color, piece, mv.from);
+                }
+            }
+        }
+        match mv.kind {
+            MoveKind::Castle(side) => {
+                let (rook_from, rook_to) = castle_rook_squares(self.castling, color, side);
+                self.clear(color, Piece::Rook, rook_to);
+                self.set(color, Piece::Rook, rook_from);
+            }
+            MoveKind::EnPassant => {
+                self.set(color.flip(), Piece::Pawn, en_passant_victim(mv));
+            }
+            MoveKind::Normal | MoveKind::DoublePush | MoveKind::Drop(_) => {
+                if let Some(captured) = undo.captured {
+                    self.set(color.flip(), captured, mv.to);
+                }
+            }
+        }
+
+        self.hash = undo.hash;
+        Some(mv)
+    }
+
+    /// The move that produced this position, if it was made on this board.
+    pub fn last_move(&self) -> Option<Move> {
+        self.undo_stack.last().map(|u| u.mv)
+    }
+
+    /// Number of moves made on this board since it was set up.
+    pub fn ply_count(&self) -> usize {
+        self.undo_stack.len()
+    }
+
+    /// The moves made on this board since it was set up, oldest first.
+    pub fn moves(&self) -> Vec<Move> {
+        self.undo_stack.iter().map(|u| u.mv).collect()
+    }
+
+    /// The position this board was set up with, before any of `moves`.
+    pub fn root_position(&self) -> Bitboards {
+        let mut root = self.clone();
+        while root.unmake_move().is_some() {}
+        root
+    }
+
+    /// Pieces of `color` captured by the moves made on this board (and, in
+    /// atomic, blown up alongside), cheapest first. Read off the undo stack,
+    /// so promotions and crazyhouse drops don't count.
+    pub fn captured(&self, color: Color) -> Vec<Piece> {
+        // The side that made the first move on this board.
+        let mut mover = if self.undo_stack.len().is_multiple_of(2) {
+            self.side_to_move
+        } else {
+            self.side_to_move.flip()
+        };
+        let mut gone = Vec::new();
+        for undo in &self.undo_stack {
+            if mover != color {
+                gone.extend(undo.captured);
+            }
+            gone.extend(
+                undo.exploded
+                    .iter()
+                    .filter(|&&(c, _, _)| c == color)
+                    .map(|&(_, piece, _)| piece),
+            );
+            mover = mover.flip();
+        }
+        gone.sort_by_key(|&piece| idx_piece(piece));
+        gone
+    }
+
+    /// Material on the board in pawns (1/3/3/5/9, kings not counted),
+    /// white's minus black's.
+    pub fn material_balance(&self) -> i32 {
+        let side = |color| -> i32 {
+            Piece::ALL
+                .iter()
+                .map(|&piece| self.board(color, piece).count_ones() as i32 * material_value(piece))
+                .sum()
+        };
+        side(Color::White) - side(Color::Black)
+    }
+
+    /// Bitmask of squares the last move changed: from + to, plus the rook
+    /// squares for a castle, the captured pawn for en passant and anything
+    /// an atomic capture blew up. 0 if no move has been made. Drives the
+    /// board widget's last-move highlight.
+    pub fn last_move_mask(&self) -> u64 {
+        let Some(undo) = self.undo_stack.last() else {
+            return 0;
+        };
+        let mv = undo.mv;
+        let mut mask = bit(mv.from) | bit(mv.to);
+        match mv.kind {
+            MoveKind::Castle(side) => {
+                let color = self.side_to_move.flip();
+                let (rook_from, rook_to) = castle_rook_squares(undo.castling, color, side);
+                mask |= bit(rook_from) | bit(rook_to);
+            }
+            MoveKind::EnPassant => mask |= bit(en_passant_victim(mv)),
+            MoveKind::Normal | MoveKind::DoublePush | MoveKind::Drop(_) => {}
+        }
+        for &(_, _, sq) in &undo.exploded {
+            mask |= bit(sq);
+        }
+        mask
+    }
+
+    /// Hashes of every earlier position of the game, oldest first.
+    pub fn hash_history(&self) -> impl DoubleEndedIterator<Item = u64> + '_ {
+        self.undo_stack.iter().map(|u| u.hash)
+    }
+}
+
+// Rook origin and destination for a castle by `color` on `side`. `rights` are
+// the rights *before* the castle (the rook's origin is looked up there).
+fn castle_rook_squares(rights: CastlingRights, color: Color, side: CastleSide) -> (u8, u8) {
+    let rook_from = rights.rook_square(color, side);
+    let rank = rook_from / 8;
+    let rook_to = match side {
+        CastleSide::King => square(5, rank),
+        CastleSide::Queen => square(3, rank),
+    };
+    (rook_from, rook_to)
+}
+
+// Where the king lands when castling: g- or c-file, whatever the variant.
+fn castle_king_square(color: Color, side: CastleSide) -> u8 {
+    let rank = match color {
+        Color::White => 0,
+        Color::Black => 7,
+    };
+    match side {
+        CastleSide::King => square(6, rank),
+        CastleSide::Queen => square(2, rank),
     }
 }
 
+// Square of the pawn taken by an en-passant capture: same file as `to`, same
+// rank as `from`.
+fn en_passant_victim(mv: Move) -> u8 {
+    square(mv.to % 8, mv.from / 8)
+}
+
 fn char_to_piece(ch: char) -> Option<(Color, Piece)> {
     let color = if ch.is_ascii_uppercase() {
         Color::White
@@ -260,6 +977,14 @@ fn char_to_piece(ch: char) -> Option<(Color, Piece)> {
     Some((color, piece))
 }
 
+fn piece_to_char(color: Color, piece: Piece) -> char {
+    let ch = piece_char(piece);
+    match color {
+        Color::White => ch.to_ascii_uppercase(),
+        Color::Black => ch,
+    }
+}
+
 fn parse_square(b: &[u8]) -> Result<u8, String> {
     if b.len() != 2 {
         return Err(format!("bad square len {}", b.len()));
@@ -275,16 +1000,50 @@ fn parse_square(b: &[u8]) -> Result<u8, String> {
     Ok(square(file - b'a', rank - b'1'))
 }
 
+fn piece_char(p: Piece) -> char {
+    match p {
+        Piece::Pawn => 'p',
+        Piece::Knight => 'n',
+        Piece::Bishop => 'b',
+        Piece::Rook => 'r',
+        Piece::Queen => 'q',
+        Piece::King => 'k',
+    }
+}
+
 fn promotion_piece(b: u8) -> Result<Piece, String> {
     match b {
         b'q' => Ok(Piece::Queen),
         b'r' => Ok(Piece::Rook),
         b'b' => Ok(Piece::Bishop),
         b'n' => Ok(Piece::Knight),
+        // Antichess only.
+        b'k' => Ok(Piece::King),
         other => Err(format!("bad promotion '{}'", other as char)),
     }
 }
 
+// A three-check FEN counter: "2+3" is checks still needed by white and
+// black, "+1+0" is checks already given. Returns checks given.
+fn parse_checks(field: &str) -> Result<[u8; 2], String> {
+    let bad = || format!("FEN: bad check counter '{}'", field);
+    let (given, counts) = match field.strip_prefix('+') {
+        Some(counts) => (true, counts),
+        None => (false, field),
+    };
+    let (white, black) = counts.split_once('+').ok_or_else(bad)?;
+    let white: u8 = white.parse().map_err(|_| bad())?;
+    let black: u8 = black.parse().map_err(|_| bad())?;
+    if white > 3 || black > 3 {
+        return Err(bad());
+    }
+    Ok(if given {
+        [white, black]
+    } else {
+        [3 - white, 3 - black]
+    })
+}
+
 fn square_name(sq: u8) -> String {
     let file = (b'a' + (sq % 8)) as char;
     let rank = (b'1' + (sq / 8)) as char;
@@ -342,4 +1101,149 @@ mod tests {
         bb.apply_uci_move("a7a8q").unwrap();
         assert_eq!(bb.piece_at(square(0, 7)), Some((Color::White, Piece::Queen)));
     }
+
+    #[test]
+    fn fen_round_trip() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 b - - 37 52",
+        ] {
+            assert_eq!(Bitboards::from_fen(fen).unwrap().to_fen(), fen);
+        }
+    }
+
+    #[test]
+    fn moves_update_fen_state() {
+        let mut bb = Bitboards::starting_position();
+        bb.apply_uci_moves("e2e4");
+        assert_eq!(
+            bb.to_fen(),
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+        );
+        bb.apply_uci_moves("e7e5 g1f3 b8c6 e1e2");
+        assert_eq!(
+            bb.to_fen(),
+            "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPPKPPP/RNBQ1B1R b kq - 3 3"
+        );
+    }
+
+    #[test]
+    fn rook_capture_removes_castling_right() {
+        let mut bb = Bitboards::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        bb.apply_uci_move("a1a8").unwrap();
+        assert!(!bb.castling.has(Color::White, CastleSide::Queen));
+        assert!(!bb.castling.has(Color::Black, CastleSide::Queen));
+        assert!(bb.castling.has(Color::Black, CastleSide::King));
+        assert_eq!(bb.halfmove_clock, 0);
+    }
+
+    #[test]
+    fn make_unmake_restores_position() {
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        let mut bb = Bitboards::from_fen(fen).unwrap();
+        bb.apply_uci_move("a2a4").unwrap();
+        let before = bb.to_fen();
+        let hash = bb.hash;
+        // Black's replies include en passant, captures and both castles.
+        for mv in bb.legal_moves() {
+            bb.make_move(mv);
+            assert_eq!(bb.hash, bb.compute_hash(), "{}", mv.to_uci());
+            assert_eq!(bb.unmake_move(), Some(mv));
+            assert_eq!(bb.to_fen(), before, "{}", mv.to_uci());
+            assert_eq!(bb.hash, hash);
+        }
+    }
+
+    #[test]
+    fn last_move_mask_covers_castling_rook() {
+        let mut bb = Bitboards::from_fen("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1").unwrap();
+        bb.apply_uci_move("e8c8").unwrap();
+        let expected =
+            bit(square(4, 7)) | bit(square(2, 7)) | bit(square(0, 7)) | bit(square(3, 7));
+        assert_eq!(bb.last_move_mask(), expected);
+    }
+
+    #[test]
+    fn captured_pieces_and_material() {
+        let mut bb = Bitboards::starting_position();
+        assert!(bb.captured(Color::White).is_empty());
+        assert_eq!(bb.material_balance(), 0);
+
+        bb.apply_uci_moves("e2e4 d7d5 e4d5 d8d5 b1c3 d5a2 a1a2");
+        assert_eq!(bb.captured(Color::White), [Piece::Pawn, Piece::Pawn]);
+        assert_eq!(bb.captured(Color::Black), [Piece::Pawn, Piece::Queen]);
+        assert_eq!(bb.material_balance(), 8);
+    }
+
+    #[test]
+    fn captured_pieces_ignore_promotions_and_drops() {
+        let mut bb = Bitboards::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        bb.apply_uci_move("a7a8q").unwrap();
+        assert!(bb.captured(Color::White).is_empty());
+        assert!(bb.captured(Color::Black).is_empty());
+
+        // Each side takes a pawn, then Black drops its pawn back in.
+        let mut bb = Bitboards::variant_start(Variant::Crazyhouse);
+        bb.apply_uci_moves("e2e4 d7d5 e4d5 d8d5 b1c3 P@e6");
+        assert_eq!(bb.ply_count(), 6);
+        assert_eq!(bb.captured(Color::White), [Piece::Pawn]);
+        assert_eq!(bb.captured(Color::Black), [Piece::Pawn]);
+    }
+
+    #[test]
+    fn chess960_castling_rights_in_fen() {
+        // Shredder-FEN file letters come back out as X-FEN KQkq when they
+        // name the outermost rooks.
+        let bb = Bitboards::from_fen(
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+        )
+        .unwrap();
+        assert!(bb.chess960);
+        assert_eq!(bb.castling.rook_square(Color::White, CastleSide::King), square(7, 0));
+        assert_eq!(bb.castling.rook_square(Color::Black, CastleSide::Queen), square(5, 7));
+        assert_eq!(
+            bb.to_fen(),
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9"
+        );
+        assert_eq!(Bitboards::from_fen(&bb.to_fen()).unwrap().castling, bb.castling);
+
+        // An inner rook has to keep its file letter.
+        let bb = Bitboards::from_fen("4k3/8/8/8/8/8/8/RR1K4 w B - 0 1").unwrap();
+        assert_eq!(bb.castling.rook_square(Color::White, CastleSide::Queen), square(1, 0));
+        assert_eq!(bb.to_fen(), "4k3/8/8/8/8/8/8/RR1K4 w B - 0 1");
+
+        let standard = Bitboards::starting_position();
+        assert!(!standard.chess960);
+    }
+
+    #[test]
+    fn chess960_king_takes_rook_castling() {
+        let fen = "1rk3r1/6p1/8/8/8/8/6P1/1RK3R1 w GBgb - 0 1";
+        let mut bb = Bitboards::from_fen(fen).unwrap();
+        let castles: Vec<String> = bb
+            .legal_moves()
+            .into_iter()
+            .filter(|m| matches!(m.kind, MoveKind::Castle(_)))
+            .map(|m| bb.move_to_uci(m))
+            .collect();
+        assert_eq!(castles, ["c1g1", "c1b1"]);
+
+        bb.apply_uci_moves("c1g1 c8b8");
+        assert_eq!(bb.to_fen(), "2kr2r1/6p1/8/8/8/8/6P1/1R3RK1 w - - 2 2");
+        let expected = bit(square(2, 7)) | bit(square(1, 7)) | bit(square(3, 7));
+        assert_eq!(bb.last_move_mask(), expected);
+        assert_eq!(bb.hash, bb.compute_hash());
+
+        bb.unmake_move();
+        bb.unmake_move();
+        assert_eq!(bb.to_fen(), "1rk3r1/6p1/8/8/8/8/6P1/1RK3R1 w KQkq - 0 1");
+
+        // Standard games accept the king-takes-rook spelling too.
+        let mut bb = Bitboards::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        assert_eq!(bb.find_legal("e1h1"), bb.find_legal("e1g1"));
+        bb.apply_uci_move("e1h1").unwrap();
+        assert_eq!(bb.to_fen(), "r3k2r/8/8/8/8/8/8/R4RK1 b kq - 1 1");
+    }
 }
@@ -0,0 +1,572 @@
+// Legal move generation on top of the 12-bitboard layout.
+//
+// Pseudo-legal moves are produced per piece type from precomputed leaper
+// tables (knight, king) and ray-walked slider attacks, then filtered against
+// the side-to-move's king in the same pass: in double check only king moves
+// survive, in single check a move must capture or block the checker, and a
+// pinned piece may only slide along its pin ray. En passant is the odd one
+// out — it removes two pieces from the same rank, so it is verified by
+// re-testing the king against the post-capture occupancy.
+
+use super::{Bitboards, CastleSide, Color, Piece, Variant, bit, piece_char, square, square_name};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MoveKind {
+    Normal,
+    DoublePush,
+    EnPassant,
+    Castle(CastleSide),
+    /// Crazyhouse: a piece from the pocket put down on `to` (`from == to`).
+    Drop(Piece),
+}
+
+/// A move in board coordinates. For castling `to` is the king's destination
+/// square (g1/c1/g8/c8), matching standard UCI, in Chess960 too; use
+/// `Bitboards::move_to_uci` to get the king-takes-rook form there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub from: u8,
+    pub to: u8,
+    pub promotion: Option<Piece>,
+    pub kind: MoveKind,
+}
+
+impl Move {
+    pub fn to_uci(&self) -> String {
+        if let MoveKind::Drop(piece) = self.kind {
+            let letter = piece_char(piece).to_ascii_uppercase();
+            return format!("{}@{}", letter, square_name(self.to));
+        }
+        let mut uci = format!("{}{}", square_name(self.from), square_name(self.to));
+        if let Some(p) = self.promotion {
+            uci.push(piece_char(p));
+        }
+        uci
+    }
+}
+
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+const FILE_H: u64 = FILE_A << 7;
+
+const ROOK_DIRS: [(i8, i8); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const BISHOP_DIRS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+const KNIGHT_ATTACKS: [u64; 64] = leaper_table(&[
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+]);
+const KING_ATTACKS: [u64; 64] = leaper_table(&[
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+]);
+
+const PROMOTION_PIECES: [Piece; 4] = [Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight];
+const ANTICHESS_PROMOTION_PIECES: [Piece; 5] = [
+    Piece::Queen,
+    Piece::Rook,
+    Piece::Bishop,
+    Piece::Knight,
+    Piece::King,
+];
+
+// Build a 64-entry attack table for a piece that jumps by fixed (file, rank)
+// offsets. Evaluated at compile time.
+const fn leaper_table(deltas: &[(i8, i8)]) -> [u64; 64] {
+    let mut table = [0u64; 64];
+    let mut sq = 0;
+    while sq < 64 {
+        let file = (sq % 8) as i8;
+        let rank = (sq / 8) as i8;
+        let mut i = 0;
+        while i < deltas.len() {
+            let f = file + deltas[i].0;
+            let r = rank + deltas[i].1;
+            if f >= 0 && f < 8 && r >= 0 && r < 8 {
+                table[sq] |= 1u64 << (r * 8 + f);
+            }
+            i += 1;
+        }
+        sq += 1;
+    }
+    table
+}
+
+// Walk each direction from `sq` until the edge of the board or the first
+// occupied square (which is included — it may be a capture).
+fn ray_attacks(sq: u8, occ: u64, dirs: &[(i8, i8)]) -> u64 {
+    let mut attacks = 0u64;
+    for &(df, dr) in dirs {
+        let mut f = (sq % 8) as i8 + df;
+        let mut r = (sq / 8) as i8 + dr;
+        while (0..8).contains(&f) && (0..8).contains(&r) {
+            let b = 1u64 << (r * 8 + f);
+            attacks |= b;
+            if occ & b != 0 {
+                break;
+            }
+            f += df;
+            r += dr;
+        }
+    }
+    attacks
+}
+
+pub fn knight_attacks(sq: u8) -> u64 {
+    KNIGHT_ATTACKS[sq as usize]
+}
+
+pub fn king_attacks(sq: u8) -> u64 {
+    KING_ATTACKS[sq as usize]
+}
+
+pub fn rook_attacks(sq: u8, occ: u64) -> u64 {
+    ray_attacks(sq, occ, &ROOK_DIRS)
+}
+
+pub fn bishop_attacks(sq: u8, occ: u64) -> u64 {
+    ray_attacks(sq, occ, &BISHOP_DIRS)
+}
+
+/// Squares a pawn of `color` standing on `sq` attacks.
+pub fn pawn_attacks(color: Color, sq: u8) -> u64 {
+    let b = bit(sq);
+    match color {
+        Color::White => ((b << 7) & !FILE_H) | ((b << 9) & !FILE_A),
+        Color::Black => ((b >> 9) & !FILE_H) | ((b >> 7) & !FILE_A),
+    }
+}
+
+/// Squares strictly between `a` and `b` when they share a rank, file or
+/// diagonal; 0 otherwise.
+pub fn between(a: u8, b: u8) -> u64 {
+    let (fa, ra) = ((a % 8) as i8, (a / 8) as i8);
+    let (fb, rb) = ((b % 8) as i8, (b / 8) as i8);
+    let (df, dr) = (fb - fa, rb - ra);
+    if a == b || !(df == 0 || dr == 0 || df.abs() == dr.abs()) {
+        return 0;
+    }
+    let (sf, sr) = (df.signum(), dr.signum());
+    let mut mask = 0u64;
+    let (mut f, mut r) = (fa + sf, ra + sr);
+    while (f, r) != (fb, rb) {
+        mask |= 1u64 << (r * 8 + f);
+        f += sf;
+        r += sr;
+    }
+    mask
+}
+
+/// Iterate the set squares of a bitboard, lowest first.
+pub fn squares(mut bb: u64) -> impl Iterator<Item = u8> {
+    std::iter::from_fn(move || {
+        if bb == 0 {
+            return None;
+        }
+        let sq = bb.trailing_zeros() as u8;
+        bb &= bb - 1;
+        Some(sq)
+    })
+}
+
+impl Bitboards {
+    pub fn occupancy(&self, color: Color) -> u64 {
+        Piece::ALL
+            .iter()
+            .fold(0, |acc, &p| acc | self.board(color, p))
+    }
+
+    pub fn occupied(&self) -> u64 {
+        self.occupancy(Color::White) | self.occupancy(Color::Black)
+    }
+
+    pub fn king_square(&self, color: Color) -> Option<u8> {
+        let kings = self.board(color, Piece::King);
+        (kings != 0).then(|| kings.trailing_zeros() as u8)
+    }
+
+    /// Pieces of color `by` attacking `sq`, with sliders blocked by `occ`.
+    /// Taking the occupancy as a parameter lets callers ask "would this
+    /// square be attacked once X has moved" without mutating the board.
+    pub fn attackers_to(&self, sq: u8, by: Color, occ: u64) -> u64 {
+        let diagonal = self.board(by, Piece::Bishop) | self.board(by, Piece::Queen);
+        let straight = self.board(by, Piece::Rook) | self.board(by, Piece::Queen);
+        (pawn_attacks(by.flip(), sq) & self.board(by, Piece::Pawn))
+            | (knight_attacks(sq) & self.board(by, Piece::Knight))
+            | (king_attacks(sq) & self.board(by, Piece::King))
+            | (bishop_attacks(sq, occ) & diagonal)
+            | (rook_attacks(sq, occ) & straight)
+    }
+
+    pub fn is_attacked(&self, sq: u8, by: Color) -> bool {
+        self.attackers_to(sq, by, self.occupied()) != 0
+    }
+
+    /// Enemy pieces currently giving check to the side to move.
+    pub fn checkers(&self) -> u64 {
+        let us = self.side_to_move;
+        let Some(k) = self.king_square(us) else {
+            return 0;
+        };
+        let attackers = self.attackers_to(k, us.flip(), self.occupied());
+        match self.variant {
+            // No such thing as check when the king is an ordinary piece.
+            Variant::Antichess => 0,
+            // Kings can't capture (they'd explode), and touching kings shield
+            // each other: any capture next to one blows up both.
+            Variant::Atomic => {
+                if king_attacks(k) & self.board(us.flip(), Piece::King) != 0 {
+                    0
+                } else {
+                    attackers & !self.board(us.flip(), Piece::King)
+                }
+            }
+            _ => attackers,
+        }
+    }
+
+    pub fn in_check(&self) -> bool {
+        self.checkers() != 0
+    }
+
+    /// Look up the legal move matching `uci`, if there is one. Castling is
+    /// accepted both as a two-file king move and as king-takes-rook.
+    pub fn find_legal(&self, uci: &str) -> Option<Move> {
+        let mv = self.parse_uci(uci).ok()?;
+        self.legal_moves().into_iter().find(|&m| m == mv)
+    }
+
+    pub fn is_legal(&self, mv: Move) -> bool {
+        self.legal_moves().contains(&mv)
+    }
+
+    /// Every legal move for `side_to_move`, under the board's variant rules.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        match self.variant {
+            Variant::Standard | Variant::Chess960 | Variant::FromPosition => self.generate(false),
+            _ => self.variant_legal_moves(),
+        }
+    }
+
+    // Standard legal moves, or with `pseudo` set, pseudo-legal ones: king
+    // safety ignored and every king moving like any other piece. Variants
+    // whose legality isn't "don't leave your king in check" (atomic,
+    // antichess) start from the pseudo-legal list and filter it themselves.
+    pub(super) fn generate(&self, pseudo: bool) -> Vec<Move> {
+        let us = self.side_to_move;
+        let them = us.flip();
+        let own = self.occupancy(us);
+        let enemy = self.occupancy(them);
+        let occ = own | enemy;
+        let king = self.king_square(us);
+        let checkers = self.checkers();
+
+        // Squares a non-king move may land on: anywhere when not in check,
+        // capture-or-block when in single check, nowhere in double check.
+        let evasion = match (king, checkers.count_ones()) {
+            (_, 0) => !0u64,
+            _ if pseudo => !0u64,
+            (Some(k), 1) => between(k, checkers.trailing_zeros() as u8) | checkers,
+            _ => 0,
+        };
+        let pins = match king {
+            Some(k) if !pseudo => self.pins(k),
+            _ => Vec::new(),
+        };
+        let allowed = |from: u8| -> u64 {
+            pins.iter()
+                .find(|(sq, _)| *sq == from)
+                .map_or(evasion, |(_, ray)| evasion & ray)
+        };
+
+        let mut moves = Vec::with_capacity(48);
+
+        // Pawns. Horde's white pawns may also double-push from the first
+        // rank; antichess lets them promote to a king.
+        let (push, start_rank, promo_rank): (i8, u8, u8) = match us {
+            Color::White => (8, 1, 7),
+            Color::Black => (-8, 6, 0),
+        };
+        let horde_pawns = self.variant == Variant::Horde && us == Color::White;
+        let promotions: &[Piece] = if self.variant == Variant::Antichess {
+            &ANTICHESS_PROMOTION_PIECES
+        } else {
+            &PROMOTION_PIECES
+        };
+        for from in squares(self.board(us, Piece::Pawn)) {
+            let mask = allowed(from);
+            let one = from as i8 + push;
+            if (0..64).contains(&one) && occ & bit(one as u8) == 0 {
+                let one = one as u8;
+                if mask & bit(one) != 0 {
+                    push_pawn_move(&mut moves, from, one, promo_rank, promotions);
+                }
+                if from / 8 == start_rank || (horde_pawns && from / 8 == 0) {
+                    let two = (one as i8 + push) as u8;
+                    if occ & bit(two) == 0 && mask & bit(two) != 0 {
+                        moves.push(Move {
+                            from,
+                            to: two,
+                            promotion: None,
+                            kind: MoveKind::DoublePush,
+                        });
+                    }
+                }
+            }
+            for to in squares(pawn_attacks(us, from) & enemy & mask) {
+                push_pawn_move(&mut moves, from, to, promo_rank, promotions);
+            }
+            if let Some(ep) = self.ep_square
+                && pawn_attacks(us, from) & bit(ep) != 0
+                && if pseudo {
+                    self.board(them, Piece::Pawn) & bit(square(ep % 8, from / 8)) != 0
+                } else {
+                    self.en_passant_is_legal(from, ep)
+                }
+            {
+                moves.push(Move {
+                    from,
+                    to: ep,
+                    promotion: None,
+                    kind: MoveKind::EnPassant,
+                });
+            }
+        }
+
+        // Knights and sliders.
+        for piece in [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen] {
+            for from in squares(self.board(us, piece)) {
+                let attacks = match piece {
+                    Piece::Knight => knight_attacks(from),
+                    Piece::Bishop => bishop_attacks(from, occ),
+                    Piece::Rook => rook_attacks(from, occ),
+                    _ => bishop_attacks(from, occ) | rook_attacks(from, occ),
+                };
+                for to in squares(attacks & !own & allowed(from)) {
+                    moves.push(Move {
+                        from,
+                        to,
+                        promotion: None,
+                        kind: MoveKind::Normal,
+                    });
+                }
+            }
+        }
+
+        // King. Test destinations with the king lifted off the board so a
+        // slider checking along a line can't be "escaped" by stepping back
+        // along that same line.
+        if pseudo {
+            for k in squares(self.board(us, Piece::King)) {
+                for to in squares(king_attacks(k) & !own) {
+                    moves.push(Move {
+                        from: k,
+                        to,
+                        promotion: None,
+                        kind: MoveKind::Normal,
+                    });
+                }
+            }
+        }
+        if let Some(k) = king.filter(|_| !pseudo) {
+            let occ_without_king = occ & !bit(k);
+            for to in squares(king_attacks(k) & !own) {
+                if self.attackers_to(to, them, occ_without_king) == 0 {
+                    moves.push(Move {
+                        from: k,
+                        to,
+                        promotion: None,
+                        kind: MoveKind::Normal,
+                    });
+                }
+            }
+        }
+        if let Some(k) = king
+            && checkers == 0
+        {
+            for side in [CastleSide::King, CastleSide::Queen] {
+                if let Some(mv) = self.castle_move(k, side) {
+                    moves.push(mv);
+                }
+            }
+        }
+
+        moves
+    }
+
+    // (pinned square, squares it may still move to) for every piece of the
+    // side to move that is absolutely pinned to its king on `ksq`.
+    fn pins(&self, ksq: u8) -> Vec<(u8, u64)> {
+        let us = self.side_to_move;
+        let them = us.flip();
+        let own = self.occupancy(us);
+        let enemy = self.occupancy(them);
+        let diagonal = self.board(them, Piece::Bishop) | self.board(them, Piece::Queen);
+        let straight = self.board(them, Piece::Rook) | self.board(them, Piece::Queen);
+
+        // Cast rays from the king through our own pieces to find enemy
+        // sliders that would be attacking it if exactly one of ours moved.
+        let snipers =
+            (bishop_attacks(ksq, enemy) & diagonal) | (rook_attacks(ksq, enemy) & straight);
+        let mut pins = Vec::new();
+        for sniper in squares(snipers) {
+            let ray = between(ksq, sniper);
+            let blockers = ray & (own | enemy);
+            if blockers.count_ones() == 1 && blockers & own != 0 {
+                pins.push((blockers.trailing_zeros() as u8, ray | bit(sniper)));
+            }
+        }
+        pins
+    }
+
+    // En passant takes two pieces off one rank at once, which the pin logic
+    // can't see. Re-check the king against the post-capture occupancy.
+    pub(super) fn en_passant_is_legal(&self, from: u8, ep: u8) -> bool {
+        let us = self.side_to_move;
+        let them = us.flip();
+        let captured = square(ep % 8, from / 8);
+        if self.board(them, Piece::Pawn) & bit(captured) == 0 {
+            return false;
+        }
+        let Some(k) = self.king_square(us) else {
+            return true;
+        };
+        let occ = (self.occupied() & !bit(from) & !bit(captured)) | bit(ep);
+        self.attackers_to(k, them, occ) & !bit(captured) == 0
+    }
+
+    // The castling move for `side`, if rights, occupancy and attacks allow
+    // it. Only called when the king is not in check.
+    fn castle_move(&self, ksq: u8, side: CastleSide) -> Option<Move> {
+        let us = self.side_to_move;
+        if !self.castling.has(us, side) {
+            return None;
+        }
+        let rook_sq = self.castling.rook_square(us, side);
+        if self.board(us, Piece::Rook) & bit(rook_sq) == 0 {
+            return None;
+        }
+        let rank = ksq / 8;
+        let (king_to, rook_to) = match side {
+            CastleSide::King => (square(6, rank), square(5, rank)),
+            CastleSide::Queen => (square(2, rank), square(3, rank)),
+        };
+
+        // Every square either piece crosses or lands on must be empty apart
+        // from the king and rook themselves.
+        let travel =
+            between(ksq, king_to) | bit(king_to) | between(rook_sq, rook_to) | bit(rook_to);
+        let occ = self.occupied() & !bit(ksq) & !bit(rook_sq);
+        if travel & occ != 0 {
+            return None;
+        }
+
+        // The king may not pass through or land on an attacked square.
+        let king_path = between(ksq, king_to) | bit(king_to);
+        if squares(king_path).any(|sq| self.attackers_to(sq, us.flip(), occ) != 0) {
+            return None;
+        }
+
+        Some(Move {
+            from: ksq,
+            to: king_to,
+            promotion: None,
+            kind: MoveKind::Castle(side),
+        })
+    }
+}
+
+fn push_pawn_move(moves: &mut Vec<Move>, from: u8, to: u8, promo_rank: u8, promotions: &[Piece]) {
+    if to / 8 == promo_rank {
+        for &piece in promotions {
+            moves.push(Move {
+                from,
+                to,
+                promotion: Some(piece),
+                kind: MoveKind::Normal,
+            });
+        }
+    } else {
+        moves.push(Move {
+            from,
+            to,
+            promotion: None,
+            kind: MoveKind::Normal,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uci_set(bb: &Bitboards) -> Vec<String> {
+        let mut v: Vec<String> = bb.legal_moves().iter().map(Move::to_uci).collect();
+        v.sort();
+        v
+    }
+
+    #[test]
+    fn starting_position_has_twenty_moves() {
+        assert_eq!(Bitboards::starting_position().legal_moves().len(), 20);
+    }
+
+    #[test]
+    fn pinned_piece_stays_on_pin_ray() {
+        // White rook on e2 pinned by the e8 rook: only moves along the e-file.
+        let bb = Bitboards::from_fen("4r1k1/8/8/8/8/8/4R3/4K3 w - - 0 1").unwrap();
+        let rook_moves: Vec<String> = uci_set(&bb)
+            .into_iter()
+            .filter(|m| m.starts_with("e2"))
+            .collect();
+        assert_eq!(
+            rook_moves,
+            vec!["e2e3", "e2e4", "e2e5", "e2e6", "e2e7", "e2e8"]
+        );
+    }
+
+    #[test]
+    fn double_check_allows_only_king_moves() {
+        let bb = Bitboards::from_fen("4r1k1/8/8/8/8/3n4/8/R3K3 w - - 0 1").unwrap();
+        let moves = bb.legal_moves();
+        assert!(!moves.is_empty());
+        assert!(moves.iter().all(|m| m.from == square(4, 0)));
+    }
+
+    #[test]
+    fn castling_blocked_through_attacked_square() {
+        // Black rook on f8 covers f1: O-O is illegal, O-O-O is fine.
+        let bb = Bitboards::from_fen("5rk1/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+        let moves = uci_set(&bb);
+        assert!(!moves.contains(&"e1g1".to_string()));
+        assert!(moves.contains(&"e1c1".to_string()));
+    }
+
+    #[test]
+    fn en_passant_horizontal_pin() {
+        // Capturing d6 would expose the a5 king to the h5 rook.
+        let bb = Bitboards::from_fen("8/8/8/K2pP2r/8/8/8/7k w - d6 0 1").unwrap();
+        assert!(!uci_set(&bb).contains(&"e5d6".to_string()));
+    }
+
+    #[test]
+    fn promotions_generate_all_four_pieces() {
+        let bb = Bitboards::from_fen("8/P6k/8/8/8/8/8/K7 w - - 0 1").unwrap();
+        let promos: Vec<String> = uci_set(&bb)
+            .into_iter()
+            .filter(|m| m.starts_with("a7"))
+            .collect();
+        assert_eq!(promos, vec!["a7a8b", "a7a8n", "a7a8q", "a7a8r"]);
+    }
+}
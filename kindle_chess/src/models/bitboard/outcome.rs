@@ -0,0 +1,197 @@
+// Game-end detection for positions we evaluate ourselves (offline play,
+// analysis) where no server hands us a status string. Everything is derived
+// from the current `Bitboards`; repetitions come from the hash history its
+// undo stack carries, so the board must have been reached via `make_move`
+// (or `apply_uci_moves`) for threefold repetition to be seen.
+
+use super::movegen::{pawn_attacks, squares};
+use super::{Bitboards, Color, Piece, bit};
+
+/// Why a game ended. Checkmate and the variant-specific endings are the
+/// decisive results; everything else is a draw.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Checkmate {
+        winner: Color,
+    },
+    /// A variant's own rule ended the game (three checks, king on the hill,
+    /// explosion...). Racing Kings can end this way drawn.
+    VariantEnd {
+        winner: Option<Color>,
+        reason: &'static str,
+    },
+    Stalemate,
+    InsufficientMaterial,
+    FiftyMoveRule,
+    ThreefoldRepetition,
+}
+
+impl Outcome {
+    pub fn winner(&self) -> Option<Color> {
+        match self {
+            Outcome::Checkmate { winner } => Some(*winner),
+            Outcome::VariantEnd { winner, .. } => *winner,
+            _ => None,
+        }
+    }
+
+    /// Short human-readable reason, for the sidebar status line.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Outcome::Checkmate { .. } => "Checkmate",
+            Outcome::VariantEnd { reason, .. } => reason,
+            Outcome::Stalemate => "Stalemate",
+            Outcome::InsufficientMaterial => "Insufficient material",
+            Outcome::FiftyMoveRule => "Fifty-move rule",
+            Outcome::ThreefoldRepetition => "Threefold repetition",
+        }
+    }
+}
+
+const LIGHT_SQUARES: u64 = 0x55AA_55AA_55AA_55AA;
+
+impl Bitboards {
+    /// The result of the game if it is over in this position, `None` while
+    /// play continues. Variant endings, then mate and stalemate, take
+    /// precedence over the draw rules, so a mating move on ply 100 still
+    /// wins.
+    pub fn outcome(&self) -> Option<Outcome> {
+        if let Some(outcome) = self.variant_outcome() {
+            return Some(outcome);
+        }
+        if self.legal_moves().is_empty() {
+            return Some(if self.in_check() {
+                Outcome::Checkmate {
+                    winner: self.side_to_move.flip(),
+                }
+            } else {
+                Outcome::Stalemate
+            });
+        }
+        if self.variant.has_material_draws() && self.has_insufficient_material() {
+            return Some(Outcome::InsufficientMaterial);
+        }
+        if self.halfmove_clock >= 100 {
+            return Some(Outcome::FiftyMoveRule);
+        }
+        if self.repetitions() >= 2 {
+            return Some(Outcome::ThreefoldRepetition);
+        }
+        None
+    }
+
+    pub fn is_checkmate(&self) -> bool {
+        self.in_check() && self.legal_moves().is_empty()
+    }
+
+    pub fn is_stalemate(&self) -> bool {
+        !self.in_check() && self.legal_moves().is_empty()
+    }
+
+    /// Neither side can possibly mate: bare kings, a single minor piece, or
+    /// any number of bishops that all stand on the same square colour.
+    pub fn has_insufficient_material(&self) -> bool {
+        let heavy_or_pawns = [Piece::Pawn, Piece::Rook, Piece::Queen]
+            .iter()
+            .any(|&p| self.board(Color::White, p) | self.board(Color::Black, p) != 0);
+        if heavy_or_pawns {
+            return false;
+        }
+        let knights =
+            self.board(Color::White, Piece::Knight) | self.board(Color::Black, Piece::Knight);
+        let bishops =
+            self.board(Color::White, Piece::Bishop) | self.board(Color::Black, Piece::Bishop);
+        if (knights | bishops).count_ones() <= 1 {
+            return true;
+        }
+        knights == 0 && (bishops & LIGHT_SQUARES == 0 || bishops & !LIGHT_SQUARES == 0)
+    }
+
+    /// How many earlier positions of the game are the same position as
+    /// `self` under the repetition rule (equal Zobrist hashes — see
+    /// `state_key`). Only positions since the last irreversible move can
+    /// match, so the scan stops after `halfmove_clock` plies.
+    pub fn repetitions(&self) -> usize {
+        self.hash_history()
+            .rev()
+            .take(self.halfmove_clock as usize)
+            .filter(|&h| h == self.hash)
+            .count()
+    }
+
+    /// `ep_square`, but only if a pawn of the side to move could legally
+    /// capture onto it.
+    pub fn capturable_ep_square(&self) -> Option<u8> {
+        let ep = self.ep_square?;
+        let us = self.side_to_move;
+        let capturers = squares(self.board(us, Piece::Pawn)).any(|from| {
+            pawn_attacks(us, from) & bit(ep) != 0 && self.en_passant_is_legal(from, ep)
+        });
+        capturers.then_some(ep)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome_after(fen: &str, moves: &str) -> Option<Outcome> {
+        let mut board = Bitboards::from_fen(fen).unwrap();
+        board.apply_uci_moves(moves);
+        board.outcome()
+    }
+
+    #[test]
+    fn fools_mate() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!(
+            outcome_after(start, "f2f3 e7e5 g2g4 d8h4"),
+            Some(Outcome::Checkmate {
+                winner: Color::Black
+            })
+        );
+    }
+
+    #[test]
+    fn stalemate() {
+        assert_eq!(
+            outcome_after("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", ""),
+            Some(Outcome::Stalemate)
+        );
+    }
+
+    #[test]
+    fn insufficient_material() {
+        let cases = [
+            ("8/8/4k3/8/8/3K4/8/8 w - - 0 1", true),
+            ("8/8/4k3/8/8/3KN3/8/8 w - - 0 1", true),
+            ("8/8/3bk3/8/8/3KB3/8/8 w - - 0 1", true),
+            ("8/8/2b1k3/8/8/3KB3/8/8 w - - 0 1", false),
+            ("8/8/4k3/8/8/3KNN2/8/8 w - - 0 1", false),
+            ("8/8/4k3/8/8/3KP3/8/8 w - - 0 1", false),
+        ];
+        for (fen, expected) in cases {
+            let bb = Bitboards::from_fen(fen).unwrap();
+            assert_eq!(bb.has_insufficient_material(), expected, "{}", fen);
+        }
+    }
+
+    #[test]
+    fn fifty_move_rule() {
+        assert_eq!(
+            outcome_after("8/8/4k3/8/8/3KR3/8/8 w - - 99 80", "e3h3"),
+            Some(Outcome::FiftyMoveRule)
+        );
+    }
+
+    #[test]
+    fn threefold_repetition() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let shuffle = "g1f3 g8f6 f3g1 f6g8";
+        assert_eq!(outcome_after(start, shuffle), None);
+        assert_eq!(
+            outcome_after(start, &format!("{shuffle} {shuffle}")),
+            Some(Outcome::ThreefoldRepetition)
+        );
+    }
+}
@@ -0,0 +1,172 @@
+// Perft: count the leaf nodes of the legal move tree to a fixed depth. The
+// totals for a handful of well-known positions are published, so matching
+// them is the standard way to check a move generator end to end — castling,
+// en passant, promotions, pins and checks all show up within a few plies.
+//
+// `divide` splits the count by root move, which is what you want when a
+// total is off: compare against a reference engine's divide output and
+// follow the move whose subtree disagrees.
+
+use super::{Bitboards, Move};
+
+impl Bitboards {
+    /// Number of leaf nodes `depth` plies below this position. The board is
+    /// walked with `make_move`/`unmake_move` and left as it was found.
+    pub fn perft(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.legal_moves();
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        let mut nodes = 0;
+        for mv in moves {
+            self.make_move(mv);
+            nodes += self.perft(depth - 1);
+            self.unmake_move();
+        }
+        nodes
+    }
+
+    /// Perft per root move, in move-generation order. The counts sum to
+    /// `perft(depth)`.
+    pub fn divide(&mut self, depth: u32) -> Vec<(Move, u64)> {
+        if depth == 0 {
+            return Vec::new();
+        }
+        self.legal_moves()
+            .into_iter()
+            .map(|mv| {
+                self.make_move(mv);
+                let nodes = self.perft(depth - 1);
+                self.unmake_move();
+                (mv, nodes)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    // Rook-and-pawn endgame full of en-passant discovered checks.
+    const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+    // Promotions, under-promotions and castling out of check.
+    const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+    const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+    const POSITION_6: &str =
+        "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+    // Chess960 starts from the published Fischer-random perft suite.
+    const CHESS960_1: &str = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9";
+    const CHESS960_2: &str = "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9";
+    const CHESS960_3: &str = "b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9";
+
+    fn check(fen: &str, expected: &[u64]) {
+        let mut bb = Bitboards::from_fen(fen).unwrap();
+        let before = bb.to_fen();
+        for (depth, &nodes) in expected.iter().enumerate() {
+            let depth = depth as u32 + 1;
+            assert_eq!(bb.perft(depth), nodes, "{} depth {}", fen, depth);
+        }
+        assert_eq!(bb.to_fen(), before, "perft must leave the board untouched");
+        assert_eq!(bb.ply_count(), 0);
+    }
+
+    #[test]
+    fn start_position() {
+        check(START, &[20, 400, 8_902]);
+    }
+
+    #[test]
+    fn kiwipete() {
+        check(KIWIPETE, &[48, 2_039]);
+    }
+
+    #[test]
+    fn position_3() {
+        check(POSITION_3, &[14, 191, 2_812]);
+    }
+
+    #[test]
+    fn position_4() {
+        check(POSITION_4, &[6, 264, 9_467]);
+    }
+
+    #[test]
+    fn position_5() {
+        check(POSITION_5, &[44, 1_486]);
+    }
+
+    #[test]
+    fn position_6() {
+        check(POSITION_6, &[46, 2_079]);
+    }
+
+    #[test]
+    fn divide_sums_to_perft() {
+        let mut bb = Bitboards::from_fen(KIWIPETE).unwrap();
+        let split = bb.divide(2);
+        assert_eq!(split.len(), 48);
+        assert_eq!(split.iter().map(|(_, n)| n).sum::<u64>(), 2_039);
+        let e1g1 = split.iter().find(|(mv, _)| mv.to_uci() == "e1g1").unwrap();
+        assert_eq!(e1g1.1, 43);
+    }
+
+    #[test]
+    fn chess960() {
+        check(CHESS960_1, &[21, 528]);
+        check(CHESS960_2, &[21, 807]);
+        check(CHESS960_3, &[20, 479]);
+    }
+
+    // The deep runs are slow in a debug build; run them with
+    // `cargo test --release -- --ignored`.
+
+    #[test]
+    #[ignore]
+    fn start_position_deep() {
+        check(START, &[20, 400, 8_902, 197_281, 4_865_609]);
+    }
+
+    #[test]
+    #[ignore]
+    fn kiwipete_deep() {
+        check(KIWIPETE, &[48, 2_039, 97_862, 4_085_603]);
+    }
+
+    #[test]
+    #[ignore]
+    fn position_3_deep() {
+        check(POSITION_3, &[14, 191, 2_812, 43_238, 674_624, 11_030_083]);
+    }
+
+    #[test]
+    #[ignore]
+    fn position_4_deep() {
+        check(POSITION_4, &[6, 264, 9_467, 422_333]);
+    }
+
+    #[test]
+    #[ignore]
+    fn position_5_deep() {
+        check(POSITION_5, &[44, 1_486, 62_379, 2_103_487]);
+    }
+
+    #[test]
+    #[ignore]
+    fn position_6_deep() {
+        check(POSITION_6, &[46, 2_079, 89_890, 3_894_594]);
+    }
+
+    #[test]
+    #[ignore]
+    fn chess960_deep() {
+        check(CHESS960_1, &[21, 528, 12_189, 326_672]);
+        check(CHESS960_2, &[21, 807, 18_002, 667_366]);
+        check(CHESS960_3, &[20, 479, 10_471, 273_318]);
+    }
+}
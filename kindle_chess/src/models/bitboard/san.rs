@@ -0,0 +1,331 @@
+// Standard Algebraic Notation. UCI stays the wire format (Lichess, engines);
+// SAN is what we show people and what PGN files carry.
+//
+// Both directions go through the legal move list: formatting needs it to
+// decide how much disambiguation a move requires, and parsing resolves the
+// (often partial) SAN against it rather than trusting the text.
+
+use super::{Bitboards, CastleSide, Move, MoveKind, Piece, char_to_piece, parse_square};
+use super::{piece_char, square_name};
+
+/// SAN for a legal move in `board`, e.g. "Nbd7", "exd6", "O-O-O", "e8=Q+",
+/// or "N@f3" for a crazyhouse drop.
+/// The result is unspecified if `mv` is not legal in `board`.
+pub fn to_san(board: &Bitboards, mv: Move) -> String {
+    let mut san = match mv.kind {
+        MoveKind::Castle(CastleSide::King) => "O-O".to_string(),
+        MoveKind::Castle(CastleSide::Queen) => "O-O-O".to_string(),
+        MoveKind::Drop(piece) => format!("{}@{}", piece_letter(piece), square_name(mv.to)),
+        _ => move_body(board, mv),
+    };
+    let mut after = board.clone();
+    after.make_move(mv);
+    if after.in_check() {
+        san.push(if after.legal_moves().is_empty() {
+            '#'
+        } else {
+            '+'
+        });
+    }
+    san
+}
+
+// Everything but castling and the check suffix.
+fn move_body(board: &Bitboards, mv: Move) -> String {
+    let Some((_, piece)) = board.piece_at(mv.from) else {
+        return mv.to_uci();
+    };
+    let capture = mv.kind == MoveKind::EnPassant || board.piece_at(mv.to).is_some();
+    let mut san = String::new();
+    if piece == Piece::Pawn {
+        if capture {
+            san.push(file_char(mv.from));
+        }
+    } else {
+        san.push(piece_letter(piece));
+        san.push_str(&disambiguation(board, mv, piece));
+    }
+    if capture {
+        san.push('x');
+    }
+    san.push_str(&square_name(mv.to));
+    if let Some(p) = mv.promotion {
+        san.push('=');
+        san.push(piece_letter(p));
+    }
+    san
+}
+
+// The shortest origin hint that singles `mv` out among the same kind of
+// piece reaching the same square: nothing, the file, the rank, or both.
+fn disambiguation(board: &Bitboards, mv: Move, piece: Piece) -> String {
+    let rivals: Vec<u8> = board
+        .legal_moves()
+        .into_iter()
+        .filter(|m| m.to == mv.to && m.from != mv.from)
+        .filter(|m| board.piece_at(m.from).map(|(_, p)| p) == Some(piece))
+        .map(|m| m.from)
+        .collect();
+    if rivals.is_empty() {
+        String::new()
+    } else if rivals.iter().all(|&sq| sq % 8 != mv.from % 8) {
+        file_char(mv.from).to_string()
+    } else if rivals.iter().all(|&sq| sq / 8 != mv.from / 8) {
+        rank_char(mv.from).to_string()
+    } else {
+        square_name(mv.from)
+    }
+}
+
+/// Resolve SAN against the legal moves of `board`. Check/mate markers and
+/// annotation glyphs ("+", "#", "!", "?") are ignored, castling may be
+/// written with letter O or digit 0, and the "=" before a promotion piece is
+/// optional. Drops may leave out the pawn letter ("@e4").
+pub fn parse_san(board: &Bitboards, san: &str) -> Result<Move, String> {
+    let text = san.trim().trim_end_matches(['+', '#', '!', '?']);
+    let castle = match text {
+        "O-O" | "0-0" => Some(CastleSide::King),
+        "O-O-O" | "0-0-0" => Some(CastleSide::Queen),
+        _ => None,
+    };
+    if let Some(side) = castle {
+        return board
+            .legal_moves()
+            .into_iter()
+            .find(|m| m.kind == MoveKind::Castle(side))
+            .ok_or_else(|| format!("castling not legal: '{}'", san));
+    }
+    if let Some((letter, square)) = text.split_once('@') {
+        let piece = match letter {
+            "" | "P" => Piece::Pawn,
+            "N" => Piece::Knight,
+            "B" => Piece::Bishop,
+            "R" => Piece::Rook,
+            "Q" => Piece::Queen,
+            _ => return Err(format!("bad SAN '{}'", san)),
+        };
+        let to =
+            parse_square(square.as_bytes()).map_err(|e| format!("bad SAN '{}': {}", san, e))?;
+        return board
+            .legal_moves()
+            .into_iter()
+            .find(|m| m.to == to && m.kind == MoveKind::Drop(piece))
+            .ok_or_else(|| format!("illegal SAN '{}'", san));
+    }
+
+    let (piece, rest) = match text.chars().next() {
+        Some(ch @ ('N' | 'B' | 'R' | 'Q' | 'K')) => (letter_piece(ch), &text[1..]),
+        Some(_) => (Piece::Pawn, text),
+        None => return Err("empty SAN".to_string()),
+    };
+    let (rest, promotion) = split_promotion(rest);
+    let rest = rest.replace('x', "");
+    let b = rest.as_bytes();
+    if b.len() < 2 {
+        return Err(format!("bad SAN '{}'", san));
+    }
+    let to = parse_square(&b[b.len() - 2..]).map_err(|e| format!("bad SAN '{}': {}", san, e))?;
+    let mut from_file = None;
+    let mut from_rank = None;
+    for &c in &b[..b.len() - 2] {
+        match c {
+            b'a'..=b'h' => from_file = Some(c - b'a'),
+            b'1'..=b'8' => from_rank = Some(c - b'1'),
+            _ => return Err(format!("bad SAN '{}'", san)),
+        }
+    }
+    // A bare pawn move ("d6") is a push, never a capture onto that square.
+    if piece == Piece::Pawn && from_file.is_none() {
+        from_file = Some(to % 8);
+    }
+
+    let mut candidates = board.legal_moves().into_iter().filter(|m| {
+        m.to == to
+            && m.promotion == promotion
+            && !matches!(m.kind, MoveKind::Castle(_))
+            && board.piece_at(m.from).map(|(_, p)| p) == Some(piece)
+            && from_file.is_none_or(|f| m.from % 8 == f)
+            && from_rank.is_none_or(|r| m.from / 8 == r)
+    });
+    match (candidates.next(), candidates.next()) {
+        (Some(mv), None) => Ok(mv),
+        (Some(_), Some(_)) => Err(format!("ambiguous SAN '{}'", san)),
+        (None, _) => Err(format!("illegal SAN '{}'", san)),
+    }
+}
+
+// Strip a trailing promotion ("=Q" or "Q") off the move text. "K" only
+// occurs in antichess.
+fn split_promotion(text: &str) -> (&str, Option<Piece>) {
+    let Some(last) = text.chars().last() else {
+        return (text, None);
+    };
+    if !matches!(last, 'N' | 'B' | 'R' | 'Q' | 'K') {
+        return (text, None);
+    }
+    let body = text[..text.len() - 1].trim_end_matches('=');
+    (body, Some(letter_piece(last)))
+}
+
+fn piece_letter(piece: Piece) -> char {
+    piece_char(piece).to_ascii_uppercase()
+}
+
+fn letter_piece(ch: char) -> Piece {
+    // Only ever called with one of "NBRQK".
+    char_to_piece(ch).map(|(_, p)| p).unwrap_or(Piece::Pawn)
+}
+
+fn file_char(sq: u8) -> char {
+    (b'a' + sq % 8) as char
+}
+
+fn rank_char(sq: u8) -> char {
+    (b'1' + sq / 8) as char
+}
+
+impl Bitboards {
+    /// Play a move given in SAN.
+    pub fn apply_san_move(&mut self, san: &str) -> Result<Move, String> {
+        let mv = parse_san(self, san)?;
+        self.make_move(mv);
+        Ok(mv)
+    }
+
+    /// The moves made on this board since it was set up, in SAN.
+    pub fn san_history(&self) -> Vec<String> {
+        let mut board = self.root_position();
+        let mut line = Vec::new();
+        for mv in self.moves() {
+            line.push(to_san(&board, mv));
+            board.make_move(mv);
+        }
+        line
+    }
+
+    /// Convert a space-separated UCI move list into SAN, starting from this
+    /// position. Stops at the first move that isn't legal.
+    pub fn uci_to_san_line(&self, moves: &str) -> Vec<String> {
+        let mut board = self.clone();
+        let mut line = Vec::new();
+        for uci in moves.split_whitespace() {
+            let Some(mv) = board.find_legal(uci) else {
+                break;
+            };
+            line.push(to_san(&board, mv));
+            board.make_move(mv);
+        }
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+    fn san_of(fen: &str, uci: &str) -> String {
+        let bb = Bitboards::from_fen(fen).unwrap();
+        to_san(&bb, bb.find_legal(uci).unwrap())
+    }
+
+    #[test]
+    fn formats_piece_pawn_and_castling_moves() {
+        assert_eq!(san_of(KIWIPETE, "e1g1"), "O-O");
+        assert_eq!(san_of(KIWIPETE, "e1c1"), "O-O-O");
+        assert_eq!(san_of(KIWIPETE, "e5f7"), "Nxf7");
+        assert_eq!(san_of(KIWIPETE, "d5e6"), "dxe6");
+        assert_eq!(san_of(KIWIPETE, "a2a4"), "a4");
+        assert_eq!(san_of(KIWIPETE, "f3f6"), "Qxf6");
+    }
+
+    #[test]
+    fn disambiguates_by_file_rank_or_square() {
+        // Knights on b1 and f3 can both reach d2.
+        let fen = "4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1";
+        assert_eq!(san_of(fen, "b1d2"), "Nbd2");
+        // Rooks on a1 and a5 share a file.
+        let fen = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+        assert_eq!(san_of(fen, "a1a3"), "R1a3");
+        // Three queens reach d4: a1 needs both file and rank.
+        let fen = "8/8/8/6k1/Q7/8/8/Q2Q3K w - - 0 1";
+        assert_eq!(san_of(fen, "a1d4"), "Qa1d4");
+        assert_eq!(san_of(fen, "a4d4"), "Q4d4");
+        assert_eq!(san_of(fen, "d1d4"), "Qdd4");
+    }
+
+    #[test]
+    fn check_mate_promotion_and_en_passant() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let mut bb = Bitboards::from_fen(start).unwrap();
+        bb.apply_uci_moves("f2f3 e7e5 g2g4");
+        assert_eq!(to_san(&bb, bb.find_legal("d8h4").unwrap()), "Qh4#");
+        assert_eq!(san_of("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", "a1a8"), "Ra8+");
+        assert_eq!(san_of("8/4P3/8/8/8/8/k7/4K3 w - - 0 1", "e7e8q"), "e8=Q");
+        assert_eq!(san_of("8/4P3/8/8/8/8/k7/4K3 w - - 0 1", "e7e8n"), "e8=N");
+        assert_eq!(
+            san_of("3rk3/4P3/8/8/8/8/8/4K3 w - - 0 1", "e7d8r"),
+            "exd8=R+"
+        );
+        assert_eq!(san_of("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"), "exd6");
+    }
+
+    #[test]
+    fn parses_san() {
+        let bb = Bitboards::from_fen("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1").unwrap();
+        assert_eq!(parse_san(&bb, "Nbd2").unwrap().to_uci(), "b1d2");
+        assert_eq!(parse_san(&bb, "Nfd2").unwrap().to_uci(), "f3d2");
+        assert!(parse_san(&bb, "Nd2").unwrap_err().contains("ambiguous"));
+        assert!(parse_san(&bb, "Nd3").unwrap_err().contains("illegal"));
+
+        let bb = Bitboards::from_fen(KIWIPETE).unwrap();
+        assert_eq!(parse_san(&bb, "O-O-O").unwrap().to_uci(), "e1c1");
+        assert_eq!(parse_san(&bb, "0-0").unwrap().to_uci(), "e1g1");
+        assert_eq!(parse_san(&bb, "dxe6").unwrap().to_uci(), "d5e6");
+        assert_eq!(parse_san(&bb, "Qxf6!?").unwrap().to_uci(), "f3f6");
+        assert!(parse_san(&bb, "e6").is_err());
+
+        let bb = Bitboards::from_fen("3r4/4P3/8/8/8/8/k7/4K3 w - - 0 1").unwrap();
+        assert_eq!(parse_san(&bb, "e8=Q").unwrap().to_uci(), "e7e8q");
+        assert_eq!(parse_san(&bb, "exd8N").unwrap().to_uci(), "e7d8n");
+        assert!(parse_san(&bb, "e8").is_err());
+
+        let bb = Bitboards::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        assert_eq!(parse_san(&bb, "exd6").unwrap().kind, MoveKind::EnPassant);
+    }
+
+    #[test]
+    fn round_trips_every_legal_move() {
+        let fens = [
+            KIWIPETE,
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "8/8/8/6k1/Q7/8/8/Q2Q3K w - - 0 1",
+        ];
+        for fen in fens {
+            let bb = Bitboards::from_fen(fen).unwrap();
+            for mv in bb.legal_moves() {
+                let san = to_san(&bb, mv);
+                assert_eq!(parse_san(&bb, &san), Ok(mv), "{} in {}", san, fen);
+            }
+        }
+    }
+
+    #[test]
+    fn uci_line_to_san() {
+        let bb = Bitboards::starting_position();
+        assert_eq!(
+            bb.uci_to_san_line("e2e4 e7e5 g1f3 b8c6 f1b5 a7a6 e1g1"),
+            ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "O-O"]
+        );
+    }
+
+    #[test]
+    fn history_to_san() {
+        let mut bb = Bitboards::starting_position();
+        assert!(bb.san_history().is_empty());
+        bb.apply_uci_moves("e2e4 d7d5 e4d5 d8d5 b1c3");
+        assert_eq!(bb.san_history(), ["e4", "d5", "exd5", "Qxd5", "Nc3"]);
+    }
+}
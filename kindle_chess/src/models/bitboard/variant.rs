@@ -0,0 +1,603 @@
+// Lichess variants. The board layout is shared by all of them; what differs
+// is which moves are legal, what a move does to the board beyond moving a
+// piece, and when the game is over:
+//
+// - Crazyhouse: captured pieces go to the capturer's pocket and can be
+//   dropped back ("N@f3"). Promoted pieces return to the pocket as pawns.
+// - Atomic: a capture explodes the capturing piece and every non-pawn piece
+//   next to the target square. Blowing up the enemy king wins.
+// - Three-check: giving the third check wins.
+// - King of the Hill: getting your king to d4, e4, d5 or e5 wins.
+// - Antichess: captures are compulsory, the king is an ordinary piece and
+//   losing all your pieces (or being stalemated) wins.
+// - Horde: white has 36 pawns and no king, and must be mated by capturing
+//   everything; white pawns may double-push from the first rank.
+// - Racing Kings: nobody may give check; first king to the eighth rank wins,
+//   and a black king reaching it right after the white one draws.
+//
+// `make_move`/`unmake_move` and `legal_moves` call into here; the standard
+// paths stay untouched for Standard, Chess960 and From Position.
+
+use super::movegen::{Move, MoveKind, between, king_attacks, squares};
+use super::outcome::Outcome;
+use super::{Bitboards, Color, Piece, bit, en_passant_victim, idx_color, idx_piece};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Variant {
+    #[default]
+    Standard,
+    Chess960,
+    FromPosition,
+    Crazyhouse,
+    ThreeCheck,
+    KingOfTheHill,
+    Atomic,
+    Antichess,
+    Horde,
+    RacingKings,
+}
+
+impl Variant {
+    /// Look a variant up by its Lichess key ("kingOfTheHill") or display
+    /// name ("King of the Hill", as in a PGN Variant tag). Anything unknown
+    /// is treated as standard chess.
+    pub fn parse(name: &str) -> Variant {
+        let key: String = name
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        match key.as_str() {
+            "chess960" | "fischerandom" => Variant::Chess960,
+            "fromposition" => Variant::FromPosition,
+            "crazyhouse" => Variant::Crazyhouse,
+            "threecheck" => Variant::ThreeCheck,
+            "kingofthehill" => Variant::KingOfTheHill,
+            "atomic" => Variant::Atomic,
+            "antichess" => Variant::Antichess,
+            "horde" => Variant::Horde,
+            "racingkings" => Variant::RacingKings,
+            _ => Variant::Standard,
+        }
+    }
+
+    /// Display name, as Lichess writes it in PGN.
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::Standard => "Standard",
+            Variant::Chess960 => "Chess960",
+            Variant::FromPosition => "From Position",
+            Variant::Crazyhouse => "Crazyhouse",
+            Variant::ThreeCheck => "Three-check",
+            Variant::KingOfTheHill => "King of the Hill",
+            Variant::Atomic => "Atomic",
+            Variant::Antichess => "Antichess",
+            Variant::Horde => "Horde",
+            Variant::RacingKings => "Racing Kings",
+        }
+    }
+
+    /// The variant's key in the Lichess API, as `parse` takes it back.
+    pub fn key(self) -> &'static str {
+        match self {
+            Variant::Standard => "standard",
+            Variant::Chess960 => "chess960",
+            Variant::FromPosition => "fromPosition",
+            Variant::Crazyhouse => "crazyhouse",
+            Variant::ThreeCheck => "threeCheck",
+            Variant::KingOfTheHill => "kingOfTheHill",
+            Variant::Atomic => "atomic",
+            Variant::Antichess => "antichess",
+            Variant::Horde => "horde",
+            Variant::RacingKings => "racingKings",
+        }
+    }
+
+    pub fn starting_fen(self) -> &'static str {
+        match self {
+            Variant::Horde => {
+                "rnbqkbnr/pppppppp/8/1PP2PP1/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP w kq - 0 1"
+            }
+            Variant::RacingKings => "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1",
+            Variant::Antichess => "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1",
+            Variant::Crazyhouse => "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1",
+            Variant::ThreeCheck => "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 3+3 0 1",
+            _ => "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        }
+    }
+
+    // Variants where bare kings (or a lone minor piece) can't win. In the
+    // others the goal isn't mate, or pocketed pieces can come back.
+    pub(super) fn has_material_draws(self) -> bool {
+        matches!(
+            self,
+            Variant::Standard | Variant::Chess960 | Variant::FromPosition
+        )
+    }
+}
+
+/// Pieces that can be held in a crazyhouse pocket, in `idx_piece` order.
+pub const POCKET_PIECES: [Piece; 5] = [
+    Piece::Pawn,
+    Piece::Knight,
+    Piece::Bishop,
+    Piece::Rook,
+    Piece::Queen,
+];
+
+const CENTER: u64 = (1 << 27) | (1 << 28) | (1 << 35) | (1 << 36);
+const BACK_RANKS: u64 = 0xFF00_0000_0000_00FF;
+
+impl Bitboards {
+    /// The variant's starting position with its rules switched on.
+    pub fn variant_start(variant: Variant) -> Self {
+        let mut bb = Self::from_fen(variant.starting_fen()).expect("variant FEN must parse");
+        bb.set_variant(variant);
+        bb
+    }
+
+    /// Switch the rules this board plays by. Pockets and check counts read
+    /// from the FEN are kept; castling rights are dropped for antichess,
+    /// which has no castling on Lichess.
+    pub fn set_variant(&mut self, variant: Variant) {
+        self.variant = variant;
+        if variant == Variant::Chess960 {
+            self.chess960 = true;
+        }
+        if variant == Variant::Antichess {
+            self.castling.remove_color(Color::White);
+            self.castling.remove_color(Color::Black);
+        }
+        self.hash = self.compute_hash();
+    }
+
+    /// How many of `piece` `color` holds in its crazyhouse pocket.
+    pub fn pocket(&self, color: Color, piece: Piece) -> u8 {
+        match piece {
+            Piece::King => 0,
+            _ => self.pockets[idx_color(color)][idx_piece(piece)],
+        }
+    }
+
+    /// Three-check: checks `color` has given so far.
+    pub fn checks_given(&self, color: Color) -> u8 {
+        self.checks_given[idx_color(color)]
+    }
+
+    pub(super) fn variant_legal_moves(&self) -> Vec<Move> {
+        match self.variant {
+            Variant::Crazyhouse => {
+                let mut moves = self.generate(false);
+                self.push_drops(&mut moves);
+                moves
+            }
+            Variant::Atomic => self
+                .generate(true)
+                .into_iter()
+                .filter(|&mv| self.atomic_is_legal(mv))
+                .collect(),
+            Variant::Antichess => {
+                let moves = self.generate(true);
+                let captures: Vec<Move> = moves
+                    .iter()
+                    .copied()
+                    .filter(|mv| self.is_capture(*mv))
+                    .collect();
+                if captures.is_empty() { moves } else { captures }
+            }
+            Variant::RacingKings => {
+                let mut scratch = self.scratch();
+                self.generate(false)
+                    .into_iter()
+                    .filter(|&mv| {
+                        scratch.make_move(mv);
+                        let check = scratch.in_check();
+                        scratch.unmake_move();
+                        !check
+                    })
+                    .collect()
+            }
+            _ => self.generate(false),
+        }
+    }
+
+    /// The game-ending condition specific to the variant, if it has been
+    /// met. Checkmate and the draw rules are handled by `outcome`.
+    pub(super) fn variant_outcome(&self) -> Option<Outcome> {
+        let us = self.side_to_move;
+        let them = us.flip();
+        let win = |winner: Color, reason| Outcome::VariantEnd {
+            winner: Some(winner),
+            reason,
+        };
+        match self.variant {
+            Variant::ThreeCheck if self.checks_given(them) >= 3 => Some(win(them, "Three checks")),
+            Variant::KingOfTheHill => [them, us]
+                .into_iter()
+                .find(|&color| self.board(color, Piece::King) & CENTER != 0)
+                .map(|color| win(color, "King in the center")),
+            Variant::Atomic => [us, them]
+                .into_iter()
+                .find(|&color| self.board(color, Piece::King) == 0)
+                .map(|color| win(color.flip(), "King exploded")),
+            Variant::Antichess if self.legal_moves().is_empty() => Some(win(us, "No moves left")),
+            Variant::Horde if self.occupancy(Color::White) == 0 => {
+                Some(win(Color::Black, "Horde destroyed"))
+            }
+            Variant::RacingKings => self.race_outcome(),
+            _ => None,
+        }
+    }
+
+    fn race_outcome(&self) -> Option<Outcome> {
+        let home = |color| self.board(color, Piece::King) & (0xFF << 56) != 0;
+        match (home(Color::White), home(Color::Black)) {
+            (true, true) => Some(Outcome::VariantEnd {
+                winner: None,
+                reason: "Both kings finished",
+            }),
+            (false, true) => Some(Outcome::VariantEnd {
+                winner: Some(Color::Black),
+                reason: "Race won",
+            }),
+            (true, false) => {
+                // Black gets one more move to draw by reaching the goal too.
+                let black_can_follow = self.side_to_move == Color::Black
+                    && self.legal_moves().iter().any(|mv| {
+                        self.board(Color::Black, Piece::King) & bit(mv.from) != 0 && mv.to / 8 == 7
+                    });
+                (!black_can_follow).then_some(Outcome::VariantEnd {
+                    winner: Some(Color::White),
+                    reason: "Race won",
+                })
+            }
+            (false, false) => None,
+        }
+    }
+
+    fn is_capture(&self, mv: Move) -> bool {
+        mv.kind == MoveKind::EnPassant
+            || (!matches!(mv.kind, MoveKind::Castle(_) | MoveKind::Drop(_))
+                && self.occupancy(self.side_to_move.flip()) & bit(mv.to) != 0)
+    }
+
+    // Drops onto empty squares; pawns never onto the first or last rank. In
+    // check a drop has to block, which rules it out against contact checks.
+    fn push_drops(&self, moves: &mut Vec<Move>) {
+        let us = self.side_to_move;
+        let empty = !self.occupied();
+        let checkers = self.checkers();
+        let targets = match (self.king_square(us), checkers.count_ones()) {
+            (_, 0) => empty,
+            (Some(k), 1) => between(k, checkers.trailing_zeros() as u8) & empty,
+            _ => 0,
+        };
+        for piece in POCKET_PIECES {
+            if self.pocket(us, piece) == 0 {
+                continue;
+            }
+            let allowed = match piece {
+                Piece::Pawn => targets & !BACK_RANKS,
+                _ => targets,
+            };
+            for to in squares(allowed) {
+                moves.push(Move {
+                    from: to,
+                    to,
+                    promotion: None,
+                    kind: MoveKind::Drop(piece),
+                });
+            }
+        }
+    }
+
+    // A pseudo-legal atomic move is legal if our king survives the
+    // explosion and is not left capturable — unless the enemy king went up
+    // with it, which ends the game on the spot. Kings never capture.
+    fn atomic_is_legal(&self, mv: Move) -> bool {
+        let us = self.side_to_move;
+        let them = us.flip();
+        if self.board(us, Piece::King) & bit(mv.from) != 0 && self.is_capture(mv) {
+            return false;
+        }
+        let mut after = self.scratch();
+        after.make_move(mv);
+        if after.king_square(us).is_none() {
+            return false;
+        }
+        let Some(their_king) = after.king_square(them) else {
+            return true;
+        };
+        let k = after.king_square(us).unwrap_or(their_king);
+        if king_attacks(k) & bit(their_king) != 0 {
+            return true;
+        }
+        after.attackers_to(k, them, after.occupied()) & !bit(their_king) == 0
+    }
+
+    // Throwaway copy for trying moves out, without the undo history a plain
+    // clone would drag along.
+    fn scratch(&self) -> Bitboards {
+        Bitboards {
+            boards: self.boards,
+            side_to_move: self.side_to_move,
+            castling: self.castling,
+            chess960: self.chess960,
+            variant: self.variant,
+            pockets: self.pockets,
+            promoted: self.promoted,
+            checks_given: self.checks_given,
+            ep_square: self.ep_square,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            hash: self.hash,
+            undo_stack: Vec::new(),
+        }
+    }
+
+    /// Crazyhouse pocket and promoted-piece bookkeeping for a move by
+    /// `color` that takes `captured`, run before the board is updated.
+    pub(super) fn crazyhouse_move(&mut self, mv: Move, color: Color, captured: Option<Piece>) {
+        if let MoveKind::Drop(piece) = mv.kind {
+            let count = &mut self.pockets[idx_color(color)][idx_piece(piece)];
+            *count = count.saturating_sub(1);
+            return;
+        }
+        if let Some(captured) = captured {
+            let victim = match mv.kind {
+                MoveKind::EnPassant => en_passant_victim(mv),
+                _ => mv.to,
+            };
+            let piece = if self.promoted & bit(victim) != 0 {
+                Piece::Pawn
+            } else {
+                captured
+            };
+            self.pockets[idx_color(color)][idx_piece(piece)] += 1;
+        }
+        let was_promoted = self.promoted & bit(mv.from) != 0;
+        self.promoted &= !(bit(mv.from) | bit(mv.to));
+        if was_promoted || mv.promotion.is_some() {
+            self.promoted |= bit(mv.to);
+        }
+    }
+
+    /// Atomic: blow up the capturing piece on `sq` and every non-pawn piece
+    /// around it. Returns what was removed so `unmake_move` can put it back.
+    pub(super) fn explode(&mut self, sq: u8) -> Vec<(Color, Piece, u8)> {
+        let mut blast = Vec::new();
+        for target in squares(bit(sq) | king_attacks(sq)) {
+            match self.piece_at(target) {
+                Some((_, Piece::Pawn)) if target != sq => {}
+                Some((color, piece)) => blast.push((color, piece, target)),
+                None => {}
+            }
+        }
+        for &(color, piece, target) in &blast {
+            self.clear(color, piece, target);
+            if piece == Piece::King {
+                self.castling.remove_color(color);
+            }
+            self.castling.discard_square(target);
+        }
+        blast
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::bitboard::square;
+
+    fn start(variant: Variant, moves: &str) -> Bitboards {
+        let mut bb = Bitboards::variant_start(variant);
+        bb.apply_uci_moves(moves);
+        bb
+    }
+
+    fn winner(bb: &Bitboards) -> Option<Color> {
+        bb.outcome().and_then(|o| o.winner())
+    }
+
+    #[test]
+    fn parses_lichess_keys_and_names() {
+        assert_eq!(Variant::parse("kingOfTheHill"), Variant::KingOfTheHill);
+        assert_eq!(Variant::parse("King of the Hill"), Variant::KingOfTheHill);
+        assert_eq!(Variant::parse("threeCheck"), Variant::ThreeCheck);
+        assert_eq!(Variant::parse("Three-check"), Variant::ThreeCheck);
+        assert_eq!(Variant::parse("racingKings"), Variant::RacingKings);
+        assert_eq!(Variant::parse("standard"), Variant::Standard);
+        assert_eq!(Variant::parse("something new"), Variant::Standard);
+        assert_eq!(Variant::parse(Variant::Horde.key()), Variant::Horde);
+        assert_eq!(
+            Variant::parse(Variant::FromPosition.key()),
+            Variant::FromPosition
+        );
+    }
+
+    #[test]
+    fn crazyhouse_pockets_and_drops() {
+        let mut bb = start(Variant::Crazyhouse, "e2e4 d7d5 e4d5 d8d5 b1c3 d5a5");
+        assert_eq!(bb.pocket(Color::White, Piece::Pawn), 1);
+        assert_eq!(bb.pocket(Color::Black, Piece::Pawn), 1);
+        let fen = bb.to_fen();
+        assert_eq!(
+            fen,
+            "rnb1kbnr/ppp1pppp/8/q7/8/2N5/PPPP1PPP/R1BQKBNR[Pp] w KQkq - 2 4"
+        );
+        assert_eq!(Bitboards::from_fen(&fen).unwrap().hash, bb.hash);
+
+        let drop = bb.find_legal("P@d5").unwrap();
+        assert_eq!(drop.kind, MoveKind::Drop(Piece::Pawn));
+        assert!(bb.find_legal("P@d8").is_none());
+        bb.make_move(drop);
+        assert_eq!(bb.piece_at(square(3, 4)), Some((Color::White, Piece::Pawn)));
+        assert_eq!(bb.pocket(Color::White, Piece::Pawn), 0);
+        assert_eq!(bb.hash, bb.compute_hash());
+        bb.unmake_move();
+        assert_eq!(bb.to_fen(), fen);
+    }
+
+    #[test]
+    fn crazyhouse_promoted_piece_returns_as_pawn() {
+        let mut bb = Bitboards::from_fen("1n2k3/P7/8/8/8/8/8/4K3[] w - - 0 1").unwrap();
+        bb.set_variant(Variant::Crazyhouse);
+        bb.apply_uci_moves("a7b8q e8d7");
+        assert_eq!(bb.to_fen(), "1Q~6/3k4/8/8/8/8/8/4K3[N] w - - 1 2");
+
+        let mut bb = Bitboards::from_fen("1Q~6/2k5/8/8/8/8/8/4K3[N] b - - 0 1").unwrap();
+        bb.apply_uci_move("c7b8").unwrap();
+        assert_eq!(bb.pocket(Color::Black, Piece::Pawn), 1);
+        assert_eq!(bb.pocket(Color::Black, Piece::Queen), 0);
+    }
+
+    #[test]
+    fn crazyhouse_drop_can_block_check() {
+        let mut bb = Bitboards::from_fen("4k3/8/8/8/8/8/8/r3K3[N] w - - 0 1").unwrap();
+        bb.set_variant(Variant::Crazyhouse);
+        let drops: Vec<String> = bb
+            .legal_moves()
+            .into_iter()
+            .filter(|m| matches!(m.kind, MoveKind::Drop(_)))
+            .map(|m| m.to_uci())
+            .collect();
+        assert_eq!(drops, ["N@b1", "N@c1", "N@d1"]);
+    }
+
+    #[test]
+    fn atomic_explosions() {
+        let mut bb =
+            Bitboards::from_fen("rnbqkbnr/pppppppp/8/6N1/8/8/PPPPPPPP/RNBQKB1R w KQkq - 0 1")
+                .unwrap();
+        bb.set_variant(Variant::Atomic);
+        let before = bb.to_fen();
+        bb.apply_uci_move("g5f7").unwrap();
+        // Knight, captured pawn, king e8, bishop f8 and knight g8 all gone;
+        // the neighbouring pawns survive.
+        assert_eq!(
+            bb.to_fen(),
+            "rnbq3r/ppppp1pp/8/8/8/8/PPPPPPPP/RNBQKB1R b KQ - 0 1"
+        );
+        assert_eq!(winner(&bb), Some(Color::White));
+        assert_eq!(bb.hash, bb.compute_hash());
+        bb.unmake_move();
+        assert_eq!(bb.to_fen(), before);
+    }
+
+    #[test]
+    fn atomic_legality() {
+        // The king may not capture, and a capture next to our own king
+        // would blow it up.
+        let mut bb = Bitboards::from_fen("4k3/8/8/8/8/8/3p4/3QK3 w - - 0 1").unwrap();
+        bb.set_variant(Variant::Atomic);
+        assert!(bb.find_legal("e1d2").is_none());
+        assert!(bb.find_legal("d1d2").is_none());
+        // Touching kings can't be checked.
+        let mut bb = Bitboards::from_fen("8/8/8/8/8/4k3/4K3/7r w - - 0 1").unwrap();
+        bb.set_variant(Variant::Atomic);
+        assert!(!bb.in_check());
+    }
+
+    #[test]
+    fn three_check() {
+        let bb = start(
+            Variant::ThreeCheck,
+            "e2e4 e7e5 f1c4 d7d6 c4f7 e8f7 d1h5 g7g6 h5f3",
+        );
+        assert_eq!(bb.checks_given(Color::White), 3);
+        assert_eq!(winner(&bb), Some(Color::White));
+        assert!(bb.to_fen().contains(" - 0+3 "), "{}", bb.to_fen());
+
+        let bb = start(Variant::ThreeCheck, "e2e4 e7e5 f1c4 d7d6 c4f7");
+        let fen = bb.to_fen();
+        assert_eq!(
+            fen,
+            "rnbqkbnr/ppp2Bpp/3p4/4p3/4P3/8/PPPP1PPP/RNBQK1NR b KQkq - 2+3 0 3"
+        );
+        let reparsed = Bitboards::from_fen(&fen).unwrap();
+        assert_eq!(reparsed.variant, Variant::ThreeCheck);
+        assert_eq!(reparsed.checks_given(Color::White), 1);
+        assert_eq!(reparsed.hash, bb.hash);
+    }
+
+    #[test]
+    fn king_of_the_hill() {
+        let bb = start(Variant::KingOfTheHill, "e2e3 e7e6 e1e2 e8e7 e2d3 e7d6");
+        assert_eq!(winner(&bb), None);
+        let bb = start(Variant::KingOfTheHill, "e2e3 e7e6 e1e2 e8e7 e2d3 e7d6 d3d4");
+        assert_eq!(winner(&bb), Some(Color::White));
+    }
+
+    #[test]
+    fn antichess_forced_captures_and_win() {
+        let bb = start(Variant::Antichess, "e2e4 d7d5");
+        let moves: Vec<String> = bb.legal_moves().iter().map(Move::to_uci).collect();
+        assert_eq!(moves, ["e4d5"]);
+
+        let mut bb = Bitboards::from_fen("8/8/8/8/8/8/1p6/8 b - - 0 1").unwrap();
+        bb.set_variant(Variant::Antichess);
+        assert!(bb.find_legal("b2b1k").is_some());
+        bb.apply_uci_move("b2b1k").unwrap();
+        // White has nothing left: it has "lost" everything and wins.
+        assert_eq!(winner(&bb), Some(Color::White));
+    }
+
+    #[test]
+    fn horde() {
+        let bb = Bitboards::variant_start(Variant::Horde);
+        assert!(bb.find_legal("a1a3").is_none()); // blocked by a2
+        let mut bb = Bitboards::from_fen("4k3/8/8/8/8/8/8/P7 w - - 0 1").unwrap();
+        bb.set_variant(Variant::Horde);
+        bb.apply_uci_move("a1a3").unwrap();
+        assert_eq!(bb.ep_square, Some(square(0, 1)));
+        let mut bb = Bitboards::from_fen("4k3/8/8/8/8/8/1q6/P7 b - - 0 1").unwrap();
+        bb.set_variant(Variant::Horde);
+        bb.apply_uci_move("b2a1").unwrap();
+        assert_eq!(winner(&bb), Some(Color::Black));
+    }
+
+    #[test]
+    fn racing_kings() {
+        let bb = Bitboards::variant_start(Variant::RacingKings);
+        // No move may give check.
+        assert!(bb.legal_moves().iter().all(|&mv| {
+            let mut after = bb.clone();
+            after.make_move(mv);
+            !after.in_check()
+        }));
+
+        // Black is too far back to answer.
+        let mut bb = Bitboards::from_fen("8/6K1/k7/8/8/8/8/8 w - - 0 1").unwrap();
+        bb.set_variant(Variant::RacingKings);
+        bb.apply_uci_move("g7g8").unwrap();
+        assert_eq!(winner(&bb), Some(Color::White));
+
+        let mut bb = Bitboards::from_fen("8/k5K1/8/8/8/8/8/8 w - - 0 1").unwrap();
+        bb.set_variant(Variant::RacingKings);
+        bb.apply_uci_move("g7g8").unwrap();
+        assert_eq!(bb.outcome(), None, "black may still equalise");
+        bb.apply_uci_move("a7b6").unwrap();
+        assert_eq!(winner(&bb), Some(Color::White));
+        bb.unmake_move();
+        bb.apply_uci_move("a7a8").unwrap();
+        let outcome = bb.outcome().unwrap();
+        assert_eq!(outcome.winner(), None);
+    }
+
+    #[test]
+    fn variant_perft() {
+        let cases: [(Variant, &[u64]); 4] = [
+            (Variant::Horde, &[8, 128, 1_274]),
+            (Variant::RacingKings, &[21, 421, 11_264]),
+            (Variant::Antichess, &[20, 400, 8_067]),
+            (Variant::Crazyhouse, &[20, 400, 8_902]),
+        ];
+        for (variant, expected) in cases {
+            let mut bb = Bitboards::variant_start(variant);
+            for (depth, &nodes) in expected.iter().enumerate() {
+                let depth = depth as u32 + 1;
+                assert_eq!(bb.perft(depth), nodes, "{:?} depth {}", variant, depth);
+            }
+        }
+    }
+}
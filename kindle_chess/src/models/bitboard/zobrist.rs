@@ -0,0 +1,184 @@
+// Zobrist hashing. Every (color, piece, square) triple, the side to move,
+// each castling-rights combination and each en-passant file gets a fixed
+// random key, as do the crazyhouse pocket counts and three-check counters;
+// a position's hash is the XOR of the keys that apply to it.
+//
+// `Bitboards::set`/`clear` XOR the piece keys in as pieces come and go, and
+// `make_move` swaps the side/castling/en-passant keys out and back in around
+// each move, so the hash is never recomputed from scratch during play.
+// `compute_hash` exists for FEN loading and for checking the incremental
+// updates in tests.
+//
+// The keys come from a fixed-seed splitmix64 run at compile time, so hashes
+// are stable across runs and builds (useful for anything persisted later).
+
+use super::movegen::squares;
+use super::{Bitboards, Color, Piece, idx_color, idx_piece};
+
+struct Keys {
+    pieces: [[[u64; 64]; 6]; 2],
+    black_to_move: u64,
+    castling: [u64; 16],
+    ep_file: [u64; 8],
+    // [Color][pocket piece][count], count capped at 16.
+    pocket: [[[u64; 17]; 5]; 2],
+    // [Color][checks given].
+    checks: [[u64; 4]; 2],
+}
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (state, z ^ (z >> 31))
+}
+
+const fn generate_keys() -> Keys {
+    let mut state = 0x4B49_4E44_4C45_4348; // "KINDLECH"
+    let mut keys = Keys {
+        pieces: [[[0; 64]; 6]; 2],
+        black_to_move: 0,
+        castling: [0; 16],
+        ep_file: [0; 8],
+        pocket: [[[0; 17]; 5]; 2],
+        checks: [[0; 4]; 2],
+    };
+    let mut c = 0;
+    while c < 2 {
+        let mut p = 0;
+        while p < 6 {
+            let mut sq = 0;
+            while sq < 64 {
+                let (s, key) = splitmix64(state);
+                state = s;
+                keys.pieces[c][p][sq] = key;
+                sq += 1;
+            }
+            p += 1;
+        }
+        c += 1;
+    }
+    let (s, key) = splitmix64(state);
+    state = s;
+    keys.black_to_move = key;
+    // Castling rights index straight by their flag bits, so the empty set
+    // must hash to 0.
+    let mut i = 1;
+    while i < 16 {
+        let (s, key) = splitmix64(state);
+        state = s;
+        keys.castling[i] = key;
+        i += 1;
+    }
+    let mut f = 0;
+    while f < 8 {
+        let (s, key) = splitmix64(state);
+        state = s;
+        keys.ep_file[f] = key;
+        f += 1;
+    }
+    // Variant keys come last so standard-chess keys stay as they were, and
+    // a count of zero hashes to 0 so standard positions are unaffected.
+    let mut c = 0;
+    while c < 2 {
+        let mut p = 0;
+        while p < 5 {
+            let mut n = 1;
+            while n < 17 {
+                let (s, key) = splitmix64(state);
+                state = s;
+                keys.pocket[c][p][n] = key;
+                n += 1;
+            }
+            p += 1;
+        }
+        let mut n = 1;
+        while n < 4 {
+            let (s, key) = splitmix64(state);
+            state = s;
+            keys.checks[c][n] = key;
+            n += 1;
+        }
+        c += 1;
+    }
+    keys
+}
+
+const KEYS: Keys = generate_keys();
+
+pub(super) fn piece_key(color: Color, piece: Piece, sq: u8) -> u64 {
+    KEYS.pieces[idx_color(color)][idx_piece(piece)][sq as usize]
+}
+
+impl Bitboards {
+    /// Hash of everything that isn't piece placement: side to move, castling
+    /// rights, pockets, check counts and — only when a capture onto it is
+    /// actually possible — the en-passant file. Matches the repetition
+    /// rule, so two positions with equal hashes count as "the same
+    /// position".
+    pub(super) fn state_key(&self) -> u64 {
+        let mut key = KEYS.castling[self.castling.bits() as usize];
+        if self.side_to_move == Color::Black {
+            key ^= KEYS.black_to_move;
+        }
+        if let Some(ep) = self.capturable_ep_square() {
+            key ^= KEYS.ep_file[(ep % 8) as usize];
+        }
+        for c in 0..2 {
+            for (p, &count) in self.pockets[c].iter().enumerate() {
+                key ^= KEYS.pocket[c][p][count.min(16) as usize];
+            }
+            key ^= KEYS.checks[c][self.checks_given[c].min(3) as usize];
+        }
+        key
+    }
+
+    /// Full Zobrist hash computed from scratch.
+    pub fn compute_hash(&self) -> u64 {
+        let mut hash = self.state_key();
+        for color in [Color::White, Color::Black] {
+            for piece in Piece::ALL {
+                for sq in squares(self.board(color, piece)) {
+                    hash ^= piece_key(color, piece, sq);
+                }
+            }
+        }
+        hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn incremental_hash_matches_full_recompute() {
+        let mut bb = Bitboards::from_fen(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        )
+        .unwrap();
+        // Castles, captures, a double push that enables en passant, and the
+        // en-passant capture itself.
+        bb.apply_uci_moves("e1g1 h3g2 a2a4 b4a3 f3f6 e8c8 d5e6");
+        assert_eq!(bb.hash, bb.compute_hash());
+    }
+
+    #[test]
+    fn transposition_has_equal_hash() {
+        let mut a = Bitboards::starting_position();
+        a.apply_uci_moves("g1f3 g8f6 b1c3");
+        let mut b = Bitboards::starting_position();
+        b.apply_uci_moves("b1c3 g8f6 g1f3");
+        assert_eq!(a.hash, b.hash);
+    }
+
+    #[test]
+    fn uncapturable_en_passant_square_is_not_hashed() {
+        let mut bb = Bitboards::starting_position();
+        bb.apply_uci_move("e2e4").unwrap();
+        let fen = Bitboards::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1")
+            .unwrap();
+        assert_eq!(bb.hash, fen.hash);
+    }
+}
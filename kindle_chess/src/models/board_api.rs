@@ -1,8 +1,8 @@
 use serde::{Deserialize, Deserializer, Serialize};
+use std::time::Duration;
 
 use crate::models::{
-    bitboard::Bitboards,
-    game::Player,
+    bitboard::{Bitboards, Color, Move, Outcome, Variant},
     oauth::{LichessUser, TokenInfo},
 };
 
@@ -19,9 +19,10 @@ pub struct Idle;
 // state event — the sidebar reads it to render "Your turn" / "Waiting".
 //
 // `initial_board` is the position parsed from `GameFull.initial_fen`. Each
-// `GameState` event delivers the full move list from move 1, so we rebuild the
-// current `board` by cloning `initial_board` and replaying the moves — no
-// incremental state to keep in sync.
+// `GameState` event delivers the full move list from move 1; `moves` is the
+// list `board` currently reflects. When a new list extends it we only make the
+// new tail on `board`, and fall back to replaying from `initial_board` when it
+// doesn't (takeback, or a fresh `GameFull` after a reconnect).
 #[derive(Debug, Clone)]
 pub struct InGame {
     pub game_id: String,
@@ -31,13 +32,68 @@ pub struct InGame {
     pub turn: Turn,
     pub initial_board: Bitboards,
     pub board: Bitboards,
+    pub moves: String,
+    // Starting time on each clock, from `GameFull`; `None` for untimed
+    // (correspondence) games.
+    pub initial_time: Option<Duration>,
 }
 
+// `reason` is a short human-readable cause ("Checkmate", "Resignation",
+// "Stalemate", ...) — from the Lichess status string for online games, or from
+// our own `Outcome` for positions we evaluate locally.
 #[derive(Debug, Clone)]
 pub enum Turn {
     Playing,
     Waiting,
-    Over { winner: Option<String> },
+    Over {
+        winner: Option<String>,
+        reason: Option<String>,
+    },
+}
+
+impl Turn {
+    /// Terminal turn for a game whose result we worked out ourselves.
+    /// `white` / `black` are the names credited on a checkmate.
+    pub fn from_outcome(outcome: Outcome, white: &str, black: &str) -> Turn {
+        Turn::Over {
+            winner: outcome.winner().map(|color| match color {
+                Color::White => white.to_string(),
+                Color::Black => black.to_string(),
+            }),
+            reason: Some(outcome.reason().to_string()),
+        }
+    }
+}
+
+// Something the opponent has asked for that needs a yes or no (the
+// `wdraw` / `bdraw` / `wtakeback` / `btakeback` flags of a game state).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Offer {
+    Draw,
+    Takeback,
+}
+
+// A move we've shown on the board but the server hasn't echoed yet. `before`
+// and `turn` are what to put back if the POST fails; `ply` is where the move
+// should appear in the server's move list (see `PendingMove::reconcile`).
+#[derive(Debug, Clone)]
+pub struct PendingMove {
+    pub mv: Move,
+    pub uci: String,
+    pub ply: usize,
+    pub before: Bitboards,
+    pub turn: Turn,
+}
+
+// How a streamed position relates to the pending move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reconciled {
+    // The server's move list hasn't reached the move yet.
+    Pending,
+    // The move is in the server's list where we played it.
+    Confirmed,
+    // Something else happened at that ply (or before it, after a takeback).
+    Diverged,
 }
 
 #[derive(Debug, Clone)]
@@ -47,7 +103,7 @@ pub struct BoardAPI<S> {
     pub state: S,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GameData {
     pub full_id: String,
@@ -66,7 +122,7 @@ pub struct GameData {
     pub variant: GameVariant,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GameDataList {
     pub now_playing: Vec<GameData>,
@@ -74,18 +130,21 @@ pub struct GameDataList {
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~ STREAMS ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+// `/stream/event`: each line carries its payload under `game` or
+// `challenge`, next to the `type` tag.
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "type")]
 #[serde(rename_all = "camelCase")]
 pub enum StreamEvent {
-    GameStart(GameStartEvent),
-    GameFinish(GameFinishEvent),
-    Challenge(ChallengeEvent),
-    ChallengeDeclined(ChallengeDeclinedEvent),
+    GameStart { game: GameStartEvent },
+    GameFinish { game: GameFinishEvent },
+    Challenge { challenge: ChallengeEvent },
+    ChallengeCanceled { challenge: ChallengeEvent },
+    ChallengeDeclined { challenge: ChallengeDeclinedEvent },
 }
 
 // ~~~~~~~~~~~~~~~~ EVENT-STREAM-TYPES ~~~~~~~~~~~~~~~~
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GameStartEvent {
     #[serde(rename = "fullId")]
     pub full_id: String,
@@ -93,129 +152,95 @@ pub struct GameStartEvent {
     pub game_id: String,
     pub fen: String,
     pub color: String,
-    #[serde(rename = "lastMove")]
+    #[serde(rename = "lastMove", default)]
     pub last_move: String,
     pub source: String,
     pub status: GameStatus,
-    pub variant: String,
+    pub variant: GameVariant,
     pub speed: String,
     pub perf: String,
     pub rated: bool,
     #[serde(rename = "hasMoved")]
     pub has_moved: bool,
-    pub opponent: Opponent,
+    pub opponent: PlayedBy,
     #[serde(rename = "isMyTurn")]
     pub is_my_turn: bool,
     #[serde(rename = "secondsLeft")]
     pub seconds_left: Option<u64>,
-    pub compat: Compat,
-    pub id: String,
+    pub compat: Option<Compat>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GameFinishEvent {
     #[serde(rename = "fullId")]
     pub full_id: String,
     #[serde(rename = "gameId")]
     pub game_id: String,
-    pub fen: String,
     pub color: String,
-    #[serde(rename = "lastMove")]
-    pub last_move: String,
-    pub source: String,
     pub status: GameStatus,
-    pub variant: GameVariant,
-    pub speed: String,
-    pub perf: String,
-    pub rated: bool,
-    #[serde(rename = "hasMoved")]
-    pub has_moved: bool,
-    pub opponent: Opponent,
-    #[serde(rename = "isMyTurn")]
-    pub is_my_turn: bool,
-    #[serde(rename = "secondsLeft")]
-    pub seconds_left: Option<u64>,
+    pub opponent: PlayedBy,
     pub winner: Option<String>,
     #[serde(rename = "ratingDiff")]
     pub rating_diff: Option<i16>,
-    pub compat: Compat,
-    pub id: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ChallengeEvent {
     pub id: String,
     pub url: String,
     pub status: String,
-    pub challenger: Player,
+    pub challenger: PlayedBy,
+    // `None` for an open challenge, which anyone with the link can take.
     #[serde(rename = "destUser")]
-    pub dest_user: Player,
+    pub dest_user: Option<PlayedBy>,
     pub variant: GameVariant,
     pub rated: bool,
     pub speed: String,
     #[serde(rename = "timeControl")]
     pub time_control: TimeControl,
+    // What the challenger asked for ("white", "black" or "random") and, once
+    // drawn, the colour the challenger gets.
     pub color: String,
     #[serde(rename = "finalColor")]
-    pub final_color: String,
-    pub perf: PerfCallenge,
-    pub compat: Compat,
+    pub final_color: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ChallengeDeclinedEvent {
     pub id: String,
-    pub url: String,
-    pub status: String,
-    pub challenger: Player,
     #[serde(rename = "destUser")]
-    pub dest_user: Player,
-    pub variant: GameVariant,
-    pub rated: bool,
-    pub speed: String,
-    #[serde(rename = "timeControl")]
-    pub time_control: TimeControl,
-    pub color: String,
-    #[serde(rename = "finalColor")]
-    pub final_color: String,
-    pub perf: PerfCallenge,
-    pub compat: Compat,
-    #[serde(rename = "declineReason")]
-    decline_reason: String,
-    #[serde(rename = "declineReasonKey")]
-    decline_reason_key: String,
+    pub dest_user: Option<PlayedBy>,
+    // Human-readable, in the decliner's language.
+    #[serde(rename = "declineReason", default)]
+    pub decline_reason: String,
+    #[serde(rename = "declineReasonKey", default)]
+    pub decline_reason_key: String,
 }
 
 // MISC EVENT-STREAM-TYPES
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GameStatus {
-    id: u16,
-    name: String,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Opponent {
-    id: String,
-    username: String,
-    rating: u64,
+    pub id: u16,
+    pub name: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Compat {
-    bot: bool,
-    board: bool,
+    pub bot: bool,
+    pub board: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+// `clock` games have `limit` and `increment` in seconds, `correspondence`
+// ones `daysPerTurn`; `unlimited` has neither.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TimeControl {
     #[serde(rename = "type")]
-    tc_type: String,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct PerfCallenge {
-    icon: String,
-    name: String,
+    pub tc_type: String,
+    pub limit: Option<u64>,
+    pub increment: Option<u64>,
+    #[serde(rename = "daysPerTurn")]
+    pub days_per_turn: Option<u64>,
+    pub show: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -284,6 +309,13 @@ pub struct GameOverEvent {
     pub winner: String,
 }
 
+// One line of `GET /board/game/{id}/chat`, the player room's history.
+#[derive(Deserialize, Debug)]
+pub struct ChatHistoryLine {
+    pub user: String,
+    pub text: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ChatLineEvent {
     // #[serde(rename = "type")]
@@ -298,16 +330,23 @@ pub struct OpponentGoneEvent {
     // #[serde(rename = "type")]
     // pub event_type: String,
     pub gone: bool,
-    #[serde(rename = "claimWinInSeconds")]
-    pub claim_win_in_seconds: u64,
+    // Only sent while `gone`.
+    #[serde(rename = "claimWinInSeconds", default)]
+    pub claim_win_in_seconds: Option<u64>,
 }
 
 // MISC GAME-STATE-STREAM-EVENT-TYPES
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GameVariant {
-    key: String,
-    name: String,
-    short: Option<String>,
+    pub key: String,
+    pub name: String,
+    pub short: Option<String>,
+}
+
+impl GameVariant {
+    pub fn variant(&self) -> Variant {
+        Variant::parse(&self.key)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -318,8 +357,9 @@ pub struct PerfMode {
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Clock {
-    initial: u64,
-    increment: u64,
+    // Milliseconds.
+    pub initial: u64,
+    pub increment: u64,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -398,7 +438,7 @@ pub struct Performance {
     name: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum Speed {
     UltraBullet,
@@ -1,6 +1,48 @@
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::models::{bitboard::Bitboards, board_api::Turn, engine::Strength, uci::UciHandle};
+
+// A game played entirely on the device. player0 is the person holding the
+// Kindle, as in `BoardAPI`'s in-game state; in pass-and-play that's whoever
+// has white.
 #[derive(Debug, Clone)]
 pub struct BoardLocal {
-    // pub game_id: String,
-    pub player0_white: bool, // if true then player0 had first turn
-    pub player0_turn: bool,  // if true then it's currently player0's turn
+    pub board: Bitboards,
+    pub player0_white: bool, // if true then player0 plays the white pieces
+    pub turn: Turn,          // from player0's point of view; Waiting = engine to move
+    pub opponent: LocalOpponent,
+    // Pass-and-play: turn the board after every move so the side to move
+    // always has its pieces at the bottom.
+    pub auto_flip: bool,
+    // Where the game is autosaved (see local/save.rs); `None` for games
+    // that aren't kept.
+    pub save_path: Option<PathBuf>,
+    // PGN Date tag, "YYYY.MM.DD", fixed when the game starts.
+    pub date: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum LocalOpponent {
+    // An installed UCI engine plays instead of the built-in one when present.
+    Engine {
+        strength: Strength,
+        uci: Option<UciHandle>,
+    },
+    // A second person sharing the device. Both sides move from the same
+    // screen, so the turn is always `Playing` until the game ends.
+    Human,
+}
+
+/// A savegame as the ongoing-games list shows it, read from the PGN tags
+/// alone. The game itself is only loaded when it's picked.
+#[derive(Debug, Clone)]
+pub struct SaveSummary {
+    pub path: PathBuf,
+    /// The engine's level; `None` for pass-and-play.
+    pub engine_level: Option<u8>,
+    /// Whether it's the device owner's move. Always false for saves that
+    /// don't record a PlyCount.
+    pub my_turn: bool,
+    pub modified: SystemTime,
 }
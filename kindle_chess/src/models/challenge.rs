@@ -0,0 +1,72 @@
+// ~~~~~~~~~~~~~~~~ CHALLENGES ~~~~~~~~~~~~~~~~
+// What the new-challenge screen builds and `BoardAPI<Idle>` sends. The
+// handling lives in app/challenge.rs.
+
+use serde::Deserialize;
+
+use crate::models::bitboard::Variant;
+
+/// Who the challenge goes to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChallengeTarget {
+    /// A Lichess user, by name.
+    User(String),
+    /// Whoever opens its link first.
+    Open,
+    /// Lichess' Stockfish, level 1 to 8. Starts the game straight away.
+    Ai(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChallengeTime {
+    /// Seconds on each clock, and added per move.
+    RealTime {
+        limit: u32,
+        increment: u32,
+    },
+    Correspondence {
+        days: u8,
+    },
+    Unlimited,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChallengeColor {
+    #[default]
+    Random,
+    White,
+    Black,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChallengeRequest {
+    pub variant: Variant,
+    pub time: ChallengeTime,
+    /// The colour we play.
+    pub color: ChallengeColor,
+    /// Ignored for the AI, which only plays casual games.
+    pub rated: bool,
+}
+
+/// Lichess' answer to a new challenge. A game against the AI has no links.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreatedChallenge {
+    pub id: String,
+    pub url: Option<String>,
+    // Open challenges: links that seat whoever opens them as that colour.
+    #[serde(rename = "urlWhite")]
+    pub url_white: Option<String>,
+    #[serde(rename = "urlBlack")]
+    pub url_black: Option<String>,
+}
+
+/// A lobby seek, paired by Lichess with a compatible one. Real-time only: a
+/// correspondence seek outlives its request and can't be taken back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Seek {
+    /// Minutes on each clock.
+    pub minutes: u32,
+    /// Seconds added per move.
+    pub increment: u32,
+    pub rated: bool,
+}
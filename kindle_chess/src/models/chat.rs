@@ -0,0 +1,24 @@
+// ~~~~~~~~~~~~~~~~ CHAT ~~~~~~~~~~~~~~~~
+// The two chat rooms of an online game, kept for as long as its screen is
+// open. The handling lives in app/chat.rs.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatRoom {
+    /// Between the two players.
+    Player,
+    Spectator,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatMessage {
+    pub room: ChatRoom,
+    pub username: String,
+    pub text: String,
+}
+
+/// Both rooms' messages, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct ChatLog {
+    pub player: Vec<ChatMessage>,
+    pub spectator: Vec<ChatMessage>,
+}
@@ -0,0 +1,18 @@
+use std::time::Duration;
+
+use crate::models::bitboard::Color;
+
+// ~~~~~~~~~~~~~~~~ CLOCK ~~~~~~~~~~~~~~~~
+// Time left in a timed online game, as of the last server update and counted
+// down locally in between (see app/clock.rs).
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameClock {
+    pub white: Duration,
+    pub black: Duration,
+    /// Whose time is running: the side to move once both sides have moved,
+    /// `None` before that and after the game ends.
+    pub running: Option<Color>,
+    /// Below this a side's clock shows the low-time indicator.
+    pub low_time: Duration,
+}
@@ -0,0 +1,138 @@
+use std::time::{Duration, Instant};
+
+use crate::models::bitboard::Move;
+
+// ~~~~~~~~~~~~~~~~ ENGINE ~~~~~~~~~~~~~~~~
+// The built-in opponent for offline games. Search and evaluation live in
+// local/engine.rs; this is the state it carries between searches and the
+// knobs callers turn.
+
+/// Playing strength, 1 (weakest) to 8 (strongest) — the same scale Lichess
+/// uses for its AI levels. Lower levels search shallower and pick more
+/// loosely among the moves they consider good enough.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Strength(u8);
+
+impl Strength {
+    pub const MIN: Strength = Strength(1);
+    pub const MAX: Strength = Strength(8);
+
+    /// Clamps `level` into 1..=8.
+    pub fn new(level: u8) -> Self {
+        Strength(level.clamp(Self::MIN.0, Self::MAX.0))
+    }
+
+    pub fn level(self) -> u8 {
+        self.0
+    }
+
+    /// The next level up, wrapping from 8 back to 1 (for a cycling button).
+    pub fn cycle(self) -> Self {
+        if self == Self::MAX {
+            Self::MIN
+        } else {
+            Strength(self.0 + 1)
+        }
+    }
+
+    /// Search limits for this level. The time caps keep replies within a
+    /// few seconds on the Kindle's single ARM core whatever the depth.
+    pub fn limits(self) -> SearchLimits {
+        let (depth, millis) = match self.0 {
+            1 | 2 => (1, 300),
+            3 => (2, 500),
+            4 => (3, 800),
+            5 => (4, 1_000),
+            6 => (5, 1_500),
+            7 => (8, 2_500),
+            _ => (MAX_DEPTH, 4_000),
+        };
+        SearchLimits {
+            depth,
+            nodes: None,
+            time: Some(Duration::from_millis(millis)),
+        }
+    }
+
+    /// How far (in centipawns) below the best root move a move may score
+    /// and still be picked at random instead. 0 always plays the best move.
+    pub fn tolerance(self) -> i32 {
+        match self.0 {
+            1 => 400,
+            2 => 250,
+            3 => 150,
+            4 => 80,
+            5 => 40,
+            6 => 15,
+            _ => 0,
+        }
+    }
+}
+
+impl Default for Strength {
+    fn default() -> Self {
+        Strength(4)
+    }
+}
+
+/// When to stop searching. The search always finishes depth 1, so there is
+/// a move to play even with a tiny budget.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchLimits {
+    pub depth: u8,
+    pub nodes: Option<u64>,
+    pub time: Option<Duration>,
+}
+
+/// Deepest iteration the search will attempt.
+pub const MAX_DEPTH: u8 = 32;
+
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    /// `None` only when the side to move has no legal moves.
+    pub best_move: Option<Move>,
+    /// Centipawns from the side to move's point of view; mates are scored
+    /// near ±`MATE_SCORE`.
+    pub score: i32,
+    /// Deepest fully completed iteration.
+    pub depth: u8,
+    pub nodes: u64,
+    pub elapsed: Duration,
+    /// Principal variation, starting with `best_move`.
+    pub pv: Vec<Move>,
+}
+
+pub const MATE_SCORE: i32 = 30_000;
+
+pub struct Engine {
+    pub strength: Strength,
+    pub(crate) tt: Vec<TtEntry>,
+    // Two quiet moves per ply that recently caused a beta cutoff.
+    pub(crate) killers: Vec<[Option<Move>; 2]>,
+    pub(crate) nodes: u64,
+    pub(crate) node_limit: Option<u64>,
+    pub(crate) deadline: Option<Instant>,
+    pub(crate) stopped: bool,
+    pub(crate) rng: u64,
+}
+
+// Transposition table slot, indexed by the low bits of the Zobrist hash and
+// verified against the full key.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct TtEntry {
+    pub(crate) key: u64,
+    pub(crate) best: Option<Move>,
+    pub(crate) score: i32,
+    pub(crate) depth: i8,
+    pub(crate) bound: Bound,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum Bound {
+    #[default]
+    Exact,
+    // Score is at least this (the node failed high).
+    Lower,
+    // Score is at most this (no move raised alpha).
+    Upper,
+}
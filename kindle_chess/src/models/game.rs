@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::models::board_api::Performance;
+use crate::models::pgn::{PgnGame, parse_pgn};
 
 // ~~~~~~~~~~~~~~~~ GAME ~~~~~~~~~~~~~~~~
 #[derive(Serialize, Deserialize, Debug)]
@@ -13,6 +14,17 @@ pub struct Game {
     clock: String,
 }
 
+impl Game {
+    /// The game's moves as a PGN game tree. Lichess sends bare SAN movetext
+    /// here (no tags, no result).
+    pub fn pgn_game(&self) -> Result<PgnGame, String> {
+        parse_pgn(&self.pgn)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| "empty PGN".to_string())
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Player {
     name: String,
@@ -2,8 +2,15 @@ pub mod app;
 pub mod bitboard;
 pub mod board_api;
 pub mod board_local;
+pub mod challenge;
+pub mod chat;
 pub mod chess;
+pub mod clock;
+pub mod engine;
 pub mod game;
 pub mod oauth;
+pub mod pgn;
 pub mod puzzle;
+pub mod stream;
+pub mod uci;
 pub mod ui;
@@ -0,0 +1,852 @@
+// Portable Game Notation: reading and writing game collections.
+//
+// A `PgnGame` is a tag list plus a move tree. The tree is stored as a flat
+// arena (`nodes`, index 0 is the root position) so variations can point at
+// their parent without reference counting; a node's first child is the main
+// continuation and any further children are alternatives to it.
+//
+// Moves are resolved against `Bitboards` while parsing, so every node holds
+// a legal `Move` and the PGN we write back out is always re-generated SAN,
+// regardless of how loosely the input was written.
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::Chars;
+
+use crate::models::bitboard::{Bitboards, Color, Move, Outcome, Variant, parse_san, to_san};
+
+/// The Seven Tag Roster, in the order PGN requires them to be written, with
+/// the placeholder each one gets when unknown.
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+const LINE_WIDTH: usize = 80;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameNode {
+    /// The move leading to this node; `None` only for the root.
+    pub mv: Option<Move>,
+    pub san: String,
+    /// Numeric annotation glyphs ($1 = "!", $2 = "?", ...).
+    pub nags: Vec<u8>,
+    /// Comment written before the move. Only the first move of a variation
+    /// can carry one; elsewhere it would read as the previous move's comment.
+    pub starting_comment: Option<String>,
+    /// Comment written after the move (or, on the root, before the first).
+    pub comment: Option<String>,
+    pub parent: Option<usize>,
+    /// Continuations; the first is the main line.
+    pub children: Vec<usize>,
+}
+
+impl GameNode {
+    fn new(mv: Option<Move>, san: String, parent: Option<usize>) -> Self {
+        Self {
+            mv,
+            san,
+            nags: Vec::new(),
+            starting_comment: None,
+            comment: None,
+            parent,
+            children: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PgnGame {
+    /// Tag pairs in file order.
+    pub tags: Vec<(String, String)>,
+    /// Position before the first move (the FEN tag, if any).
+    pub start: Bitboards,
+    pub nodes: Vec<GameNode>,
+}
+
+impl Default for PgnGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PgnGame {
+    pub const ROOT: usize = 0;
+
+    /// Empty game from the standard starting position, with the Seven Tag
+    /// Roster filled with placeholders.
+    pub fn new() -> Self {
+        Self::from_position(Bitboards::starting_position())
+    }
+
+    /// Empty game from an arbitrary position; adds a Variant tag for
+    /// anything but standard chess, and the SetUp/FEN tags when it isn't the
+    /// variant's usual start.
+    pub fn from_position(start: Bitboards) -> Self {
+        let mut game = Self {
+            tags: SEVEN_TAG_ROSTER
+                .iter()
+                .map(|&(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            start,
+            nodes: vec![GameNode::new(None, String::new(), None)],
+        };
+        let variant = match game.start.variant {
+            Variant::Standard | Variant::FromPosition if game.start.chess960 => {
+                Some(Variant::Chess960)
+            }
+            Variant::Standard | Variant::FromPosition => None,
+            variant => Some(variant),
+        };
+        if let Some(variant) = variant {
+            game.set_tag("Variant", variant.name());
+        }
+        let fen = game.start.to_fen();
+        if fen != Bitboards::variant_start(game.start.variant).to_fen() {
+            game.set_tag("SetUp", "1");
+            game.set_tag("FEN", &fen);
+        }
+        game
+    }
+
+    /// Game holding a single line of UCI moves from `start`, e.g. a finished
+    /// Lichess or offline game. If the line ends the game, the Result tag is
+    /// set to match.
+    pub fn from_uci_moves(start: Bitboards, moves: &str) -> Result<Self, String> {
+        let mut game = Self::from_position(start);
+        let mut board = game.start.clone();
+        let mut node = Self::ROOT;
+        for uci in moves.split_whitespace() {
+            let mv = board
+                .find_legal(uci)
+                .ok_or_else(|| format!("illegal move '{}'", uci))?;
+            node = game.add_move(node, &board, mv);
+            board.make_move(mv);
+        }
+        if let Some(outcome) = board.outcome() {
+            game.set_tag("Result", result_for(outcome));
+        }
+        Ok(game)
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(k, _)| k == name) {
+            Some(tag) => tag.1 = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// "1-0", "0-1", "1/2-1/2" or "*" for an unfinished game.
+    pub fn result(&self) -> &str {
+        self.tag("Result").unwrap_or("*")
+    }
+
+    /// Add `mv` (legal in `board`, the position at `parent`) as a child of
+    /// `parent` and return its index. Playing a move that already exists
+    /// there just returns the existing node.
+    pub fn add_move(&mut self, parent: usize, board: &Bitboards, mv: Move) -> usize {
+        if let Some(&existing) = self.nodes[parent]
+            .children
+            .iter()
+            .find(|&&c| self.nodes[c].mv == Some(mv))
+        {
+            return existing;
+        }
+        let idx = self.nodes.len();
+        self.nodes
+            .push(GameNode::new(Some(mv), to_san(board, mv), Some(parent)));
+        self.nodes[parent].children.push(idx);
+        idx
+    }
+
+    /// Node indices along the main line, root excluded.
+    pub fn mainline(&self) -> Vec<usize> {
+        let mut line = Vec::new();
+        let mut node = Self::ROOT;
+        while let Some(&next) = self.nodes[node].children.first() {
+            line.push(next);
+            node = next;
+        }
+        line
+    }
+
+    pub fn mainline_moves(&self) -> Vec<Move> {
+        self.mainline()
+            .into_iter()
+            .filter_map(|n| self.nodes[n].mv)
+            .collect()
+    }
+
+    /// Position after `node`, replayed from the start so its undo history
+    /// (and with it repetition detection) is intact.
+    pub fn board_at(&self, node: usize) -> Bitboards {
+        let mut path = Vec::new();
+        let mut cur = node;
+        while let Some(parent) = self.nodes[cur].parent {
+            path.extend(self.nodes[cur].mv);
+            cur = parent;
+        }
+        let mut board = self.start.clone();
+        for mv in path.into_iter().rev() {
+            board.make_move(mv);
+        }
+        board
+    }
+
+    /// Render as PGN text: tags (Seven Tag Roster first), then movetext
+    /// wrapped at 80 columns and terminated by the result.
+    pub fn to_pgn(&self) -> String {
+        let mut out = String::new();
+        for (name, default) in SEVEN_TAG_ROSTER {
+            let value = self.tag(name).unwrap_or(default);
+            out.push_str(&format!("[{} \"{}\"]\n", name, escape_tag(value)));
+        }
+        for (name, value) in &self.tags {
+            if !SEVEN_TAG_ROSTER.iter().any(|(n, _)| n == name) {
+                out.push_str(&format!("[{} \"{}\"]\n", name, escape_tag(value)));
+            }
+        }
+        out.push('\n');
+
+        let mut writer = MovetextWriter::default();
+        if let Some(comment) = &self.nodes[Self::ROOT].comment {
+            writer.comment(comment);
+        }
+        let mut board = self.start.clone();
+        self.write_children(Self::ROOT, &mut board, &mut writer);
+        writer.token(self.result());
+
+        out.push_str(&writer.wrap());
+        out.push('\n');
+        out
+    }
+
+    // Write the continuation from `node`: its main move, the alternatives
+    // to that move as parenthesised variations, then the rest of the main
+    // line. `board` is the position at `node` and is restored on return.
+    fn write_children(&self, node: usize, board: &mut Bitboards, w: &mut MovetextWriter) {
+        let Some((&main, alternatives)) = self.nodes[node].children.split_first() else {
+            return;
+        };
+        self.write_move(main, board, w);
+        for &alt in alternatives {
+            w.open_variation();
+            self.write_move(alt, board, w);
+            self.write_after(alt, board, w);
+            w.close_variation();
+        }
+        self.write_after(main, board, w);
+    }
+
+    fn write_after(&self, node: usize, board: &mut Bitboards, w: &mut MovetextWriter) {
+        let Some(mv) = self.nodes[node].mv else {
+            return;
+        };
+        board.make_move(mv);
+        self.write_children(node, board, w);
+        board.unmake_move();
+    }
+
+    fn write_move(&self, node: usize, board: &Bitboards, w: &mut MovetextWriter) {
+        let n = &self.nodes[node];
+        if let Some(comment) = &n.starting_comment {
+            w.comment(comment);
+        }
+        match board.side_to_move {
+            Color::White => w.token(&format!("{}.", board.fullmove_number)),
+            Color::Black if w.needs_number => w.token(&format!("{}...", board.fullmove_number)),
+            Color::Black => {}
+        }
+        w.token(&n.san);
+        w.needs_number = false;
+        for nag in &n.nags {
+            w.token(&format!("${}", nag));
+        }
+        if let Some(comment) = &n.comment {
+            w.comment(comment);
+        }
+    }
+}
+
+/// Result tag value for a decided position.
+pub fn result_for(outcome: Outcome) -> &'static str {
+    match outcome.winner() {
+        Some(Color::White) => "1-0",
+        Some(Color::Black) => "0-1",
+        None => "1/2-1/2",
+    }
+}
+
+// Collects movetext tokens and tracks when a black move needs an explicit
+// "N..." number (start of the game or a variation, after a comment, after a
+// variation closes).
+struct MovetextWriter {
+    tokens: Vec<String>,
+    needs_number: bool,
+    open_paren: bool,
+}
+
+impl Default for MovetextWriter {
+    fn default() -> Self {
+        Self {
+            tokens: Vec::new(),
+            needs_number: true,
+            open_paren: false,
+        }
+    }
+}
+
+impl MovetextWriter {
+    fn token(&mut self, text: &str) {
+        if self.open_paren {
+            self.tokens.push(format!("({}", text));
+            self.open_paren = false;
+        } else {
+            self.tokens.push(text.to_string());
+        }
+    }
+
+    fn comment(&mut self, text: &str) {
+        // A "}" inside a comment would end it early; PGN has no escape.
+        self.token(&format!("{{{}}}", text.replace('}', "")));
+        self.needs_number = true;
+    }
+
+    fn open_variation(&mut self) {
+        self.open_paren = true;
+        self.needs_number = true;
+    }
+
+    fn close_variation(&mut self) {
+        if let Some(last) = self.tokens.last_mut() {
+            last.push(')');
+        }
+        self.needs_number = true;
+    }
+
+    // Greedy word wrap. Comments may contain spaces and are split like any
+    // other text, which PGN allows.
+    fn wrap(&self) -> String {
+        let mut out = String::new();
+        let mut line_len = 0;
+        for word in self.tokens.iter().flat_map(|t| t.split_whitespace()) {
+            if line_len > 0 && line_len + 1 + word.len() > LINE_WIDTH {
+                out.push('\n');
+                line_len = 0;
+            } else if line_len > 0 {
+                out.push(' ');
+                line_len += 1;
+            }
+            out.push_str(word);
+            line_len += word.len();
+        }
+        out
+    }
+}
+
+fn escape_tag(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// ---------------------------------------------------------------------------
+// Parsing
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Tag(String, String),
+    Comment(String),
+    Nag(u8),
+    Open,
+    Close,
+    Result(String),
+    /// A SAN move, move-number prefix already stripped.
+    San(String),
+}
+
+/// Parse every game in `text`. Games are separated by their result token
+/// (or by the next tag section); movetext without any tags — like the
+/// `pgn` field Lichess sends with the daily puzzle — is accepted too.
+pub fn parse_pgn(text: &str) -> Result<Vec<PgnGame>, String> {
+    let tokens = tokenize(text)?;
+    let mut games = Vec::new();
+    let mut iter = tokens.into_iter().peekable();
+    while iter.peek().is_some() {
+        let mut tags = Vec::new();
+        while let Some(Token::Tag(..)) = iter.peek() {
+            if let Some(Token::Tag(k, v)) = iter.next() {
+                tags.push((k, v));
+            }
+        }
+        let mut movetext = Vec::new();
+        while let Some(tok) = iter.next_if(|t| !matches!(t, Token::Tag(..))) {
+            let done = matches!(tok, Token::Result(_));
+            movetext.push(tok);
+            if done {
+                break;
+            }
+        }
+        let game =
+            build_game(tags, movetext).map_err(|e| format!("game {}: {}", games.len() + 1, e))?;
+        games.push(game);
+    }
+    Ok(games)
+}
+
+fn build_game(tags: Vec<(String, String)>, movetext: Vec<Token>) -> Result<PgnGame, String> {
+    let variant = tags
+        .iter()
+        .find(|(k, _)| k == "Variant")
+        .map_or(Variant::Standard, |(_, v)| Variant::parse(v));
+    let mut start = match tags.iter().find(|(k, _)| k == "FEN") {
+        Some((_, fen)) => Bitboards::from_fen(fen)?,
+        None => Bitboards::variant_start(variant),
+    };
+    if variant != Variant::Standard {
+        start.set_variant(variant);
+    }
+    let mut game = PgnGame::from_position(start);
+    for (k, v) in &tags {
+        game.set_tag(k, v);
+    }
+
+    let mut board = game.start.clone();
+    let mut node = PgnGame::ROOT;
+    // (node, board) to return to when the current variation closes.
+    let mut stack: Vec<(usize, Bitboards)> = Vec::new();
+    let mut starting_comment: Option<String> = None;
+
+    for tok in movetext {
+        match tok {
+            Token::San(san) => {
+                let mv = parse_san(&board, &san)?;
+                node = game.add_move(node, &board, mv);
+                board.make_move(mv);
+                game.nodes[node].starting_comment = starting_comment.take();
+                game.nodes[node].nags.extend(suffix_nag(&san));
+            }
+            Token::Comment(text) => {
+                // Right after "(" the comment belongs to the variation's
+                // first move; everywhere else to the move before it.
+                let first_in_variation = !stack.is_empty() && stack_top_is(&stack, &game, node);
+                if first_in_variation {
+                    starting_comment = Some(join_comment(starting_comment, text));
+                } else {
+                    let slot = &mut game.nodes[node].comment;
+                    *slot = Some(join_comment(slot.take(), text));
+                }
+            }
+            Token::Nag(nag) => {
+                if node != PgnGame::ROOT {
+                    game.nodes[node].nags.push(nag);
+                }
+            }
+            Token::Open => {
+                let parent = game.nodes[node]
+                    .parent
+                    .ok_or_else(|| "variation before the first move".to_string())?;
+                stack.push((node, board.clone()));
+                board.unmake_move();
+                node = parent;
+            }
+            Token::Close => {
+                let (saved_node, saved_board) =
+                    stack.pop().ok_or_else(|| "unbalanced ')'".to_string())?;
+                node = saved_node;
+                board = saved_board;
+                starting_comment = None;
+            }
+            Token::Result(result) => {
+                if game.tag("Result").is_none_or(|r| r == "*") {
+                    game.set_tag("Result", &result);
+                }
+            }
+            Token::Tag(..) => {}
+        }
+    }
+    if !stack.is_empty() {
+        return Err("unterminated variation".to_string());
+    }
+    Ok(game)
+}
+
+// True while we're at the branch point of the innermost open variation,
+// i.e. no move has been played inside it yet.
+fn stack_top_is(stack: &[(usize, Bitboards)], game: &PgnGame, node: usize) -> bool {
+    stack
+        .last()
+        .is_some_and(|&(saved, _)| game.nodes[saved].parent == Some(node))
+}
+
+fn join_comment(existing: Option<String>, text: String) -> String {
+    match existing {
+        Some(prev) => format!("{} {}", prev, text),
+        None => text,
+    }
+}
+
+// Traditional suffix annotations map onto NAGs 1-6.
+fn suffix_nag(san: &str) -> Option<u8> {
+    let suffix = san.trim_start_matches(|c| c != '!' && c != '?');
+    match suffix {
+        "!" => Some(1),
+        "?" => Some(2),
+        "!!" => Some(3),
+        "??" => Some(4),
+        "!?" => Some(5),
+        "?!" => Some(6),
+        _ => None,
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line_start = true;
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => {
+                line_start = true;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            // Escape mechanism: a line starting with '%' is ignored.
+            '%' if line_start => {
+                chars.by_ref().take_while(|&c| c != '\n').for_each(drop);
+                continue;
+            }
+            ';' => {
+                let comment: String = chars.by_ref().take_while(|&c| c != '\n').collect();
+                tokens.push(Token::Comment(comment.trim().to_string()));
+                // `take_while` ate the newline.
+                line_start = true;
+                continue;
+            }
+            '{' => {
+                let mut comment = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => comment.push(c),
+                        None => return Err("unterminated comment".to_string()),
+                    }
+                }
+                let comment = comment.split_whitespace().collect::<Vec<_>>().join(" ");
+                tokens.push(Token::Comment(comment));
+            }
+            '[' => tokens.push(read_tag(&mut chars)?),
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '$' => {
+                let mut digits = String::new();
+                while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                    digits.push(d);
+                    chars.next();
+                }
+                let nag = digits
+                    .parse()
+                    .map_err(|_| format!("bad NAG '${}'", digits))?;
+                tokens.push(Token::Nag(nag));
+            }
+            _ => {
+                let mut symbol = c.to_string();
+                while let Some(&n) = chars.peek() {
+                    if n.is_whitespace() || "{}()[];$".contains(n) {
+                        break;
+                    }
+                    symbol.push(n);
+                    chars.next();
+                }
+                tokens.extend(symbol_token(&symbol));
+            }
+        }
+        line_start = false;
+    }
+    Ok(tokens)
+}
+
+fn read_tag(chars: &mut Peekable<Chars>) -> Result<Token, String> {
+    let mut name = String::new();
+    while let Some(c) = chars.next_if(|&c| c != '"' && c != ']') {
+        if !c.is_whitespace() {
+            name.push(c);
+        }
+    }
+    if chars.next() != Some('"') {
+        return Err(format!("bad tag '{}'", name));
+    }
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            Some('\\') => value.extend(chars.next()),
+            Some('"') => break,
+            Some(c) => value.push(c),
+            None => return Err(format!("unterminated tag '{}'", name)),
+        }
+    }
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    match chars.next() {
+        Some(']') => Ok(Token::Tag(name, value)),
+        _ => Err(format!("unterminated tag '{}'", name)),
+    }
+}
+
+// A whitespace-delimited symbol: a result, a move number, or SAN that may
+// still carry its move number ("12.Nf3", "12...Nf6").
+fn symbol_token(symbol: &str) -> Option<Token> {
+    if matches!(symbol, "1-0" | "0-1" | "1/2-1/2" | "*") {
+        return Some(Token::Result(symbol.to_string()));
+    }
+    if symbol.starts_with("0-0") {
+        return Some(Token::San(symbol.to_string()));
+    }
+    let san = symbol.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+    (!san.is_empty()).then(|| Token::San(san.to_string()))
+}
+
+// ---------------------------------------------------------------------------
+// Files
+
+/// Read every game in a PGN file.
+pub fn load_pgn_file(path: &Path) -> Result<Vec<PgnGame>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    parse_pgn(&text)
+}
+
+/// The tag pairs of the first game in `path`, without reading (or
+/// replaying) its moves.
+pub fn read_pgn_tags(path: &Path) -> Result<Vec<(String, String)>, String> {
+    let file = fs::File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut tags = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| format!("{}: {}", path.display(), e))?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('%') {
+            continue;
+        }
+        if !line.starts_with('[') {
+            break;
+        }
+        let mut chars = line[1..].chars().peekable();
+        if let Token::Tag(name, value) = read_tag(&mut chars)? {
+            tags.push((name, value));
+        }
+    }
+    Ok(tags)
+}
+
+/// Write `games` to `path`, replacing whatever was there.
+pub fn save_pgn_file(path: &Path, games: &[PgnGame]) -> Result<(), String> {
+    let text = games
+        .iter()
+        .map(PgnGame::to_pgn)
+        .collect::<Vec<_>>()
+        .join("\n");
+    fs::write(path, text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Add one game to the end of a collection, creating the file if needed.
+pub fn append_pgn_file(path: &Path, game: &PgnGame) -> Result<(), String> {
+    use std::io::Write;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    let separator = if file.metadata().map(|m| m.len()).unwrap_or(0) > 0 {
+        "\n"
+    } else {
+        ""
+    };
+    write!(file, "{}{}", separator, game.to_pgn()).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IMMORTAL: &str = r#"[Event "London"]
+[Site "London ENG"]
+[Date "1851.06.21"]
+[Round "?"]
+[White "Adolf Anderssen"]
+[Black "Lionel Kieseritzky"]
+[Result "1-0"]
+
+1.e4 e5 2.f4 exf4 3.Bc4 Qh4+ 4.Kf1 b5 5.Bxb5 Nf6 6.Nf3 Qh6 7.d3 Nh5 8.Nh4 Qg5
+9.Nf5 c6 10.g4 Nf6 11.Rg1 cxb5 12.h4 Qg6 13.h5 Qg5 14.Qf3 Ng8 15.Bxf4 Qf6
+16.Nc3 Bc5 17.Nd5 Qxb2 18.Bd6 Bxg1 19.e5 Qxa1+ 20.Ke2 Na6 21.Nxg7+ Kd8
+22.Qf6+ Nxf6 23.Be7# 1-0
+"#;
+
+    #[test]
+    fn parses_tags_and_mainline() {
+        let games = parse_pgn(IMMORTAL).unwrap();
+        assert_eq!(games.len(), 1);
+        let game = &games[0];
+        assert_eq!(game.tag("White"), Some("Adolf Anderssen"));
+        assert_eq!(game.result(), "1-0");
+        assert_eq!(game.mainline().len(), 45);
+        let end = game.board_at(*game.mainline().last().unwrap());
+        assert!(end.is_checkmate());
+    }
+
+    #[test]
+    fn parses_comments_nags_and_variations() {
+        let text = "{Opening} 1. e4 {King's pawn} e5 $1 2. Nf3 (2. f4!? exf4 (2... d5) 3. Nf3) \
+                    2... Nc6 ; rest of line\n3. Bb5 *";
+        let game = &parse_pgn(text).unwrap()[0];
+        assert_eq!(
+            game.nodes[PgnGame::ROOT].comment.as_deref(),
+            Some("Opening")
+        );
+        let main = game.mainline();
+        let sans: Vec<_> = main.iter().map(|&n| game.nodes[n].san.as_str()).collect();
+        assert_eq!(sans, ["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+        assert_eq!(game.nodes[main[0]].comment.as_deref(), Some("King's pawn"));
+        assert_eq!(game.nodes[main[1]].nags, [1]);
+        assert_eq!(game.nodes[main[3]].comment.as_deref(), Some("rest of line"));
+
+        // 2. f4 hangs off e5 as an alternative to 2. Nf3 ...
+        let f4 = game.nodes[main[1]].children[1];
+        assert_eq!(game.nodes[f4].san, "f4");
+        assert_eq!(game.nodes[f4].nags, [5]);
+        // ... and 2... d5 is an alternative to 2... exf4 inside it.
+        let exf4 = game.nodes[f4].children[0];
+        assert_eq!(game.nodes[exf4].san, "exf4");
+        assert_eq!(game.nodes[game.nodes[f4].children[1]].san, "d5");
+        assert_eq!(game.nodes[game.nodes[exf4].children[0]].san, "Nf3");
+        assert_eq!(game.result(), "*");
+    }
+
+    #[test]
+    fn starting_comment_in_variation() {
+        let game = &parse_pgn("1. e4 ({Or} 1. d4 {solid}) 1... e5 *").unwrap()[0];
+        let d4 = game.nodes[PgnGame::ROOT].children[1];
+        assert_eq!(game.nodes[d4].starting_comment.as_deref(), Some("Or"));
+        assert_eq!(game.nodes[d4].comment.as_deref(), Some("solid"));
+    }
+
+    #[test]
+    fn parses_multiple_games_and_fen_setup() {
+        let text = format!(
+            "{}\n[Event \"Study\"]\n[SetUp \"1\"]\n[FEN \"4k3/8/4K3/4P3/8/8/8/8 b - - 0 1\"]\n\n\
+             1... Kd8 2. Kd6 *\n\n1. d4 d5 1/2-1/2",
+            IMMORTAL
+        );
+        let games = parse_pgn(&text).unwrap();
+        assert_eq!(games.len(), 3);
+        assert_eq!(games[1].tag("Event"), Some("Study"));
+        assert_eq!(games[1].mainline().len(), 2);
+        assert_eq!(games[2].tag("Event"), Some("?"));
+        assert_eq!(games[2].result(), "1/2-1/2");
+    }
+
+    #[test]
+    fn puzzle_movetext_without_tags() {
+        let game = &parse_pgn("e4 e5 Nf3 Nc6 Bc4 Bc5").unwrap()[0];
+        assert_eq!(game.mainline().len(), 6);
+        assert_eq!(game.result(), "*");
+    }
+
+    #[test]
+    fn rejects_illegal_moves() {
+        let err = parse_pgn("[Event \"x\"]\n1. e4 e5 2. Ke3 *").unwrap_err();
+        assert!(err.starts_with("game 1"), "{}", err);
+    }
+
+    #[test]
+    fn writes_what_it_reads() {
+        let text = r#"[Event "Casual"]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "A \"quoted\" name"]
+[Black "?"]
+[Result "*"]
+[Annotator "me"]
+
+{Opening} 1. e4 {King's pawn} 1... e5 $1 2. Nf3 (2. f4 $5 exf4 (2... d5) 3. Nf3)
+2... Nc6 *
+"#;
+        let game = &parse_pgn(text).unwrap()[0];
+        assert_eq!(game.tag("White"), Some("A \"quoted\" name"));
+        assert_eq!(game.to_pgn(), text);
+    }
+
+    #[test]
+    fn writes_fen_games_and_wraps_long_lines() {
+        let game = PgnGame::from_uci_moves(
+            Bitboards::from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 40").unwrap(),
+            "e8d7 e2e4",
+        )
+        .unwrap();
+        let pgn = game.to_pgn();
+        assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 40\"]"));
+        assert!(pgn.ends_with("\n40... Kd7 41. e4 *\n"), "{}", pgn);
+
+        let immortal = &parse_pgn(IMMORTAL).unwrap()[0];
+        let pgn = immortal.to_pgn();
+        assert!(pgn.lines().all(|l| l.len() <= LINE_WIDTH));
+        assert_eq!(
+            parse_pgn(&pgn).unwrap()[0].mainline_moves(),
+            immortal.mainline_moves()
+        );
+    }
+
+    #[test]
+    fn finished_game_gets_result() {
+        let game =
+            PgnGame::from_uci_moves(Bitboards::starting_position(), "f2f3 e7e5 g2g4 d8h4").unwrap();
+        assert_eq!(game.result(), "0-1");
+        assert!(game.to_pgn().ends_with("1. f3 e5 2. g4 Qh4# 0-1\n"));
+    }
+
+    #[test]
+    fn variant_games() {
+        let text = "[Variant \"Crazyhouse\"]\n\n1. e4 d5 2. exd5 Qxd5 3. Nc3 Qa5 4. P@d5 *";
+        let game = &parse_pgn(text).unwrap()[0];
+        assert_eq!(game.start.variant, Variant::Crazyhouse);
+        assert_eq!(game.tag("FEN"), None);
+        let written = game.to_pgn();
+        assert!(written.contains("4. P@d5 *"), "{}", written);
+        assert_eq!(parse_pgn(&written).unwrap()[0].to_pgn(), written);
+
+        let horde =
+            PgnGame::from_uci_moves(Bitboards::variant_start(Variant::Horde), "a4a5").unwrap();
+        assert_eq!(horde.tag("Variant"), Some("Horde"));
+        assert_eq!(horde.tag("SetUp"), None);
+        let reread = &parse_pgn(&horde.to_pgn()).unwrap()[0];
+        assert_eq!(reread.start.to_fen(), Variant::Horde.starting_fen());
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("kindle_chess_pgn_{}.pgn", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let game = &parse_pgn(IMMORTAL).unwrap()[0];
+        append_pgn_file(&path, game).unwrap();
+        append_pgn_file(&path, &PgnGame::new()).unwrap();
+        let loaded = load_pgn_file(&path).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].mainline_moves(), game.mainline_moves());
+
+        save_pgn_file(&path, &loaded[..1]).unwrap();
+        assert_eq!(load_pgn_file(&path).unwrap().len(), 1);
+        fs::remove_file(&path).unwrap();
+    }
+}
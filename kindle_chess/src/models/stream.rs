@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+// ~~~~~~~~~~~~~~~~ STREAMS ~~~~~~~~~~~~~~~~
+// The long-lived Lichess streams (account events, game state) and what keeps
+// them up when the connection drops. The supervisor is in api/stream.rs,
+// the backoff arithmetic in app/stream.rs.
+
+/// Which stream the supervisor keeps up. Only the game stream's state is
+/// reported with `StreamStatus`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamKind {
+    Account,
+    Game,
+}
+
+/// What the supervisor reports while a stream is down. A stream that's up
+/// says so with its first event (for a game, the `gameFull` it resyncs
+/// from).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamState {
+    /// Lost the connection; trying again in `retry_in`. `attempt` counts
+    /// from 1 since the stream was last up.
+    Reconnecting { attempt: u32, retry_in: Duration },
+    /// Lichess refused the stream (bad token, no such game); not retried.
+    Lost(String),
+}
+
+/// How a connection to a stream ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamEnd {
+    /// The connection closed, failed or fell silent while there was more to
+    /// come. Only a connection that delivered an event counts as having been
+    /// up: one dropped straight away keeps the backoff growing.
+    Dropped { received_any: bool },
+    /// There's nothing more to stream (the game is over).
+    Finished,
+}
+
+/// Why a stream couldn't be opened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamFailure {
+    /// A client error from Lichess: retrying won't help.
+    Refused(String),
+    /// The network or the server; worth another try.
+    Failed(String),
+}
+
+/// Exponential backoff between reconnects: `base` doubling up to `max`,
+/// with each delay jittered down to no less than half.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    pub base: Duration,
+    pub max: Duration,
+    pub(crate) attempt: u32,
+}
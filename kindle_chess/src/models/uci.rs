@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+use tokio::sync::Mutex;
+
+use crate::models::bitboard::Move;
+
+// ~~~~~~~~~~~~~~~~ UCI ~~~~~~~~~~~~~~~~
+// An external engine (a static ARM Stockfish, say) driven over the UCI
+// protocol as a child process. The protocol code lives in local/uci.rs.
+
+/// A running engine that has finished the `uci`/`isready` handshake.
+#[derive(Debug)]
+pub struct UciEngine {
+    /// From the engine's `id name` line; empty if it never sent one.
+    pub name: String,
+    /// Names of the options the engine advertised, as spelled by it.
+    pub options: Vec<String>,
+    pub(crate) child: Child,
+    pub(crate) stdin: ChildStdin,
+    pub(crate) lines: Lines<BufReader<ChildStdout>>,
+}
+
+/// One engine binary shared by every clone of a game. The process is
+/// started on first use and kept for later searches; the lock also keeps
+/// two searches from talking to it at once.
+#[derive(Debug, Clone)]
+pub struct UciHandle {
+    pub path: PathBuf,
+    pub(crate) engine: Arc<Mutex<Option<UciEngine>>>,
+}
+
+/// A search report from an `info` line. Lines carrying neither a depth nor
+/// a score (`info string ...`, `info currmove ...`) aren't reported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UciInfo {
+    pub depth: u8,
+    pub seldepth: Option<u8>,
+    pub multipv: Option<u8>,
+    pub score: Option<UciScore>,
+    pub nodes: Option<u64>,
+    pub nps: Option<u64>,
+    pub time: Option<Duration>,
+    /// The principal variation as far as it is legal from the searched
+    /// position.
+    pub pv: Vec<Move>,
+}
+
+/// From the side to move's point of view, as UCI sends it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UciScore {
+    Centipawns(i32),
+    /// Mate in this many moves; negative when the side to move is mated.
+    Mate(i32),
+}
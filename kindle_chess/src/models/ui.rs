@@ -8,14 +8,25 @@ use std::{
 };
 
 use image::{ImageBuffer, Luma};
+use tokio::task::JoinHandle;
 
 use crate::{
     api::github::UpdateInfo,
-    models::{board_api::GameDataList, chess::ChessApp},
+    models::{
+        bitboard::Color,
+        board_api::{ChallengeEvent, GameDataList, GameStartEvent, PendingMove, PlayedBy},
+        challenge::{ChallengeRequest, ChallengeTarget, CreatedChallenge, Seek},
+        board_local::SaveSummary,
+        chess::ChessApp,
+        clock::GameClock,
+        engine::Strength,
+    },
     ui::{
         events::{AppEvent, Rectangle, RectangleExt},
         renderer::Renderer,
-        widgets::{BoardWidget, Button, SidebarWidget},
+        widgets::{
+            BoardWidget, Button, ChatOverlay, KeyboardWidget, PlayerBarWidget, SidebarWidget,
+        },
     },
 };
 
@@ -45,32 +56,64 @@ pub trait Screen {
         event: AppEvent,
         display: &mut Display,
     ) -> Result<Transition, Box<dyn Error>>;
+
+    // Whether a starting Lichess game is this screen's business: the game
+    // it's playing, or the one a challenge or seek of its own turns into
+    // (Lichess gives a challenge's game the challenge's id). A claimed game
+    // is handed to the screen rather than opened again.
+    fn claims_game(&self, _game_id: &str) -> bool {
+        false
+    }
+
+    // The same for a game that's just started, for screens that can tell
+    // theirs only from what kind of game it is (a seek's pairing).
+    fn claims_start(&self, game: &GameStartEvent) -> bool {
+        self.claims_game(&game.game_id)
+    }
+
+    // Whether a game is in progress here. Its stream, ticker and engine
+    // send their events to the top screen, so nothing is pushed over it.
+    fn plays_game(&self) -> bool {
+        false
+    }
 }
 
 // ─── Transition ───────────────────────────────────────────────────────────────
 
 pub enum Transition {
-    Stay,                  // keep current screen, no redraw needed
-    Redraw,                // keep current screen, request a redraw
-    Push(Box<dyn Screen>), // navigate forward to a new screen
-    Pop,                   // return to the previous screen
-    Quit,                  // exit the application
+    Stay,                     // keep current screen, no redraw needed
+    Redraw,                   // keep current screen, request a redraw
+    Push(Box<dyn Screen>),    // navigate forward to a new screen
+    Replace(Box<dyn Screen>), // swap the current screen for a new one
+    Pop,                      // return to the previous screen
+    Quit,                     // exit the application
 }
 
 // ─── HomeScreen ───────────────────────────────────────────────────────────────
 // The top-level launcher. Add a button here for every future game.
 
 pub struct HomeScreen {
+    pub pass_and_play_button: Button,
+    // Toggles `auto_flip` for the next pass-and-play game.
+    pub auto_flip_button: Button,
+    pub auto_flip: bool,
     pub chess_button: Button,
+    // Cycles the built-in engine's level for the Demo (offline) game.
+    pub level_button: Button,
+    pub strength: Strength,
     pub ongoing_games_button: Button,
+    // Online only: open the SeekScreen and the NewChallengeScreen.
+    pub play_online_button: Button,
+    pub challenge_button: Button,
     pub settings_button: Button,
 
     // Auth bootstrap state. `auth_started` flips to true on the first render so
-    // we kick the token check exactly once. The chess/ongoing-games buttons
-    // stay inert until `app` is populated — either by the bootstrap (token
-    // already valid) or by a ChessReady event bubbling up from a popped
-    // ChessAuthScreen. The settings button is always live: an offline user
-    // still needs to be able to update.
+    // we kick the token check exactly once. `app` is populated either by the
+    // bootstrap (token already valid) or by a ChessReady event bubbling up
+    // from a popped ChessAuthScreen. Every button works without it: an
+    // offline user still needs to be able to update, to play (Demo and
+    // pass-and-play), and to resume saved offline games — the ongoing-games
+    // list just has no Lichess games until `app` is set.
     pub app: Option<ChessApp>,
     pub auth_started: bool,
 }
@@ -84,7 +127,28 @@ impl HomeScreen {
         const CENTER_X: i16 = 1072 / 2; // 336
         const CENTER_Y: i16 = 1448 / 2; // 304
 
+        let strength = Strength::default();
+        let auto_flip = true;
         Self {
+            pass_and_play_button: Button::new(
+                CENTER_X - BTN_W as i16 / 2,
+                CENTER_Y - (10 + BTN_H as i16) - 2 * 20 - (BTN_H as i16 - 20) - BTN_H as i16,
+                BTN_W,
+                BTN_H,
+                String::from("Pass & play"),
+                45.0,
+                true,
+            ),
+            auto_flip_button: Button::new(
+                CENTER_X - BTN_W as i16 / 2,
+                CENTER_Y - (10 + BTN_H as i16) - 20 - (BTN_H as i16 - 20),
+                BTN_W,
+                BTN_H - 20,
+                auto_flip_label(auto_flip),
+                40.0,
+                true,
+            ),
+            auto_flip,
             chess_button: Button::new(
                 CENTER_X - BTN_W as i16 / 2,
                 CENTER_Y - (10 + BTN_H as i16),
@@ -94,6 +158,16 @@ impl HomeScreen {
                 45.0,
                 true,
             ),
+            level_button: Button::new(
+                CENTER_X - BTN_W as i16 / 2,
+                CENTER_Y + 10,
+                BTN_W,
+                BTN_H - 20,
+                level_label(strength),
+                40.0,
+                true,
+            ),
+            strength,
             ongoing_games_button: Button::new(
                 CENTER_X - BTN_W as i16 / 2,
                 CENTER_Y + 10 + BTN_H as i16,
@@ -103,10 +177,28 @@ impl HomeScreen {
                 45.0,
                 true,
             ),
-            settings_button: Button::new(
+            play_online_button: Button::new(
                 CENTER_X - BTN_W as i16 / 2,
                 CENTER_Y + 10 + BTN_H as i16 + BTN_H as i16 + 20,
                 BTN_W,
+                BTN_H,
+                String::from("Play online"),
+                45.0,
+                true,
+            ),
+            challenge_button: Button::new(
+                CENTER_X - BTN_W as i16 / 2,
+                CENTER_Y + 10 + 3 * BTN_H as i16 + 2 * 20,
+                BTN_W,
+                BTN_H,
+                String::from("New challenge"),
+                45.0,
+                true,
+            ),
+            settings_button: Button::new(
+                CENTER_X - BTN_W as i16 / 2,
+                CENTER_Y + 10 + 4 * BTN_H as i16 + 3 * 20,
+                BTN_W,
                 BTN_H - 20,
                 String::from("Settings"),
                 40.0,
@@ -118,6 +210,28 @@ impl HomeScreen {
     }
 }
 
+pub fn level_label(strength: Strength) -> String {
+    format!("Engine level {}", strength.level())
+}
+
+pub fn auto_flip_label(auto_flip: bool) -> String {
+    format!("Auto-flip board: {}", if auto_flip { "on" } else { "off" })
+}
+
+/// Player bar text: "GM Name (2850)", or the AI's level.
+pub fn player_label(player: &PlayedBy) -> String {
+    match player {
+        PlayedBy::User(p) => match &p.title {
+            Some(title) => format!("{} {} ({})", title, p.name, p.rating),
+            None => format!("{} ({})", p.name, p.rating),
+        },
+        PlayedBy::Ai(ai) => match ai.ai_level {
+            Some(level) => format!("Stockfish level {}", level),
+            None => "Stockfish".to_string(),
+        },
+    }
+}
+
 // ─── ChessGameScreen ──────────────────────────────────────────────────────────
 
 pub struct ChessGameScreen {
@@ -126,6 +240,25 @@ pub struct ChessGameScreen {
     pub sidebar: SidebarWidget,
     // First-render guard: kick the game-state stream task exactly once.
     pub stream_started: bool,
+    // Online only: the supervised game-state stream, stopped on drop.
+    pub stream: Option<JoinHandle<()>>,
+    // Online only: our move, already on the board, until the stream echoes
+    // it or the POST fails.
+    pub pending: Option<PendingMove>,
+    // Online timed games: the clocks as of the last server update, run
+    // down by `Tick`s from `ticker` in between. Drawn in the player bars.
+    pub clock: Option<GameClock>,
+    pub ticker: Option<JoinHandle<()>>,
+    // Above and below the board, for whichever sides are on those edges.
+    pub top_bar: PlayerBarWidget,
+    pub bottom_bar: PlayerBarWidget,
+    // What the bars call white and black (see `player_label`).
+    pub player_labels: (String, String),
+    // When the opponent has been gone long enough to claim the win; the
+    // sidebar prompts for it on the first `Tick` after.
+    pub claim_at: Option<Instant>,
+    // Online only: drawn over the board while open.
+    pub chat: ChatOverlay,
 }
 
 impl ChessGameScreen {
@@ -133,23 +266,231 @@ impl ChessGameScreen {
         // Seed the sidebar with the initial turn from `attach_game` so the
         // sidebar shows "Your turn" / "Waiting…" immediately, before the
         // game-state stream catches up.
-        let mut sidebar = SidebarWidget::new(Rectangle::new(0, 1072, 1072, 376));
+        // Top to bottom: player bar, board, player bar, sidebar.
+        let mut sidebar = SidebarWidget::new(Rectangle::new(0, 1192, 1072, 256));
         if let Some(turn) = app.turn() {
             sidebar.set_turn(turn.clone());
         }
         Self {
             app,
-            board: BoardWidget::new(Rectangle::new(0, 0, 1072, 1072)),
+            board: BoardWidget::new(Rectangle::new(0, 60, 1072, 1072)),
             sidebar,
             stream_started: false,
+            stream: None,
+            pending: None,
+            clock: None,
+            ticker: None,
+            top_bar: PlayerBarWidget::new(Rectangle::new(0, 0, 1072, 60), Color::Black),
+            bottom_bar: PlayerBarWidget::new(Rectangle::new(0, 1132, 1072, 60), Color::White),
+            player_labels: (String::from("White"), String::from("Black")),
+            claim_at: None,
+            chat: ChatOverlay::new(Rectangle::new(0, 60, 1072, 1072)),
+        }
+    }
+}
+
+// ─── ChallengeScreen ──────────────────────────────────────────────────────────
+// Pushed by the app when the account stream announces a challenge to us.
+// Declining pops it; accepting waits for the game to start and then swaps
+// in its ChessGameScreen.
+
+pub struct ChallengeScreen {
+    pub app: ChessApp,
+    pub challenge: ChallengeEvent,
+    pub accept_button: Button,
+    pub decline_button: Button,
+    // Set once an answer is sent, until it's through; `accepted` then waits
+    // on the game. A failure lands in `error` and the buttons work again.
+    pub answering: bool,
+    pub accepted: bool,
+    pub error: Option<String>,
+}
+
+impl ChallengeScreen {
+    pub fn new(app: ChessApp, challenge: ChallengeEvent) -> Self {
+        const BTN_W: u16 = 400;
+        const BTN_H: u16 = 120;
+        const CENTER_X: i16 = 1072 / 2;
+        const CENTER_Y: i16 = 1448 / 2;
+        Self {
+            app,
+            challenge,
+            accept_button: Button::new(
+                CENTER_X - BTN_W as i16 - 20,
+                CENTER_Y + 100,
+                BTN_W,
+                BTN_H,
+                String::from("Accept"),
+                45.0,
+                true,
+            ),
+            decline_button: Button::new(
+                CENTER_X + 20,
+                CENTER_Y + 100,
+                BTN_W,
+                BTN_H,
+                String::from("Decline"),
+                45.0,
+                true,
+            ),
+            answering: false,
+            accepted: false,
+            error: None,
         }
     }
 }
 
+// ─── NewChallengeScreen ───────────────────────────────────────────────────────
+// Builds a challenge from the home screen: who to (a friend by name, an open
+// challenge, the Lichess AI), variant, time control, colour and rated, each
+// a button cycling through its choices. A friend's name is typed on the
+// on-screen keyboard. Once sent, the screen waits for the game to start and
+// swaps in its ChessGameScreen; an open challenge shows its link as a QR
+// code meanwhile.
+
+pub struct NewChallengeScreen {
+    pub app: ChessApp,
+    pub target: ChallengeTarget,
+    pub request: ChallengeRequest,
+    pub target_button: Button,
+    // The friend's name, or the AI's level; hidden for an open challenge.
+    pub detail_button: Button,
+    pub variant_button: Button,
+    pub time_button: Button,
+    pub color_button: Button,
+    pub rated_button: Button,
+    pub send_button: Button,
+    pub back_button: Button,
+    pub keyboard: KeyboardWidget,
+    pub qr_area: Rectangle,
+    // `sending` while the POST is out; `sent` once Lichess has it, until
+    // it's answered or withdrawn. `status` is the line under the options.
+    pub sending: bool,
+    pub sent: Option<CreatedChallenge>,
+    pub qr_image: Option<ImageBuffer<Luma<u8>, Vec<u8>>>,
+    pub status: Option<String>,
+}
+
+impl NewChallengeScreen {
+    pub fn new(app: ChessApp) -> Self {
+        const BTN_W: u16 = 800;
+        const BTN_H: u16 = 90;
+        const LEFT: i16 = (1072 - BTN_W as i16) / 2;
+        let row = |i: i16, label: String| {
+            Button::new(LEFT, 160 + i * (BTN_H as i16 + 15), BTN_W, BTN_H, label, 40.0, true)
+        };
+        let target = ChallengeTarget::User(String::new());
+        let request = ChallengeRequest::default();
+        let mut screen = Self {
+            app,
+            target_button: row(0, String::new()),
+            detail_button: row(1, String::new()),
+            variant_button: row(2, String::new()),
+            time_button: row(3, String::new()),
+            color_button: row(4, String::new()),
+            rated_button: row(5, String::new()),
+            send_button: Button::new(LEFT, 1300, 380, 110, String::from("Send"), 45.0, true),
+            back_button: Button::new(
+                LEFT + BTN_W as i16 - 380,
+                1300,
+                380,
+                110,
+                String::new(),
+                45.0,
+                true,
+            ),
+            keyboard: KeyboardWidget::new(Rectangle::new(30, 820, 1012, 350)),
+            qr_area: Rectangle::new(336, 800, 400, 400),
+            target,
+            request,
+            sending: false,
+            sent: None,
+            qr_image: None,
+            status: None,
+        };
+        screen.relabel();
+        screen
+    }
+
+    /// Bring every button's label in line with the current choices.
+    pub fn relabel(&mut self) {
+        self.target_button.label = format!("Opponent: {}", self.target.label());
+        self.detail_button.label = match &self.target {
+            ChallengeTarget::User(name) if name.is_empty() => "Type a username".to_string(),
+            ChallengeTarget::User(name) => name.clone(),
+            ChallengeTarget::Open => String::new(),
+            ChallengeTarget::Ai(level) => format!("AI level {}", level),
+        };
+        self.variant_button.label = format!("Variant: {}", self.request.variant.name());
+        self.time_button.label = format!("Time: {}", self.request.time.label());
+        self.color_button.label = format!("You play: {}", self.request.color.as_str());
+        self.rated_button.label = match self.target {
+            ChallengeTarget::Ai(_) => "Rated: no (AI games never are)".to_string(),
+            _ => format!("Rated: {}", if self.request.rated { "yes" } else { "no" }),
+        };
+        self.back_button.label = String::from(if self.sent.is_some() { "cancel" } else { "back" });
+    }
+}
+
+// ─── SeekScreen ───────────────────────────────────────────────────────────────
+// "Play online": pick a time control and rated or casual, then wait in the
+// lobby for Lichess to pair the seek. The game it starts replaces this
+// screen.
+
+pub struct SeekScreen {
+    pub app: ChessApp,
+    pub seek: Seek,
+    pub time_button: Button,
+    pub rated_button: Button,
+    // "Seek", or "Cancel" while seeking.
+    pub seek_button: Button,
+    pub back_button: Button,
+    // The task holding the seek request open, then waiting out the grace
+    // for its game to be announced; aborting it takes the seek off the
+    // lobby.
+    pub seeking: Option<JoinHandle<()>>,
+    pub status: Option<String>,
+}
+
+impl SeekScreen {
+    pub fn new(app: ChessApp) -> Self {
+        const BTN_W: u16 = 800;
+        const BTN_H: u16 = 120;
+        const LEFT: i16 = (1072 - BTN_W as i16) / 2;
+        let mut screen = Self {
+            app,
+            seek: Seek::default(),
+            time_button: Button::new(LEFT, 300, BTN_W, BTN_H, String::new(), 45.0, true),
+            rated_button: Button::new(LEFT, 440, BTN_W, BTN_H, String::new(), 45.0, true),
+            seek_button: Button::new(LEFT, 800, BTN_W, BTN_H, String::new(), 45.0, true),
+            back_button: Button::new(
+                LEFT,
+                1448 - (BTN_H as i16 - 20) - 32,
+                BTN_W,
+                BTN_H - 20,
+                String::from("back"),
+                40.0,
+                true,
+            ),
+            seeking: None,
+            status: None,
+        };
+        screen.relabel();
+        screen
+    }
+
+    pub fn relabel(&mut self) {
+        self.time_button.label = format!("Time: {}+{}", self.seek.minutes, self.seek.increment);
+        self.rated_button.label = format!("Rated: {}", if self.seek.rated { "yes" } else { "no" });
+        self.seek_button.label = String::from(if self.seeking.is_some() { "Cancel" } else { "Seek" });
+    }
+}
+
 // ─── ChessGameScreen ──────────────────────────────────────────────────────────
 
 pub struct OngoingChessGamesScreen {
-    pub app: ChessApp,
+    // `None` when not signed in: only saved offline games are listed.
+    pub app: Option<ChessApp>,
     pub prev_page_button: Button,
     pub next_page_button: Button,
     pub chessgame_button_0: Button,
@@ -163,6 +504,9 @@ pub struct OngoingChessGamesScreen {
     pub games: Option<Arc<GameDataList>>,
     pub error: Option<String>,
     pub loading: bool,
+    // Unfinished offline savegames, listed after the Lichess games. Scanned
+    // off the UI thread on first render (`LocalGamesLoaded`).
+    pub local_games: Vec<SaveSummary>,
 
     // Pagination: 4 game buttons per page. Labels are baked into the buttons by
     // `set_page` (called on initial load and on next/prev taps), so `render`
@@ -171,7 +515,7 @@ pub struct OngoingChessGamesScreen {
 }
 
 impl OngoingChessGamesScreen {
-    pub fn new(app: ChessApp) -> Self {
+    pub fn new(app: Option<ChessApp>) -> Self {
         // Layout: 1072 × 1448 total canvas
         const BTN_W: u16 = 800;
         const BTN_H: u16 = 120;
@@ -246,6 +590,7 @@ impl OngoingChessGamesScreen {
             games: None,
             error: None,
             loading: false,
+            local_games: Vec::new(),
             page_index: 0,
         }
     }
@@ -5,10 +5,16 @@ use x11rb::protocol::xproto;
 
 use crate::api::github::UpdateInfo;
 use crate::models::{
-    bitboard::Bitboards,
-    board_api::{GameDataList, PlayedBy, Turn},
+    bitboard::{Bitboards, Move, Piece},
+    board_api::{ChallengeEvent, GameDataList, GameStartEvent, Offer, PlayedBy, Turn},
+    board_local::SaveSummary,
+    challenge::CreatedChallenge,
+    chat::{ChatMessage, ChatRoom},
     chess::ChessApp,
     oauth::{LichessUser, TokenInfo},
+    clock::GameClock,
+    stream::{StreamKind, StreamState},
+    uci::UciInfo,
 };
 
 #[derive(Debug, Clone)]
@@ -21,6 +27,8 @@ pub enum AppEvent {
     // Ongoing-games fetch
     OngoingGamesLoaded(Arc<GameDataList>),
     OngoingGamesFailed(String),
+    // The unfinished offline savegames, scanned off the UI thread.
+    LocalGamesLoaded(Vec<SaveSummary>),
 
     // Update flow → UpdateScreen.
     // - Available: a strictly newer release was found, with verified asset metadata.
@@ -51,21 +59,87 @@ pub enum AppEvent {
         // (fresh game). Drives the board widget's last-move corner-bracket
         // highlight.
         last_move: u64,
+        // Time left as of this event; `None` for untimed games.
+        clock: Option<GameClock>,
     },
     TurnChanged {
         turn: Turn,
         board: Bitboards,
         last_move: u64,
+        clock: Option<GameClock>,
     },
+    // The opponent's open offer, sent with every game state; `None` once
+    // there's nothing to answer.
+    OpponentOffer(Option<Offer>),
+    // The opponent left: victory can be claimed after this long. `None`
+    // when they're back.
+    OpponentGone(Option<Duration>),
+    // A line said in the game's chat, and the player room's history as
+    // fetched when the game opens.
+    ChatReceived(ChatMessage),
+    ChatLoaded(Vec<ChatMessage>),
+    // The server refused a move we already drew (the POST failed); the
+    // screen takes it back and shows `reason`.
+    MoveRejected {
+        uci: String,
+        reason: String,
+    },
+
+    // Account event stream → App. Runs for the whole session once signed
+    // in. A fresh game and an incoming challenge each push their screen;
+    // the rest goes to whichever screen is on top.
+    GameStarted(GameStartEvent),
+    GameFinished(String),
+    ChallengeReceived(ChallengeEvent),
+    ChallengeCanceled(String),
+    // One of our challenges was turned down, with the decliner's reason.
+    ChallengeDeclined {
+        id: String,
+        reason: String,
+    },
+    // Outcome of sending one from the new-challenge screen.
+    ChallengeCreated(CreatedChallenge),
+    ChallengeFailed(String),
+    // The seek request ended in an error, or Lichess closed it (expired,
+    // or cancelled on the website) without pairing us.
+    SeekFailed(String),
+    SeekEnded,
+    // The game stream is down (see api/stream.rs). The game screen shows
+    // it in the sidebar until the next GameFullReceived.
+    StreamStatus(StreamKind, StreamState),
+    // Outcome of answering on the challenge screen: whether it was an
+    // accept that went through, or why it didn't.
+    ChallengeAnswered(bool),
+    ChallengeAnswerFailed(String),
 
     // UI Events
     Touch(TouchEvent),
+    // From the on-screen keyboard.
+    KeyTyped(char),
+    KeyErased,
     Redraw,
+    // Time since the previous tick, from the game screen's ticker while a
+    // game is open. Runs the clocks between server updates.
     Tick(Duration),
 
     // Chess Events
     MoveMade(ChessMove),
     SquareSelected(Square),
+    // Answers from the sidebar's prompts.
+    AnswerOffer(Offer, bool),
+    ClaimVictory,
+    // The chat overlay over the board, and a quick reply picked in it.
+    OpenChat,
+    CloseChat,
+    SendChat(ChatRoom, String),
+    // Show the position after this many plies (a tapped move in the
+    // sidebar's list), or go back to the live one with `None`.
+    BrowseTo(Option<usize>),
+    // The built-in engine's reply in an offline game, searched on the
+    // blocking pool against the position it was handed.
+    EngineMoved(Move),
+    // Progress from an external UCI engine's search, one per `info` line.
+    EngineInfo(UciInfo),
 
     // Navigation
     ShowMenu,
@@ -112,6 +186,8 @@ impl Square {
 pub struct ChessMove {
     pub from: Square,
     pub to: Square,
+    // Picked in the board's promotion overlay; `None` for any other move.
+    pub promotion: Option<Piece>,
 }
 
 pub type Rectangle = xproto::Rectangle;
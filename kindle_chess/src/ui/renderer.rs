@@ -132,15 +132,22 @@ impl Renderer {
         Ok(())
     }
 
+    /// Circle outline at thickness `width`, with the same line-width
+    /// handling as `draw_line`.
     pub fn draw_circle(
         &mut self,
         center_x: i16,
         center_y: i16,
         radius: u16,
         color: DrawColor,
+        width: u16,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let gc = self.gcs[&color];
 
+        if width >= 2 {
+            self.conn
+                .change_gc(gc, &ChangeGCAux::new().line_width(width as u32))?;
+        }
         // Use the X11 Arc type explicitly
         self.conn.poly_arc(
             self.window,
@@ -155,6 +162,34 @@ impl Renderer {
                 angle2: 360 * 64,
             }],
         )?;
+        if width >= 2 {
+            self.conn.change_gc(gc, &ChangeGCAux::new().line_width(0))?;
+        }
+
+        self.dirty = true;
+        Ok(())
+    }
+
+    pub fn fill_circle(
+        &mut self,
+        center_x: i16,
+        center_y: i16,
+        radius: u16,
+        color: DrawColor,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let gc = self.gcs[&color];
+        self.conn.poly_fill_arc(
+            self.window,
+            gc,
+            &[xproto::Arc {
+                x: center_x - radius as i16,
+                y: center_y - radius as i16,
+                width: radius * 2,
+                height: radius * 2,
+                angle1: 0,
+                angle2: 360 * 64,
+            }],
+        )?;
 
         self.dirty = true;
         Ok(())
@@ -5,15 +5,26 @@ use log::{debug, info, warn};
 use crate::{
     api::{
         github::{check_for_update, UpdateInfo},
-        oauth::{authenticate, get_user_info, load_token},
+        oauth::{authenticate, generate_qr_code, get_user_info, load_token},
+        stream::supervise,
         update::apply_update,
     },
+    local::engine::best_move,
     models::{
-        board_api::PlayedBy,
+        board_api::{
+            GameData, GameDataList, GameStartEvent, Offer, PendingMove, PlayedBy, Reconciled, Turn,
+        },
+        bitboard::{Bitboards, Color},
+        board_local::{BoardLocal, LocalOpponent, SaveSummary},
+        challenge::{ChallengeColor, ChallengeTarget, Seek},
         chess::ChessApp,
+        clock::GameClock,
+        engine::Strength,
+        stream::{Backoff, StreamKind},
         ui::{
-            ChessAuthScreen, ChessGameScreen, Display, HomeScreen, OngoingChessGamesScreen, Screen,
-            SettingsScreen, Transition, UpdateScreen, UpdateState,
+            ChallengeScreen, ChessAuthScreen, ChessGameScreen, Display, HomeScreen,
+            NewChallengeScreen, OngoingChessGamesScreen, Screen, SeekScreen, SettingsScreen, Transition, UpdateScreen, UpdateState,
+            auto_flip_label, level_label, player_label,
         },
     },
     ui::{
@@ -24,6 +35,8 @@ use crate::{
     version,
 };
 use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
 
 // ─── HomeScreen ───────────────────────────────────────────────────────────────
 
@@ -38,8 +51,13 @@ impl Screen for HomeScreen {
         }
 
         display.renderer.clear(DrawColor::White)?;
+        self.pass_and_play_button.draw(&mut display.renderer)?;
+        self.auto_flip_button.draw(&mut display.renderer)?;
         self.chess_button.draw(&mut display.renderer)?;
+        self.level_button.draw(&mut display.renderer)?;
         self.ongoing_games_button.draw(&mut display.renderer)?;
+        self.play_online_button.draw(&mut display.renderer)?;
+        self.challenge_button.draw(&mut display.renderer)?;
         self.settings_button.draw(&mut display.renderer)?;
 
         display.renderer.present()?;
@@ -75,20 +93,52 @@ impl Screen for HomeScreen {
                         info!("Settings button pressed");
                         return Ok(Transition::Push(Box::new(SettingsScreen::new())));
                     }
-
-                    let Some(app) = self.app.clone() else {
-                        info!("Button tap ignored — auth not yet complete");
-                        return Ok(Transition::Stay);
-                    };
+                    // The offline games — Demo against the engine, and
+                    // pass-and-play — need no account or network.
+                    if self.pass_and_play_button.rect.contains(touch.x, touch.y) {
+                        info!("Pass & play button pressed — auto-flip {}", self.auto_flip);
+                        let app = ChessApp::new_pass_and_play(self.auto_flip);
+                        return Ok(Transition::Push(Box::new(ChessGameScreen::new(app))));
+                    }
+                    if self.auto_flip_button.rect.contains(touch.x, touch.y) {
+                        self.auto_flip = !self.auto_flip;
+                        self.auto_flip_button.label = auto_flip_label(self.auto_flip);
+                        return Ok(Transition::Redraw);
+                    }
                     if self.chess_button.rect.contains(touch.x, touch.y) {
-                        info!("Chess button pressed — launching chess game");
+                        info!(
+                            "Demo button pressed — offline game at level {}",
+                            self.strength.level()
+                        );
+                        let app = ChessApp::new_offline(self.strength);
                         return Ok(Transition::Push(Box::new(ChessGameScreen::new(app))));
-                    } else if self.ongoing_games_button.rect.contains(touch.x, touch.y) {
+                    }
+                    if self.level_button.rect.contains(touch.x, touch.y) {
+                        self.strength = self.strength.cycle();
+                        self.level_button.label = level_label(self.strength);
+                        return Ok(Transition::Redraw);
+                    }
+
+                    if self.ongoing_games_button.rect.contains(touch.x, touch.y) {
                         info!("Ongoing-games button pressed");
                         return Ok(Transition::Push(Box::new(OngoingChessGamesScreen::new(
-                            app,
+                            self.app.clone(),
                         ))));
                     }
+                    if self.play_online_button.rect.contains(touch.x, touch.y) {
+                        let Some(app) = self.app.clone() else {
+                            info!("Play online needs a Lichess account — ignored");
+                            return Ok(Transition::Stay);
+                        };
+                        return Ok(Transition::Push(Box::new(SeekScreen::new(app))));
+                    }
+                    if self.challenge_button.rect.contains(touch.x, touch.y) {
+                        let Some(app) = self.app.clone() else {
+                            info!("New challenge needs a Lichess account — ignored");
+                            return Ok(Transition::Stay);
+                        };
+                        return Ok(Transition::Push(Box::new(NewChallengeScreen::new(app))));
+                    }
                 }
 
                 Ok(Transition::Redraw)
@@ -159,12 +209,27 @@ impl Screen for ChessGameScreen {
         // First paint after Push: spawn the game-state stream task. It owns a
         // clone of BoardAPI<InGame>; everything we care about comes back as
         // GameFullReceived / TurnChanged events (see kick_game_stream).
+        // Offline there is no stream: the position is already here, and the
+        // engine may have the first move.
         if !self.stream_started {
             self.stream_started = true;
-            kick_game_stream(&self.app, display.event_tx.clone());
+            if self.app.offline().is_some() {
+                self.sync_offline(display.event_tx.clone());
+            } else {
+                self.stream = kick_game_stream(&self.app, display.event_tx.clone());
+                kick_chat_history(&self.app, display.event_tx.clone());
+                self.sidebar.enable_chat();
+                self.ticker = Some(kick_clock_ticker(display.event_tx.clone()));
+            }
         }
 
-        self.board.render(&mut display.renderer)?;
+        if self.chat.is_open() {
+            self.chat.render(&mut display.renderer)?;
+        } else {
+            self.board.render(&mut display.renderer)?;
+        }
+        self.top_bar.render(&mut display.renderer)?;
+        self.bottom_bar.render(&mut display.renderer)?;
         self.sidebar.render(&mut display.renderer)?;
         display.renderer.present()?;
         Ok(())
@@ -183,7 +248,16 @@ impl Screen for ChessGameScreen {
                 turn,
                 board,
                 last_move,
+                clock,
             } => {
+                let current = self.reconcile_pending(&turn, &board);
+                // A reconnect: the stream is back, but chat said while it
+                // was down is only in the history.
+                if !self.sidebar.is_connected() {
+                    self.sidebar.set_connection(None);
+                    kick_chat_history(&self.app, display.event_tx.clone());
+                }
+                self.player_labels = (player_label(&white), player_label(&black));
                 self.app
                     .apply_game_full(white, black, player0_white, turn.clone());
                 // Orient the board so the local player's pieces are on the
@@ -191,9 +265,20 @@ impl Screen for ChessGameScreen {
                 // no-op when orientation is unchanged, so re-applying on
                 // every GameFull is cheap.
                 self.board.set_flipped(!player0_white);
-                self.board.set_position(board);
-                self.board.set_last_move(last_move);
-                self.sidebar.set_turn(turn);
+                self.board.set_movable(Some(if player0_white {
+                    Color::White
+                } else {
+                    Color::Black
+                }));
+                if current {
+                    self.show_live(board, last_move);
+                    self.set_clock(clock);
+                    self.sidebar.set_turn(turn);
+                } else {
+                    // Keep showing our move (and "Waiting") until it's in.
+                    self.app.apply_turn(Turn::Waiting);
+                }
+                self.refresh_bars();
                 Ok(Transition::Redraw)
             }
 
@@ -201,16 +286,25 @@ impl Screen for ChessGameScreen {
                 turn,
                 board,
                 last_move,
+                clock,
             } => {
+                if !self.reconcile_pending(&turn, &board) {
+                    return Ok(Transition::Stay);
+                }
                 self.app.apply_turn(turn.clone());
-                self.board.set_position(board);
-                self.board.set_last_move(last_move);
+                self.show_live(board, last_move);
+                self.set_clock(clock);
                 self.sidebar.set_turn(turn);
                 Ok(Transition::Redraw)
             }
 
             AppEvent::Touch(touch) => {
-                if let Some(ev) = self.board.handle_touch(&touch) {
+                // The chat overlay covers the board while it's open.
+                if self.chat.is_open() {
+                    if let Some(ev) = self.chat.handle_touch(&touch) {
+                        return self.handle_event(ev, display);
+                    }
+                } else if let Some(ev) = self.board.handle_touch(&touch) {
                     return self.handle_event(ev, display);
                 }
 
@@ -231,10 +325,28 @@ impl Screen for ChessGameScreen {
                     warn!("MoveMade before board position loaded — dropping move");
                     return Ok(Transition::Redraw);
                 };
-                if let Some(api) = self.app.online_in_game_api() {
+                if let Some(local) = self.app.offline_mut() {
+                    match local.play_user_move(&uci) {
+                        Ok(_) => {
+                            autosave(local);
+                            self.sync_offline(display.event_tx.clone());
+                        }
+                        Err(e) => warn!("Offline move {} rejected: {}", uci, e),
+                    }
+                } else if let Some(api) = self.app.online_in_game_api() {
+                    if let Some(pending) = &self.pending {
+                        warn!("Move {} while {} is pending — ignored", uci, pending.uci);
+                        return Ok(Transition::Redraw);
+                    }
+                    self.play_pending(&uci);
+                    let tx = display.event_tx.clone();
                     tokio::spawn(async move {
                         if let Err(e) = api.move_piece(&uci).await {
                             warn!("move_piece({}) failed: {}", uci, e);
+                            let _ = tx.send(AppEvent::MoveRejected {
+                                uci,
+                                reason: e.to_string(),
+                            });
                         }
                     });
                 } else {
@@ -243,11 +355,186 @@ impl Screen for ChessGameScreen {
                 Ok(Transition::Redraw)
             }
 
+            AppEvent::MoveRejected { uci, reason } => {
+                // Only the move still on the board; a rejection that lost the
+                // race with the stream has nothing left to undo.
+                let Some(pending) = self.pending.take_if(|p| p.uci == uci) else {
+                    return Ok(Transition::Stay);
+                };
+                let last_move = pending.before.last_move_mask();
+                self.show_live(pending.before, last_move);
+                self.app.apply_turn(pending.turn);
+                self.sidebar
+                    .set_status(format!("{} rejected: {}", uci, reason));
+                Ok(Transition::Redraw)
+            }
+
+            AppEvent::Tick(elapsed) => {
+                if let Some(clock) = &mut self.clock {
+                    clock.tick(elapsed);
+                    let clock = *clock;
+                    self.set_clock(Some(clock));
+                }
+                // The full redraw shows the ticked clocks too.
+                if self.claim_at.is_some_and(|at| Instant::now() >= at) {
+                    self.claim_at = None;
+                    self.sidebar.set_claimable(true);
+                    return Ok(Transition::Redraw);
+                }
+                if self.clock.is_none() {
+                    return Ok(Transition::Stay);
+                }
+                // Just the clock rectangles, and only when a shown second
+                // (or the low-time dot) changed.
+                self.top_bar.render_clock(&mut display.renderer)?;
+                self.bottom_bar.render_clock(&mut display.renderer)?;
+                display.renderer.present()?;
+                Ok(Transition::Stay)
+            }
+
+            AppEvent::OpponentOffer(offer) => {
+                if self.sidebar.offer() == offer {
+                    return Ok(Transition::Stay);
+                }
+                self.sidebar.set_offer(offer);
+                Ok(Transition::Redraw)
+            }
+
+            AppEvent::AnswerOffer(offer, accept) => {
+                let Some(api) = self.app.online_in_game_api() else {
+                    return Ok(Transition::Redraw);
+                };
+                tokio::spawn(async move {
+                    let result = match offer {
+                        Offer::Draw => api.handle_draw(accept).await,
+                        Offer::Takeback => api.handle_takeback(accept).await,
+                    };
+                    if let Err(e) = result {
+                        warn!("Answering {:?} offer failed: {}", offer, e);
+                    }
+                });
+                Ok(Transition::Redraw)
+            }
+
+            AppEvent::OpponentGone(claim_in) => {
+                self.claim_at = claim_in.map(|wait| Instant::now() + wait);
+                self.sidebar.set_claimable(false);
+                Ok(Transition::Redraw)
+            }
+
+            AppEvent::ClaimVictory => {
+                if let Some(api) = self.app.online_in_game_api() {
+                    tokio::spawn(async move {
+                        if let Err(e) = api.claim_victory().await {
+                            warn!("Claiming victory failed: {}", e);
+                        }
+                    });
+                }
+                Ok(Transition::Redraw)
+            }
+
+            AppEvent::ChatReceived(message) => {
+                if self.chat.push(message) {
+                    self.sidebar.set_chat_unread(true);
+                }
+                Ok(Transition::Redraw)
+            }
+
+            AppEvent::ChatLoaded(history) => {
+                self.chat.load(history);
+                Ok(Transition::Redraw)
+            }
+            AppEvent::StreamStatus(StreamKind::Game, state) => {
+                info!("Game-state stream: {:?}", state);
+                self.sidebar.set_connection(Some(state.label()));
+                Ok(Transition::Redraw)
+            }
+
+            AppEvent::OpenChat => {
+                if self.app.offline().is_some() {
+                    return Ok(Transition::Stay);
+                }
+                self.chat.set_open(true);
+                self.sidebar.set_chat_unread(false);
+                Ok(Transition::Redraw)
+            }
+
+            AppEvent::CloseChat => {
+                self.chat.set_open(false);
+                self.board.invalidate();
+                Ok(Transition::Redraw)
+            }
+
+            AppEvent::SendChat(room, text) => {
+                // Our line comes back on the stream like anyone else's.
+                if let Some(api) = self.app.online_in_game_api() {
+                    tokio::spawn(async move {
+                        if let Err(e) = api.post_chat(room, &text).await {
+                            warn!("Sending chat failed: {}", e);
+                        }
+                    });
+                }
+                Ok(Transition::Redraw)
+            }
+
+            AppEvent::BrowseTo(ply) => {
+                self.board.browse(ply);
+                self.sidebar.set_browsing(self.board.browsing());
+                Ok(Transition::Redraw)
+            }
+
             AppEvent::SquareSelected(square) => {
                 info!("Selected square: {}", square.to_algebraic());
                 Ok(Transition::Redraw)
             }
 
+            AppEvent::EngineMoved(mv) => {
+                let Some(local) = self.app.offline_mut() else {
+                    return Ok(Transition::Stay);
+                };
+                // Stale replies (the position moved on meanwhile) are dropped.
+                if !local.engine_to_move() || !local.board.is_legal(mv) {
+                    warn!("Dropping stale engine move {}", mv.to_uci());
+                    return Ok(Transition::Stay);
+                }
+                info!("Engine plays {}", mv.to_uci());
+                local.play(mv);
+                autosave(local);
+                self.sync_offline(display.event_tx.clone());
+                Ok(Transition::Redraw)
+            }
+
+            AppEvent::EngineInfo(info) => {
+                // Only the main line of a search that's still current.
+                let Some(local) = self.app.offline().filter(|l| l.engine_to_move()) else {
+                    return Ok(Transition::Stay);
+                };
+                if info.multipv.is_some_and(|n| n > 1) {
+                    return Ok(Transition::Stay);
+                }
+                debug!("Engine depth {} score {:?}", info.depth, info.score);
+                let summary = info.summary(&local.board);
+                if self.sidebar.set_analysis(info.depth, summary) {
+                    Ok(Transition::Redraw)
+                } else {
+                    Ok(Transition::Stay)
+                }
+            }
+
+            // Held by the app until this game is left.
+            AppEvent::GameStarted(game) if !self.claims_game(&game.game_id) => {
+                let opponent = game.opponent.display_name();
+                self.sidebar
+                    .set_notice(format!("game vs {} waiting", opponent));
+                Ok(Transition::Redraw)
+            }
+            AppEvent::ChallengeReceived(challenge) => {
+                let challenger = challenge.challenger.display_name();
+                self.sidebar
+                    .set_notice(format!("challenge from {}", challenger));
+                Ok(Transition::Redraw)
+            }
+
             AppEvent::ShowMenu => {
                 info!("Menu requested — returning to home screen");
                 Ok(Transition::Pop)
@@ -260,6 +547,7 @@ impl Screen for ChessGameScreen {
 
             AppEvent::Expose => {
                 debug!("Expose event - redrawing");
+                self.board.invalidate();
                 Ok(Transition::Redraw)
             }
 
@@ -276,25 +564,707 @@ impl Screen for ChessGameScreen {
             _ => Ok(Transition::Stay),
         }
     }
+
+    fn claims_game(&self, game_id: &str) -> bool {
+        self.app.game_id() == Some(game_id)
+    }
+
+    fn plays_game(&self) -> bool {
+        true
+    }
+}
+
+impl ChessGameScreen {
+    fn set_clock(&mut self, clock: Option<GameClock>) {
+        self.clock = clock;
+        self.top_bar.set_clock(clock.as_ref());
+        self.bottom_bar.set_clock(clock.as_ref());
+    }
+
+    // Put each side on the bar at its edge of the board, with its name,
+    // captures and clock.
+    fn refresh_bars(&mut self) {
+        let (top, bottom) = if self.board.flipped() {
+            (Color::White, Color::Black)
+        } else {
+            (Color::Black, Color::White)
+        };
+        for (bar, color) in [(&mut self.top_bar, top), (&mut self.bottom_bar, bottom)] {
+            let label = match color {
+                Color::White => &self.player_labels.0,
+                Color::Black => &self.player_labels.1,
+            };
+            bar.set_player(color, label.clone());
+            if let Some(position) = self.board.position() {
+                bar.set_material(position);
+            }
+            bar.set_clock(self.clock.as_ref());
+        }
+    }
+
+    // Put the game as it now stands on the board and in the move list. A
+    // browsed position stays on screen.
+    fn show_live(&mut self, board: Bitboards, last_move: u64) {
+        let root = board.root_position();
+        self.sidebar
+            .set_moves(board.san_history(), root.side_to_move, root.fullmove_number);
+        self.board.set_position(board);
+        self.board.set_last_move(last_move);
+        self.sidebar.set_browsing(self.board.browsing());
+        self.refresh_bars();
+    }
+
+    // Draw our online move before the server has it. The stream later
+    // confirms it (see `reconcile_pending`) or `MoveRejected` takes it back.
+    // A move the position doesn't allow is only sent, for the server to
+    // refuse.
+    fn play_pending(&mut self, uci: &str) {
+        let (Some(before), Some(turn)) = (self.board.position(), self.app.turn()) else {
+            return;
+        };
+        let Some(mv) = before.find_legal(uci) else {
+            return;
+        };
+        let (pending, after) = PendingMove::play(before, mv, turn.clone());
+        let last_move = after.last_move_mask();
+        self.show_live(after, last_move);
+        self.app.apply_turn(Turn::Waiting);
+        self.sidebar.set_status(format!("Sending {}...", pending.uci));
+        self.pending = Some(pending);
+    }
+
+    // Whether a streamed position should replace the board. While our move
+    // is pending, an echo of the position we moved from is skipped so the
+    // move doesn't blink off and back on; anything else settles it.
+    fn reconcile_pending(&mut self, turn: &Turn, board: &Bitboards) -> bool {
+        let Some(pending) = &self.pending else {
+            return true;
+        };
+        match pending.reconcile(board) {
+            Reconciled::Pending if !matches!(turn, Turn::Over { .. }) => return false,
+            Reconciled::Pending | Reconciled::Confirmed => {}
+            Reconciled::Diverged => {
+                warn!("Server position doesn't follow {} — taking it", pending.uci)
+            }
+        }
+        self.pending = None;
+        true
+    }
+
+    // Offline counterpart of the stream events: push the local game's
+    // position, orientation and turn into the widgets, and start the engine
+    // if it's its move.
+    fn sync_offline(&mut self, tx: Sender<AppEvent>) {
+        let Some(local) = self.app.offline() else {
+            return;
+        };
+        let board = local.board.clone();
+        self.player_labels = local.player_names();
+        self.board.set_flipped(local.flipped());
+        self.board.set_movable(local.player_color());
+        let last_move = board.last_move_mask();
+        self.show_live(board, last_move);
+        let Some(local) = self.app.offline() else {
+            return;
+        };
+        match local.status() {
+            Some(status) => self.sidebar.set_status(status),
+            None => self.sidebar.set_turn(local.turn.clone()),
+        }
+        if local.engine_to_move() {
+            kick_engine_move(local, tx);
+        }
+    }
+}
+
+// Offline games are saved after every move. A failed save is logged and the
+// game goes on.
+fn autosave(local: &BoardLocal) {
+    if let Err(e) = local.autosave() {
+        warn!("Autosave failed: {}", e);
+    }
 }
 
-// Spawns the game-state stream onto the tokio runtime. The task owns a fresh
-// clone of `BoardAPI<InGame>`; mutations to the clone's `state` are local
-// bookkeeping. Every state change the screen needs is sent back as an
-// `AppEvent`. No-op when the screen wasn't pushed with an in-game backend
-// (e.g. the Demo button path, which still uses an Idle ChessApp).
-fn kick_game_stream(app: &ChessApp, tx: Sender<AppEvent>) {
-    let Some(mut api) = app.online_in_game_api() else {
+// Asks the offline game's engine for its reply, which comes back as
+// `EngineMoved`. An installed UCI engine is tried first; if it fails, the
+// built-in engine answers instead.
+fn kick_engine_move(local: &BoardLocal, tx: Sender<AppEvent>) {
+    let LocalOpponent::Engine { strength, uci } = &local.opponent else {
+        return;
+    };
+    let board = local.board.clone();
+    let strength = *strength;
+    let Some(uci) = uci.clone() else {
+        kick_builtin_move(board, strength, tx);
+        return;
+    };
+    tokio::spawn(async move {
+        match uci.best_move(&board, strength, &tx).await {
+            Ok(Some(mv)) => {
+                let _ = tx.send(AppEvent::EngineMoved(mv));
+            }
+            Ok(None) => {}
+            Err(e) => {
+                warn!("UCI engine failed ({}) — using the built-in engine", e);
+                kick_builtin_move(board, strength, tx);
+            }
+        }
+    });
+}
+
+// The built-in search is CPU-bound and takes up to a few seconds, so it goes
+// to tokio's blocking pool rather than an async task.
+fn kick_builtin_move(board: Bitboards, strength: Strength, tx: Sender<AppEvent>) {
+    tokio::task::spawn_blocking(move || {
+        if let Some(mv) = best_move(&board, strength) {
+            let _ = tx.send(AppEvent::EngineMoved(mv));
+        }
+    });
+}
+
+// Drives the clocks of an open online game: a `Tick` a few times a second
+// so a shown second changes on time, with the real time since the last one.
+// The screen aborts it when it closes.
+fn kick_clock_ticker(tx: Sender<AppEvent>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(250));
+        let mut last = interval.tick().await;
+        loop {
+            let now = interval.tick().await;
+            if tx.send(AppEvent::Tick(now - last)).is_err() {
+                break;
+            }
+            last = now;
+        }
+    })
+}
+
+impl Drop for ChessGameScreen {
+    fn drop(&mut self) {
+        if let Some(ticker) = self.ticker.take() {
+            ticker.abort();
+        }
+        if let Some(stream) = self.stream.take() {
+            stream.abort();
+        }
+    }
+}
+
+// Runs until the game is over, reconnecting whenever the stream drops.
+fn kick_game_stream(app: &ChessApp, tx: Sender<AppEvent>) -> Option<JoinHandle<()>> {
+    let Some(api) = app.online_in_game_api() else {
         warn!("ChessGameScreen has no in-game backend — skipping stream");
+        return None;
+    };
+    // Each connection gets a fresh copy: its gameFull sets it up anyway.
+    let events = tx.clone();
+    let connect = move || {
+        let mut api = api.clone();
+        let tx = events.clone();
+        async move { api.stream_game_event(tx).await }
+    };
+    Some(tokio::spawn(supervise(
+        StreamKind::Game,
+        tx,
+        Backoff::default(),
+        connect,
+    )))
+}
+
+// The player room's history; the stream only has what's said from now on.
+fn kick_chat_history(app: &ChessApp, tx: Sender<AppEvent>) {
+    let Some(api) = app.online_in_game_api() else {
         return;
     };
     tokio::spawn(async move {
-        if let Err(e) = api.stream_game_event(tx).await {
-            warn!("Game-state stream errored: {}", e);
+        match api.get_chat().await {
+            Ok(history) => {
+                let _ = tx.send(AppEvent::ChatLoaded(history));
+            }
+            Err(e) => warn!("Fetching chat history failed: {}", e),
         }
     });
 }
 
+// ─── ChallengeScreen ──────────────────────────────────────────────────────────
+
+impl Screen for ChallengeScreen {
+    fn render(&mut self, display: &mut Display) -> Result<(), Box<dyn std::error::Error>> {
+        display.renderer.clear(DrawColor::White)?;
+
+        let title = "Challenge";
+        let title_size = 56.0;
+        let (tw, _) = display.renderer.measure_text(title, title_size);
+        display
+            .renderer
+            .draw_text((1072 - tw as i16) / 2, 240, title, title_size, DrawColor::Black)?;
+
+        let info_size = 36.0;
+        let lines = [
+            format!("from {}", player_label(&self.challenge.challenger)),
+            self.challenge.summary(),
+            format!("You play {}", self.challenge.our_color()),
+        ];
+        let mut y: i16 = 400;
+        for line in &lines {
+            let (lw, _) = display.renderer.measure_text(line, info_size);
+            display
+                .renderer
+                .draw_text((1072 - lw as i16) / 2, y, line, info_size, DrawColor::Black)?;
+            y += 60;
+        }
+
+        let status = if self.accepted {
+            Some("Starting game…".to_string())
+        } else if self.answering {
+            Some("Sending…".to_string())
+        } else {
+            self.error.clone()
+        };
+        if let Some(status) = status {
+            let (sw, _) = display.renderer.measure_text(&status, 32.0);
+            let sy = self.accept_button.rect.y + self.accept_button.rect.height as i16 + 60;
+            display
+                .renderer
+                .draw_text((1072 - sw as i16) / 2, sy, &status, 32.0, DrawColor::Black)?;
+        }
+
+        if !self.accepted {
+            self.accept_button.draw(&mut display.renderer)?;
+            self.decline_button.draw(&mut display.renderer)?;
+        }
+        display.renderer.present()?;
+        Ok(())
+    }
+
+    fn handle_event(
+        &mut self,
+        event: AppEvent,
+        display: &mut Display,
+    ) -> Result<Transition, Box<dyn std::error::Error>> {
+        match event {
+            AppEvent::Touch(touch) => {
+                if touch.kind != TouchKind::Up || self.answering || self.accepted {
+                    return Ok(Transition::Stay);
+                }
+                let accept = self.accept_button.rect.contains(touch.x, touch.y);
+                if !accept && !self.decline_button.rect.contains(touch.x, touch.y) {
+                    return Ok(Transition::Stay);
+                }
+                let Some(api) = self.app.online_idle_api() else {
+                    return Ok(Transition::Pop);
+                };
+                info!("Answering challenge {}: accept={}", self.challenge.id, accept);
+                self.answering = true;
+                self.error = None;
+                let id = self.challenge.id.clone();
+                let tx = display.event_tx.clone();
+                tokio::spawn(async move {
+                    let result = if accept {
+                        api.accept_challenge(&id).await
+                    } else {
+                        api.decline_challenge(&id).await
+                    };
+                    let _ = match result {
+                        Ok(()) => tx.send(AppEvent::ChallengeAnswered(accept)),
+                        Err(e) => tx.send(AppEvent::ChallengeAnswerFailed(e.to_string())),
+                    };
+                });
+                Ok(Transition::Redraw)
+            }
+            AppEvent::ChallengeAnswered(accepted) => {
+                self.answering = false;
+                if !accepted {
+                    return Ok(Transition::Pop);
+                }
+                // The account stream announces the game next.
+                self.accepted = true;
+                Ok(Transition::Redraw)
+            }
+            AppEvent::ChallengeAnswerFailed(e) => {
+                warn!("Answering challenge {} failed: {}", self.challenge.id, e);
+                self.answering = false;
+                self.error = Some(e);
+                Ok(Transition::Redraw)
+            }
+            AppEvent::GameStarted(game) if game.game_id == self.challenge.id => {
+                let app = self.app.clone().attach_game(game.game_id, game.is_my_turn);
+                Ok(Transition::Replace(Box::new(ChessGameScreen::new(app))))
+            }
+            AppEvent::ChallengeCanceled(id) if id == self.challenge.id => {
+                info!("Challenge {} was withdrawn", id);
+                Ok(Transition::Pop)
+            }
+            AppEvent::Expose => Ok(Transition::Redraw),
+            AppEvent::WindowUnmapped => {
+                warn!("Window unmapped!");
+                Ok(Transition::Stay)
+            }
+            AppEvent::Quit => Ok(Transition::Quit),
+            _ => Ok(Transition::Stay),
+        }
+    }
+
+    fn claims_game(&self, game_id: &str) -> bool {
+        self.challenge.id == game_id
+    }
+}
+
+// ─── NewChallengeScreen ───────────────────────────────────────────────────────
+
+// Longest Lichess username.
+const MAX_USERNAME_LEN: usize = 30;
+
+impl Screen for NewChallengeScreen {
+    fn render(&mut self, display: &mut Display) -> Result<(), Box<dyn std::error::Error>> {
+        display.renderer.clear(DrawColor::White)?;
+
+        let title = "New challenge";
+        let title_size = 56.0;
+        let (tw, _) = display.renderer.measure_text(title, title_size);
+        display
+            .renderer
+            .draw_text((1072 - tw as i16) / 2, 60, title, title_size, DrawColor::Black)?;
+
+        self.target_button.draw(&mut display.renderer)?;
+        if self.target != ChallengeTarget::Open {
+            self.detail_button.draw(&mut display.renderer)?;
+        }
+        self.variant_button.draw(&mut display.renderer)?;
+        self.time_button.draw(&mut display.renderer)?;
+        self.color_button.draw(&mut display.renderer)?;
+        self.rated_button.draw(&mut display.renderer)?;
+
+        if let Some(ref img) = self.qr_image {
+            display.renderer.draw_image(
+                self.qr_area.x,
+                self.qr_area.y,
+                self.qr_area.width,
+                self.qr_area.height,
+                img,
+            )?;
+        } else if self.sent.is_none() && matches!(self.target, ChallengeTarget::User(_)) {
+            self.keyboard.render(&mut display.renderer)?;
+        }
+
+        if let Some(ref status) = self.status {
+            let (sw, _) = display.renderer.measure_text(status, 32.0);
+            display
+                .renderer
+                .draw_text((1072 - sw as i16) / 2, 1230, status, 32.0, DrawColor::Black)?;
+        }
+
+        if self.sent.is_none() && !self.sending {
+            self.send_button.draw(&mut display.renderer)?;
+        }
+        self.back_button.draw(&mut display.renderer)?;
+        display.renderer.present()?;
+        Ok(())
+    }
+
+    fn handle_event(
+        &mut self,
+        event: AppEvent,
+        display: &mut Display,
+    ) -> Result<Transition, Box<dyn std::error::Error>> {
+        match event {
+            AppEvent::Touch(touch) => {
+                if touch.kind != TouchKind::Up {
+                    return Ok(Transition::Stay);
+                }
+                if self.back_button.rect.contains(touch.x, touch.y) {
+                    return Ok(self.withdraw());
+                }
+                // The options are fixed once the challenge is out.
+                if self.sending || self.sent.is_some() {
+                    return Ok(Transition::Stay);
+                }
+                if self.send_button.rect.contains(touch.x, touch.y) {
+                    self.send(display);
+                } else if self.target_button.rect.contains(touch.x, touch.y) {
+                    self.target = self.target.cycle();
+                } else if self.detail_button.rect.contains(touch.x, touch.y) {
+                    self.target.next_level();
+                } else if self.variant_button.rect.contains(touch.x, touch.y) {
+                    self.request.next_variant();
+                } else if self.time_button.rect.contains(touch.x, touch.y) {
+                    self.request.next_time();
+                } else if self.color_button.rect.contains(touch.x, touch.y) {
+                    self.request.color = self.request.color.cycle();
+                } else if self.rated_button.rect.contains(touch.x, touch.y) {
+                    self.request.rated = !self.request.rated;
+                } else if matches!(self.target, ChallengeTarget::User(_)) {
+                    if let Some(ev) = self.keyboard.handle_touch(&touch) {
+                        return self.handle_event(ev, display);
+                    }
+                    return Ok(Transition::Stay);
+                } else {
+                    return Ok(Transition::Stay);
+                }
+                self.relabel();
+                Ok(Transition::Redraw)
+            }
+            AppEvent::KeyTyped(key) => {
+                match &mut self.target {
+                    ChallengeTarget::User(name) if name.len() < MAX_USERNAME_LEN => name.push(key),
+                    _ => {}
+                }
+                self.relabel();
+                Ok(Transition::Redraw)
+            }
+            AppEvent::KeyErased => {
+                if let ChallengeTarget::User(name) = &mut self.target {
+                    name.pop();
+                }
+                self.relabel();
+                Ok(Transition::Redraw)
+            }
+            AppEvent::ChallengeCreated(created) => {
+                self.sending = false;
+                if let ChallengeTarget::Ai(_) = self.target {
+                    // The AI accepts at once; its game is already on.
+                    let my_turn = self.request.color == ChallengeColor::White;
+                    let app = self.app.clone().attach_game(created.id, my_turn);
+                    return Ok(Transition::Replace(Box::new(ChessGameScreen::new(app))));
+                }
+                self.status = Some(match &self.target {
+                    ChallengeTarget::User(name) => format!("Waiting for {} to answer…", name),
+                    _ => "Waiting for someone to open the link…".to_string(),
+                });
+                let open = self.target == ChallengeTarget::Open;
+                if let Some(link) = created.link_for(self.request.color).filter(|_| open) {
+                    info!("Open challenge at {}", link);
+                    self.qr_image = generate_qr_code(link).ok();
+                }
+                self.sent = Some(created);
+                self.relabel();
+                Ok(Transition::Redraw)
+            }
+            AppEvent::ChallengeFailed(e) => {
+                warn!("Creating the challenge failed: {}", e);
+                self.sending = false;
+                self.status = Some(e);
+                Ok(Transition::Redraw)
+            }
+            AppEvent::ChallengeDeclined { id, reason } => {
+                if !self.claims_game(&id) {
+                    return Ok(Transition::Stay);
+                }
+                self.sent = None;
+                self.status = Some(if reason.is_empty() {
+                    "Declined".to_string()
+                } else {
+                    format!("Declined: {}", reason)
+                });
+                self.relabel();
+                Ok(Transition::Redraw)
+            }
+            AppEvent::GameStarted(game) if self.claims_game(&game.game_id) => {
+                let app = self.app.clone().attach_game(game.game_id, game.is_my_turn);
+                Ok(Transition::Replace(Box::new(ChessGameScreen::new(app))))
+            }
+            AppEvent::Expose => Ok(Transition::Redraw),
+            AppEvent::WindowUnmapped => {
+                warn!("Window unmapped!");
+                Ok(Transition::Stay)
+            }
+            AppEvent::Quit => Ok(Transition::Quit),
+            _ => Ok(Transition::Stay),
+        }
+    }
+
+    fn claims_game(&self, game_id: &str) -> bool {
+        self.sent.as_ref().is_some_and(|c| c.id == game_id)
+    }
+}
+
+impl NewChallengeScreen {
+    fn send(&mut self, display: &Display) {
+        if self.target == ChallengeTarget::User(String::new()) {
+            self.status = Some("Type their username first".to_string());
+            return;
+        }
+        let Some(api) = self.app.online_idle_api() else {
+            return;
+        };
+        self.sending = true;
+        self.status = Some("Sending…".to_string());
+        let target = self.target.clone();
+        let request = self.request.clone();
+        let tx = display.event_tx.clone();
+        tokio::spawn(async move {
+            let _ = match api.create_challenge(&target, &request).await {
+                Ok(created) => tx.send(AppEvent::ChallengeCreated(created)),
+                Err(e) => tx.send(AppEvent::ChallengeFailed(e.to_string())),
+            };
+        });
+    }
+
+    // Back: leave, or first take back the challenge that's out.
+    fn withdraw(&mut self) -> Transition {
+        let Some(sent) = self.sent.take() else {
+            return if self.sending {
+                Transition::Stay
+            } else {
+                Transition::Pop
+            };
+        };
+        if let Some(api) = self.app.online_idle_api() {
+            tokio::spawn(async move {
+                if let Err(e) = api.cancel_challenge(&sent.id).await {
+                    warn!("Cancelling challenge {} failed: {}", sent.id, e);
+                }
+            });
+        }
+        self.qr_image = None;
+        self.status = Some("Challenge withdrawn".to_string());
+        self.relabel();
+        Transition::Redraw
+    }
+}
+
+// ─── SeekScreen ───────────────────────────────────────────────────────────────
+
+impl Screen for SeekScreen {
+    fn render(&mut self, display: &mut Display) -> Result<(), Box<dyn std::error::Error>> {
+        display.renderer.clear(DrawColor::White)?;
+
+        let title = "Play online";
+        let title_size = 56.0;
+        let (tw, _) = display.renderer.measure_text(title, title_size);
+        display
+            .renderer
+            .draw_text((1072 - tw as i16) / 2, 120, title, title_size, DrawColor::Black)?;
+
+        self.time_button.draw(&mut display.renderer)?;
+        self.rated_button.draw(&mut display.renderer)?;
+        self.seek_button.draw(&mut display.renderer)?;
+
+        let status = match (&self.seeking, &self.status) {
+            (Some(_), _) => Some(format!("Looking for an opponent ({})…", self.seek.label())),
+            (None, status) => status.clone(),
+        };
+        if let Some(status) = status {
+            let (sw, _) = display.renderer.measure_text(&status, 32.0);
+            display
+                .renderer
+                .draw_text((1072 - sw as i16) / 2, 960, &status, 32.0, DrawColor::Black)?;
+        }
+
+        self.back_button.draw(&mut display.renderer)?;
+        display.renderer.present()?;
+        Ok(())
+    }
+
+    fn handle_event(
+        &mut self,
+        event: AppEvent,
+        display: &mut Display,
+    ) -> Result<Transition, Box<dyn std::error::Error>> {
+        match event {
+            AppEvent::Touch(touch) => {
+                if touch.kind != TouchKind::Up {
+                    return Ok(Transition::Stay);
+                }
+                if self.back_button.rect.contains(touch.x, touch.y) {
+                    // Dropping the screen cancels the seek.
+                    return Ok(Transition::Pop);
+                }
+                if self.seek_button.rect.contains(touch.x, touch.y) {
+                    match self.seeking.take() {
+                        Some(seeking) => {
+                            info!("Seek cancelled");
+                            seeking.abort();
+                        }
+                        None => {
+                            self.status = None;
+                            self.seeking = Some(kick_seek(&self.app, self.seek, display));
+                        }
+                    }
+                } else if self.seeking.is_some() {
+                    return Ok(Transition::Stay);
+                } else if self.time_button.rect.contains(touch.x, touch.y) {
+                    self.seek.next_time();
+                } else if self.rated_button.rect.contains(touch.x, touch.y) {
+                    self.seek.rated = !self.seek.rated;
+                } else {
+                    return Ok(Transition::Stay);
+                }
+                self.relabel();
+                Ok(Transition::Redraw)
+            }
+            AppEvent::SeekFailed(e) => {
+                warn!("Seek failed: {}", e);
+                self.seeking = None;
+                self.status = Some(e);
+                self.relabel();
+                Ok(Transition::Redraw)
+            }
+            AppEvent::SeekEnded => {
+                info!("Seek closed without a game");
+                self.seeking = None;
+                self.status = Some("No opponent found".to_string());
+                self.relabel();
+                Ok(Transition::Redraw)
+            }
+            AppEvent::GameStarted(game) if self.claims_start(&game) => {
+                info!("Seek paired into game {}", game.game_id);
+                let app = self.app.clone().attach_game(game.game_id, game.is_my_turn);
+                Ok(Transition::Replace(Box::new(ChessGameScreen::new(app))))
+            }
+            AppEvent::Expose => Ok(Transition::Redraw),
+            AppEvent::WindowUnmapped => {
+                warn!("Window unmapped!");
+                Ok(Transition::Stay)
+            }
+            AppEvent::Quit => Ok(Transition::Quit),
+            _ => Ok(Transition::Stay),
+        }
+    }
+
+    // A game like the seek, starting while it's open or just closed, is the
+    // pairing. `seeking` goes once its end (`SeekEnded` / `SeekFailed`) is
+    // in.
+    fn claims_start(&self, game: &GameStartEvent) -> bool {
+        self.seeking.is_some() && self.seek.pairs(game)
+    }
+}
+
+impl Drop for SeekScreen {
+    fn drop(&mut self) {
+        if let Some(seeking) = self.seeking.take() {
+            seeking.abort();
+        }
+    }
+}
+
+// Lichess closes a seek request when it pairs, and the game is announced
+// separately on the account stream. Give that this long to arrive before
+// calling the seek unanswered.
+const SEEK_GRACE: Duration = Duration::from_secs(5);
+
+// Holds the seek open until it's cancelled (the handle is aborted), fails
+// or Lichess closes it, whether paired or not.
+fn kick_seek(app: &ChessApp, seek: Seek, display: &Display) -> JoinHandle<()> {
+    let api = app.online_idle_api();
+    let tx = display.event_tx.clone();
+    tokio::spawn(async move {
+        let Some(api) = api else {
+            let _ = tx.send(AppEvent::SeekFailed("Not signed in".to_string()));
+            return;
+        };
+        let ended = api.seek(&seek).await.map_err(|e| e.to_string());
+        let ended = match ended {
+            Ok(()) => {
+                tokio::time::sleep(SEEK_GRACE).await;
+                AppEvent::SeekEnded
+            }
+            Err(e) => AppEvent::SeekFailed(e),
+        };
+        let _ = tx.send(ended);
+    })
+}
+
 // ─── ChessAuthScreen ──────────────────────────────────────────────────────────
 
 impl Screen for ChessAuthScreen {
@@ -390,8 +1360,12 @@ impl OngoingChessGamesScreen {
         if self.loading {
             return;
         }
-        let Some(api) = self.app.online_idle_api() else {
-            self.error = Some("Offline backend has no ongoing games".into());
+        let Some(api) = self.app.as_ref().and_then(ChessApp::online_idle_api) else {
+            // Not signed in: the saved offline games are all there is.
+            self.games = Some(Arc::new(GameDataList {
+                now_playing: Vec::new(),
+            }));
+            self.set_page(0);
             return;
         };
         self.loading = true;
@@ -410,7 +1384,7 @@ impl OngoingChessGamesScreen {
 
     fn page_count(&self) -> usize {
         match &self.games {
-            Some(g) => g.now_playing.len().div_ceil(GAMES_PER_PAGE),
+            Some(g) => (g.now_playing.len() + self.local_games.len()).div_ceil(GAMES_PER_PAGE),
             None => 0,
         }
     }
@@ -422,50 +1396,86 @@ impl OngoingChessGamesScreen {
     /// touch handler also short-circuits taps on those slots.
     fn set_page(&mut self, index: usize) {
         self.page_index = index;
-        let Some(games) = self.games.as_ref() else {
+        if self.games.is_none() {
             return;
-        };
+        }
+        let labels: Vec<Option<String>> = (0..GAMES_PER_PAGE)
+            .map(|k| self.game_at_slot(k).map(slot_label))
+            .collect();
         let buttons: [&mut Button; 4] = [
             &mut self.chessgame_button_0,
             &mut self.chessgame_button_1,
             &mut self.chessgame_button_2,
             &mut self.chessgame_button_3,
         ];
-        let start = index * GAMES_PER_PAGE;
-        for (k, btn) in buttons.into_iter().enumerate() {
-            match games.now_playing.get(start + k) {
-                Some(game) => {
-                    let opp = match &game.opponent {
-                        PlayedBy::User(player) => player.name.clone(),
-                        PlayedBy::Ai(computer) => match computer.ai_level {
-                            Some(level) => format!("AI lvl {}", level),
-                            None => String::from("AI"),
-                        },
-                    };
-                    let mut l = format!("VS {opp}");
-                    if game.is_my_turn {
-                        l = format!("> {l} <");
-                    }
-                    btn.label = l;
-                }
-                None => {
-                    btn.label = "-".to_string();
-                }
-            }
+        for (btn, label) in buttons.into_iter().zip(labels) {
+            btn.label = label.unwrap_or_else(|| "-".to_string());
         }
     }
 
     /// Resolve which game `chessgame_button_{k}` currently maps to, given the
     /// current page. Returns `None` for slots that are inert ("-"-labelled
     /// because the current page has fewer than 4 games left).
-    fn game_at_slot(&self, k: usize) -> Option<&crate::models::board_api::GameData> {
-        self.games
-            .as_ref()?
-            .now_playing
-            .get(self.page_index * GAMES_PER_PAGE + k)
+    fn game_at_slot(&self, k: usize) -> Option<GameSlot<'_>> {
+        let online = &self.games.as_ref()?.now_playing;
+        let index = self.page_index * GAMES_PER_PAGE + k;
+        match online.get(index) {
+            Some(game) => Some(GameSlot::Online(game)),
+            None => self
+                .local_games
+                .get(index - online.len())
+                .map(GameSlot::Local),
+        }
     }
 }
 
+// One entry in the ongoing-games list: Lichess games first, then saved
+// offline ones.
+enum GameSlot<'a> {
+    Online(&'a GameData),
+    Local(&'a SaveSummary),
+}
+
+fn slot_label(slot: GameSlot) -> String {
+    let (opp, my_turn) = match slot {
+        GameSlot::Online(game) => {
+            let opp = match &game.opponent {
+                PlayedBy::User(player) => player.name.clone(),
+                PlayedBy::Ai(computer) => match computer.ai_level {
+                    Some(level) => format!("AI lvl {}", level),
+                    None => String::from("AI"),
+                },
+            };
+            (opp, game.is_my_turn)
+        }
+        GameSlot::Local(save) => {
+            let opp = match save.engine_level {
+                Some(level) => format!("Kindle lvl {}", level),
+                None => return String::from("Pass & play"),
+            };
+            (opp, save.my_turn)
+        }
+    };
+    let label = format!("VS {opp}");
+    if my_turn {
+        format!("> {label} <")
+    } else {
+        label
+    }
+}
+
+// Reading the saves blocks on flash, which can be slow on a Kindle.
+fn kick_local_games(tx: Sender<AppEvent>) {
+    tokio::spawn(async move {
+        match tokio::task::spawn_blocking(BoardLocal::get_ongoing_games).await {
+            Ok(saves) => {
+                let _ = tx.send(AppEvent::LocalGamesLoaded(saves));
+            }
+            Err(e) => warn!("Listing saved games failed: {}", e),
+        }
+    });
+}
+
 impl Screen for OngoingChessGamesScreen {
     fn render(&mut self, display: &mut Display) -> Result<(), Box<dyn std::error::Error>> {
         use crate::ui::renderer::DrawColor;
@@ -473,6 +1483,7 @@ impl Screen for OngoingChessGamesScreen {
         // First paint after Push: kick off the async fetch. Subsequent renders
         // (after data arrives or on reload) skip this branch.
         if self.games.is_none() && self.error.is_none() && !self.loading {
+            kick_local_games(display.event_tx.clone());
             self.kick_fetch(display);
         }
 
@@ -482,13 +1493,13 @@ impl Screen for OngoingChessGamesScreen {
 
         if let Some(err) = &self.error {
             let label = format!("Error: {}", err);
-            let (tw, th) = display.renderer.measure_text(&label, size_px);
+            let (tw, _th) = display.renderer.measure_text(&label, size_px);
             let tx = (1072 - tw as i16) / 2;
-            let ty = (1448 - th as i16) / 2;
             display
                 .renderer
-                .draw_text(tx, ty, &label, size_px, DrawColor::Black)?;
-        } else if self.games.is_some() {
+                .draw_text(tx, 160, &label, size_px, DrawColor::Black)?;
+        }
+        if self.games.is_some() {
             // Labels were baked into the buttons by `set_page` (called from
             // OngoingGamesLoaded and from next/prev taps), so render is just
             // a draw pass.
@@ -528,10 +1539,40 @@ impl Screen for OngoingChessGamesScreen {
                 Ok(Transition::Redraw)
             }
 
+            AppEvent::LocalGamesLoaded(saves) => {
+                info!("Saved offline games loaded: {} entries", saves.len());
+                self.local_games = saves;
+                self.set_page(self.page_index);
+                Ok(Transition::Redraw)
+            }
+
+            // Over while we were looking at the list: it's no longer ongoing.
+            AppEvent::GameFinished(game_id) => {
+                let Some(games) = &mut self.games else {
+                    return Ok(Transition::Stay);
+                };
+                let before = games.now_playing.len();
+                Arc::make_mut(games)
+                    .now_playing
+                    .retain(|game| game.game_id != game_id);
+                if games.now_playing.len() == before {
+                    return Ok(Transition::Stay);
+                }
+                info!("Game {} finished — dropped from the list", game_id);
+                let last = self.page_count().saturating_sub(1);
+                self.set_page(self.page_index.min(last));
+                Ok(Transition::Redraw)
+            }
+
+            // Saved offline games are still listed, under the error.
             AppEvent::OngoingGamesFailed(e) => {
                 warn!("Ongoing games fetch failed: {}", e);
                 self.error = Some(e);
+                self.games = Some(Arc::new(GameDataList {
+                    now_playing: Vec::new(),
+                }));
                 self.loading = false;
+                self.set_page(self.page_index);
                 Ok(Transition::Redraw)
             }
 
@@ -573,17 +1614,34 @@ impl Screen for OngoingChessGamesScreen {
                     if !btn.rect.contains(touch.x, touch.y) {
                         continue;
                     }
-                    let Some(game) = self.game_at_slot(k) else {
-                        return Ok(Transition::Stay);
+                    let game_app = match self.game_at_slot(k) {
+                        None => return Ok(Transition::Stay),
+                        Some(GameSlot::Online(game)) => {
+                            info!(
+                                "Picked ongoing game (page {}, slot {}): {}",
+                                self.page_index, k, game.game_id
+                            );
+                            // Online games are only listed when `app` is set.
+                            let Some(app) = self.app.clone() else {
+                                return Ok(Transition::Stay);
+                            };
+                            app.attach_game(game.game_id.clone(), game.is_my_turn)
+                        }
+                        Some(GameSlot::Local(save)) => {
+                            info!(
+                                "Picked saved offline game (page {}, slot {}): {:?}",
+                                self.page_index, k, save.path
+                            );
+                            match BoardLocal::load(&save.path) {
+                                Ok(local) => ChessApp::resume_offline(local),
+                                Err(e) => {
+                                    warn!("Can't resume saved game: {}", e);
+                                    self.error = Some(e);
+                                    return Ok(Transition::Redraw);
+                                }
+                            }
+                        }
                     };
-                    info!(
-                        "Picked ongoing game (page {}, slot {}): {}",
-                        self.page_index, k, game.game_id
-                    );
-                    let game_app = self
-                        .app
-                        .clone()
-                        .attach_game(game.game_id.clone(), game.is_my_turn);
                     return Ok(Transition::Push(Box::new(ChessGameScreen::new(game_app))));
                 }
                 Ok(Transition::Stay)
@@ -1,7 +1,15 @@
 pub mod board;
 pub mod button;
+pub mod chat;
+pub mod clock;
+pub mod keyboard;
+pub mod player_bar;
 pub mod sidebar;
 
 pub use board::BoardWidget;
 pub use button::Button;
+pub use chat::ChatOverlay;
+pub use clock::ClockWidget;
+pub use keyboard::KeyboardWidget;
+pub use player_bar::PlayerBarWidget;
 pub use sidebar::SidebarWidget;
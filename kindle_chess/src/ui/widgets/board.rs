@@ -1,3 +1,4 @@
+use crate::models::bitboard::movegen::squares;
 use crate::models::bitboard::{Bitboards, Color, Piece};
 use crate::ui::events::{
     AppEvent, ChessMove, Rectangle, RectangleExt, Square, TouchEvent, TouchKind,
@@ -24,14 +25,26 @@ const SELECTION_STROKE: u16 = 4;
 // the selection so the two decorations are distinguishable when both apply
 // to the same square.
 const LAST_MOVE_STROKE: i16 = 6;
+// Move hints for the selected piece: a dot in the middle of an empty
+// destination, a ring just inside the square around a piece it can take.
+const HINT_DOT_RADIUS: u16 = SQUARE_SIZE / 8;
+const HINT_RING_RADIUS: u16 = SQUARE_SIZE / 2 - 8;
+const HINT_RING_STROKE: u16 = 4;
 
 pub struct BoardWidget {
     area: Rectangle,
     selected_square: Option<Square>,
     flipped: bool, // View from black's perspective
     /// Current position to render. `None` until the first
-    /// `GameFullReceived` / `TurnChanged` event lands.
+    /// `GameFullReceived` / `TurnChanged` event lands. While browsing this
+    /// is an earlier position of `live`.
     position: Option<Bitboards>,
+    /// The game as it stands, from `set_position` / `set_last_move`.
+    live: Option<Bitboards>,
+    live_last_move: u64,
+    /// Number of plies into the game being shown instead of the live
+    /// position; `None` when live. Set by `browse`.
+    browsing: Option<usize>,
     /// Last position actually painted to the screen. `render` diffs this
     /// against `position` and only repaints squares whose contents changed
     /// — this is what stops the whole board flashing on every server event.
@@ -45,6 +58,23 @@ pub struct BoardWidget {
     /// rendered diff-style against `last_drawn_last_move`.
     last_move_squares: u64,
     last_drawn_last_move: u64,
+    /// Legal destinations of the selected piece (LERF bitmask), split into
+    /// the ones that capture (`capture_hints`, drawn as rings) and the rest
+    /// (dots). Recomputed on every selection or position change; rendered
+    /// diff-style like the last-move brackets.
+    move_hints: u64,
+    capture_hints: u64,
+    last_drawn_hints: u64,
+    last_drawn_capture_hints: u64,
+    /// Colour whose pieces can be picked up; `None` means whichever side is
+    /// to move (pass-and-play).
+    movable: Option<Color>,
+    /// Promotion waiting for the player to pick a piece. While it's open,
+    /// the next board tap either picks a piece or cancels the move.
+    promotion: Option<PromotionPicker>,
+    /// Squares the picker covered at the last paint (0 if none), so closing
+    /// it repaints just those.
+    last_drawn_promotion: u64,
     /// Force a full repaint on the next `render` call. Set only by changes
     /// that invalidate the entire board (orientation flip, first paint).
     /// Position and selection updates both go through the partial path.
@@ -54,14 +84,43 @@ pub struct BoardWidget {
     piece_sprites: PieceSprites,
 }
 
-type Sprite = ImageBuffer<Rgba<u8>, Vec<u8>>;
+// The overlay shown when a pawn reaches its last rank: one cell per piece it
+// may promote to, in a column running from the destination square toward
+// the middle of the board (so it's never cut off, whichever way up the
+// board is).
+struct PromotionPicker {
+    mv: ChessMove,
+    color: Color,
+    choices: Vec<Piece>,
+}
+
+impl PromotionPicker {
+    // The board square each choice is drawn on.
+    fn cells(&self) -> impl Iterator<Item = (u8, Piece)> + '_ {
+        let to = square_to_index(self.mv.to);
+        self.choices.iter().enumerate().map(move |(i, &piece)| {
+            let sq = if self.mv.to.rank == 7 {
+                to - 8 * i as u8
+            } else {
+                to + 8 * i as u8
+            };
+            (sq, piece)
+        })
+    }
+
+    fn mask(&self) -> u64 {
+        self.cells().fold(0, |mask, (sq, _)| mask | (1u64 << sq))
+    }
+}
+
+pub(crate) type Sprite = ImageBuffer<Rgba<u8>, Vec<u8>>;
 
-struct PieceSprites {
+pub(crate) struct PieceSprites {
     sprites: [[Option<Sprite>; 6]; 2],
 }
 
 impl PieceSprites {
-    fn load() -> Self {
+    pub(crate) fn load() -> Self {
         let mut sprites: [[Option<Sprite>; 6]; 2] = Default::default();
         let assets = env!("ASSETS_DIR");
         for (color, color_dir) in [(Color::White, 0), (Color::Black, 1)] {
@@ -93,7 +152,7 @@ impl PieceSprites {
         Self { sprites }
     }
 
-    fn get(&self, color: Color, piece: Piece) -> Option<&Sprite> {
+    pub(crate) fn get(&self, color: Color, piece: Piece) -> Option<&Sprite> {
         let c = match color {
             Color::White => 0,
             Color::Black => 1,
@@ -117,24 +176,99 @@ impl BoardWidget {
             selected_square: None,
             flipped: false,
             position: None,
+            live: None,
+            live_last_move: 0,
+            browsing: None,
             last_drawn_position: None,
             last_drawn_selection: None,
             last_move_squares: 0,
             last_drawn_last_move: 0,
+            move_hints: 0,
+            capture_hints: 0,
+            last_drawn_hints: 0,
+            last_drawn_capture_hints: 0,
+            movable: None,
+            promotion: None,
+            last_drawn_promotion: 0,
             force_full_repaint: false,
             piece_sprites: PieceSprites::load(),
         }
     }
 
     pub fn set_position(&mut self, board: Bitboards) {
+        self.live = Some(board);
+        self.show();
+    }
+
+    /// The live position, even while an earlier one is being browsed.
+    pub fn position(&self) -> Option<&Bitboards> {
+        self.live.as_ref()
+    }
+
+    /// Show the position after the first `ply` moves of the game, or the
+    /// live one again with `None`. The board takes no moves while browsing;
+    /// a tap on it asks to go back to live. Asking for the live ply (or
+    /// beyond) is the same as `None`.
+    pub fn browse(&mut self, ply: Option<usize>) {
+        let plies = self.live.as_ref().map_or(0, Bitboards::ply_count);
+        self.browsing = ply.filter(|&p| p < plies);
+        self.show();
+    }
+
+    pub fn browsing(&self) -> Option<usize> {
+        self.browsing
+    }
+
+    // Put the live position, or the browsed one, on `position`.
+    fn show(&mut self) {
+        let Some(live) = &self.live else {
+            return;
+        };
+        // A takeback can leave the browsed ply past the end of the game.
+        self.browsing = self.browsing.filter(|&p| p < live.ply_count());
+        let mut board = live.clone();
+        if let Some(ply) = self.browsing {
+            while board.ply_count() > ply && board.unmake_move().is_some() {}
+        }
+        self.last_move_squares = match self.browsing {
+            Some(_) => board.last_move_mask(),
+            None => self.live_last_move,
+        };
+        // A promotion picked against a position that has since changed
+        // would be for the wrong move.
+        if self.position.as_ref().is_some_and(|p| p.diff(&board) != 0) {
+            self.promotion = None;
+        }
         self.position = Some(board);
+        // Keep the selection while it's still a piece that can move; the
+        // hints may have changed either way.
+        if self.browsing.is_some()
+            || self.selected_square.is_some_and(|sq| !self.can_select(sq))
+        {
+            self.selected_square = None;
+            self.promotion = None;
+        }
+        self.update_hints();
+    }
+
+    /// Only pieces of `color` can be selected; `None` allows the side to
+    /// move, whichever it is.
+    pub fn set_movable(&mut self, color: Option<Color>) {
+        self.movable = color;
     }
 
     /// Set the squares to highlight as "last move played" — bitmask matches
     /// the LERF layout of `Bitboards`. Pass 0 to clear. Already-equal values
     /// are a no-op (no e-ink repaint), so it's safe to call on every event.
     pub fn set_last_move(&mut self, squares: u64) {
-        self.last_move_squares = squares;
+        self.live_last_move = squares;
+        if self.browsing.is_none() {
+            self.last_move_squares = squares;
+        }
+    }
+
+    pub fn flipped(&self) -> bool {
+        self.flipped
     }
 
     pub fn set_flipped(&mut self, flipped: bool) {
@@ -144,6 +278,12 @@ impl BoardWidget {
         }
     }
 
+    /// Repaint every square next render, after something else was drawn
+    /// over the board.
+    pub fn invalidate(&mut self) {
+        self.force_full_repaint = true;
+    }
+
     pub fn handle_touch(&mut self, touch: &TouchEvent) -> Option<AppEvent> {
         if !self.area.contains(touch.x, touch.y) {
             return None;
@@ -168,27 +308,94 @@ impl BoardWidget {
         if touch.kind == TouchKind::Down {
             info!("Board touched at {}", square.to_algebraic());
 
+            if self.browsing.is_some() {
+                return Some(AppEvent::BrowseTo(None));
+            }
+
+            // With the promotion picker open, a tap on one of its pieces
+            // completes the move; anywhere else on the board cancels it.
+            if let Some(picker) = self.promotion.take() {
+                let idx = square_to_index(square);
+                return match picker.cells().find(|&(sq, _)| sq == idx) {
+                    Some((_, piece)) => Some(AppEvent::MoveMade(ChessMove {
+                        promotion: Some(piece),
+                        ..picker.mv
+                    })),
+                    None => {
+                        info!("Promotion cancelled");
+                        None
+                    }
+                };
+            }
+
             if let Some(selected) = self.selected_square {
-                if selected != square {
+                if selected == square {
+                    // Tap the same square again → deselect.
+                    self.select(None);
+                    return None;
+                }
+                if self.move_hints & (1u64 << square_to_index(square)) != 0 {
                     let chess_move = ChessMove {
                         from: selected,
                         to: square,
+                        promotion: None,
                     };
-                    self.selected_square = None;
+                    self.select(None);
+                    // Hold a promotion back until a piece is picked.
+                    if let Some(picker) = self.promotion_picker(chess_move) {
+                        self.promotion = Some(picker);
+                        return None;
+                    }
                     return Some(AppEvent::MoveMade(chess_move));
-                } else {
-                    // Tap the same square again → deselect.
-                    self.selected_square = None;
                 }
-            } else {
-                self.selected_square = Some(square);
+                // Not somewhere the selected piece can go: treat the tap as
+                // a fresh selection instead of sending a move that would
+                // only be rejected.
+            }
+            if self.can_select(square) {
+                self.select(Some(square));
                 return Some(AppEvent::SquareSelected(square));
             }
+            self.select(None);
         }
 
         None
     }
 
+    fn select(&mut self, square: Option<Square>) {
+        self.selected_square = square;
+        self.update_hints();
+    }
+
+    // Whether `square` holds a piece the player may pick up.
+    fn can_select(&self, square: Square) -> bool {
+        let Some(position) = &self.position else {
+            return false;
+        };
+        let color = self.movable.unwrap_or(position.side_to_move);
+        matches!(position.piece_at(square_to_index(square)), Some((c, _)) if c == color)
+    }
+
+    // Recompute the hints for the current selection. Empty when nothing is
+    // selected or the selected piece's side isn't the one to move.
+    fn update_hints(&mut self) {
+        self.move_hints = 0;
+        self.capture_hints = 0;
+        let (Some(position), Some(selected)) = (&self.position, self.selected_square) else {
+            return;
+        };
+        let from = square_to_index(selected);
+        for mv in position.legal_moves() {
+            if mv.from != from {
+                continue;
+            }
+            self.move_hints |= 1u64 << mv.to;
+            if position.piece_at(mv.to).is_some() {
+                self.capture_hints |= 1u64 << mv.to;
+            }
+        }
+    }
+
     pub fn render(&mut self, renderer: &mut Renderer) -> Result<(), Box<dyn std::error::Error>> {
         let do_partial = !self.force_full_repaint
             && self.last_drawn_position.is_some()
@@ -201,15 +408,13 @@ impl BoardWidget {
 
         if do_partial {
             // Position diff: repaint only squares whose contents differ.
-            let prev = self.last_drawn_position.as_ref().unwrap().clone();
-            let curr = self.position.as_ref().unwrap().clone();
-            for sq in 0..64u8 {
-                let before = prev.piece_at(sq);
-                let after = curr.piece_at(sq);
-                if before != after {
-                    self.repaint_square(renderer, sq, after, before.is_some())?;
-                    repainted |= 1u64 << sq;
-                }
+            // `diff` short-circuits on equal hashes, so the common "nothing
+            // moved" frame (selection taps, repeated snapshots) costs nothing.
+            let prev = self.last_drawn_position.as_ref().unwrap();
+            let curr = self.position.as_ref().unwrap();
+            for sq in squares(curr.diff(prev)) {
+                self.repaint_square(renderer, sq, curr.piece_at(sq), prev.piece_at(sq).is_some())?;
+                repainted |= 1u64 << sq;
             }
         } else {
             // Full paint: every square + every piece + border.
@@ -252,6 +457,32 @@ impl BoardWidget {
             // highlight is gone too.
             self.last_drawn_selection = None;
             self.last_drawn_last_move = 0;
+            self.last_drawn_hints = 0;
+            self.last_drawn_capture_hints = 0;
+            self.last_drawn_promotion = 0;
+        }
+
+        // Hint diff: a square whose hint went away or changed kind (dot ↔
+        // ring) is repainted clean; current hints are stamped further down.
+        let prev_hints = self.last_drawn_hints;
+        let curr_hints = self.move_hints;
+        let changed_hints =
+            (prev_hints ^ curr_hints) | (self.last_drawn_capture_hints ^ self.capture_hints);
+        for sq in squares(prev_hints & changed_hints & !repainted) {
+            let new_piece = self.position.as_ref().and_then(|b| b.piece_at(sq));
+            self.repaint_square(renderer, sq, new_piece, true)?;
+            repainted |= 1u64 << sq;
+        }
+
+        // Promotion picker closed (or moved): put the squares it covered
+        // back. Brackets and the selection are re-stamped on them below.
+        let prev_pp = self.last_drawn_promotion;
+        let curr_pp = self.promotion.as_ref().map_or(0, PromotionPicker::mask);
+        let removed_pp = prev_pp & !curr_pp & !repainted;
+        for sq in squares(removed_pp) {
+            let new_piece = self.position.as_ref().and_then(|b| b.piece_at(sq));
+            self.repaint_square(renderer, sq, new_piece, true)?;
+            repainted |= 1u64 << sq;
         }
 
         // Last-move diff: clear squares whose bracket is going away (scrub —
@@ -306,6 +537,12 @@ impl BoardWidget {
             }
         }
 
+        // Hints sit in the middle of the square (or just inside its edge),
+        // clear of the corner decorations.
+        for sq in squares(curr_hints & (changed_hints | repainted)) {
+            self.draw_move_hint(renderer, sq)?;
+        }
+
         // Selection on top of the last-move bracket — they share corner
         // territory and the diagonal cut reads more cleanly drawn last.
         if let Some(sel) = curr_sel {
@@ -317,9 +554,18 @@ impl BoardWidget {
             }
         }
 
+        // The picker goes over everything else. Redraw it when it opens or
+        // when anything underneath it was just drawn.
+        if curr_pp != 0 && (curr_pp != prev_pp || (repainted | stamp_lm) & curr_pp != 0) {
+            self.draw_promotion_picker(renderer)?;
+        }
+
         self.last_drawn_position = self.position.clone();
         self.last_drawn_selection = self.selected_square;
         self.last_drawn_last_move = self.last_move_squares;
+        self.last_drawn_hints = self.move_hints;
+        self.last_drawn_capture_hints = self.capture_hints;
+        self.last_drawn_promotion = curr_pp;
         self.force_full_repaint = false;
         Ok(())
     }
@@ -397,25 +643,87 @@ impl BoardWidget {
         Ok(())
     }
 
-    /// Encode `mv` as a UCI string for the Lichess board API. For a pawn that
-    /// lands on its last rank, append a queen promotion suffix — there is no
-    /// promotion-picker UI yet, and queen is the practical default. Returns
+    /// Encode `mv` as a UCI string for the Lichess board API, with the
+    /// piece picked in the promotion overlay if there was one. Returns
     /// `None` if no position is loaded yet (shouldn't happen — moves are only
     /// emitted after `set_position`).
     pub fn move_to_uci(&self, mv: ChessMove) -> Option<String> {
-        let from_idx = mv.from.rank * 8 + mv.from.file;
-        let last_rank = match self.position.as_ref()?.piece_at(from_idx) {
-            Some((Color::White, Piece::Pawn)) => Some(7u8),
-            Some((Color::Black, Piece::Pawn)) => Some(0u8),
-            _ => None,
-        };
+        let position = self.position.as_ref()?;
+        let from_idx = square_to_index(mv.from);
+        let to_idx = square_to_index(mv.to);
+        // A legal move goes out through the board, which writes castling
+        // king-takes-rook when the game is Chess960. Anything else is sent
+        // as tapped for the server (or the local game) to reject.
+        if let Some(legal) = position.legal_moves().into_iter().find(|m| {
+            m.from == from_idx && m.to == to_idx && m.promotion == mv.promotion
+        }) {
+            return Some(position.move_to_uci(legal));
+        }
         let mut uci = format!("{}{}", mv.from.to_algebraic(), mv.to.to_algebraic());
-        if last_rank == Some(mv.to.rank) {
-            uci.push('q');
+        if let Some(piece) = mv.promotion {
+            uci.push(promotion_char(piece));
         }
         Some(uci)
     }
 
+    // The picker for `mv` if it's a legal promotion, offering every piece
+    // the rules allow (the king too, in Antichess).
+    fn promotion_picker(&self, mv: ChessMove) -> Option<PromotionPicker> {
+        let position = self.position.as_ref()?;
+        let from_idx = square_to_index(mv.from);
+        let to_idx = square_to_index(mv.to);
+        let mut choices: Vec<Piece> = position
+            .legal_moves()
+            .into_iter()
+            .filter(|m| m.from == from_idx && m.to == to_idx)
+            .filter_map(|m| m.promotion)
+            .collect();
+        if choices.is_empty() {
+            return None;
+        }
+        choices.sort_by_key(|&piece| PROMOTION_ORDER.iter().position(|&p| p == piece));
+        choices.dedup();
+        Some(PromotionPicker {
+            mv,
+            color: position.side_to_move,
+            choices,
+        })
+    }
+
+    /// Draw the promotion overlay: a white cell with a black border per
+    /// choice, the piece sprite on top.
+    fn draw_promotion_picker(
+        &self,
+        renderer: &mut Renderer,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(picker) = &self.promotion else {
+            return Ok(());
+        };
+        for (sq, piece) in picker.cells() {
+            let file = sq % 8;
+            let rank = sq / 8;
+            let display_file = if self.flipped { 7 - file } else { file };
+            let display_rank = if self.flipped { 7 - rank } else { rank };
+            let x = self.area.x + (display_file as i16) * SQUARE_SIZE as i16;
+            let y = self.area.y + (7 - display_rank as i16) * SQUARE_SIZE as i16;
+            let rect = Rectangle::new(x, y, SQUARE_SIZE, SQUARE_SIZE);
+            renderer.draw_rectangle(rect, DrawColor::White, true)?;
+            renderer.draw_rectangle(rect, DrawColor::Black, false)?;
+            if let Some(sprite) = self.piece_sprites.get(picker.color, piece) {
+                let inset = ((SQUARE_SIZE - PIECE_DRAW_SIZE) / 2) as i16;
+                renderer.draw_image_alpha(
+                    x + inset,
+                    y + inset,
+                    PIECE_DRAW_SIZE,
+                    PIECE_DRAW_SIZE,
+                    sprite,
+                    DrawColor::White,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
     /// Draw the selection highlight: four 45° lines starting at each corner
     /// and pointing toward the centre, each running `SEGMENT_LEN` along both
     /// axes — so each line's length is a quarter of the square's diagonal.
@@ -452,6 +760,28 @@ impl BoardWidget {
         Ok(())
     }
 
+    /// Draw a move hint: a ring around a piece that can be captured, a dot
+    /// on an empty square the selected piece can move to.
+    fn draw_move_hint(
+        &self,
+        renderer: &mut Renderer,
+        sq: u8,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let file = sq % 8;
+        let rank = sq / 8;
+        let display_file = if self.flipped { 7 - file } else { file };
+        let display_rank = if self.flipped { 7 - rank } else { rank };
+        let half = (SQUARE_SIZE / 2) as i16;
+        let cx = self.area.x + (display_file as i16) * SQUARE_SIZE as i16 + half;
+        let cy = self.area.y + (7 - display_rank as i16) * SQUARE_SIZE as i16 + half;
+        let color = highlight_color(file, rank);
+        if self.capture_hints & (1u64 << sq) != 0 {
+            renderer.draw_circle(cx, cy, HINT_RING_RADIUS, color, HINT_RING_STROKE)
+        } else {
+            renderer.fill_circle(cx, cy, HINT_DOT_RADIUS, color)
+        }
+    }
+
     /// Draw the last-move highlight: each corner gets an "L" of two
     /// `SEGMENT_LEN`-long, `LAST_MOVE_STROKE`-thick filled rectangles
     /// running along the adjacent edges. Filled rects (not poly_segment)
@@ -512,3 +842,23 @@ fn highlight_color(file: u8, rank: u8) -> DrawColor {
 fn square_to_index(sq: Square) -> u8 {
     sq.rank * 8 + sq.file
 }
+
+// Order of the promotion picker's cells, most useful first.
+const PROMOTION_ORDER: [Piece; 5] = [
+    Piece::Queen,
+    Piece::Knight,
+    Piece::Rook,
+    Piece::Bishop,
+    Piece::King,
+];
+
+fn promotion_char(piece: Piece) -> char {
+    match piece {
+        Piece::Queen => 'q',
+        Piece::Rook => 'r',
+        Piece::Bishop => 'b',
+        Piece::Knight => 'n',
+        Piece::King => 'k',
+        Piece::Pawn => 'p',
+    }
+}
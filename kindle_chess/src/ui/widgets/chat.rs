@@ -0,0 +1,223 @@
+use crate::app::chat::QUICK_REPLIES;
+use crate::models::chat::{ChatLog, ChatMessage, ChatRoom};
+use crate::ui::events::{AppEvent, Rectangle, RectangleExt, TouchEvent, TouchKind};
+use crate::ui::renderer::{DrawColor, Renderer};
+use crate::ui::widgets::Button;
+use log::info;
+
+const MARGIN: i16 = 20;
+const BUTTON_HEIGHT: u16 = 75;
+const TAB_WIDTH: u16 = 280;
+const CLOSE_WIDTH: u16 = 200;
+const REPLY_COLUMNS: usize = 2;
+const REPLY_GAP: i16 = 10;
+const FONT_SIZE: f32 = 30.0;
+const LINE_HEIGHT: i16 = 40;
+
+/// The game's chat, drawn over the board while it's open: a tab per room,
+/// the latest messages of the one picked, and quick replies to post there.
+pub struct ChatOverlay {
+    area: Rectangle,
+    log: ChatLog,
+    room: ChatRoom,
+    open: bool,
+    player_tab: Button,
+    spectator_tab: Button,
+    close_button: Button,
+    replies: Vec<Button>,
+}
+
+impl ChatOverlay {
+    pub fn new(area: Rectangle) -> Self {
+        let tab = |index: i16, label: &str| {
+            Button::new(
+                area.x + MARGIN + index * (TAB_WIDTH as i16 + MARGIN),
+                area.y + MARGIN,
+                TAB_WIDTH,
+                BUTTON_HEIGHT,
+                label.to_string(),
+                36.0,
+                true,
+            )
+        };
+        let reply_width = (area.width as i16 - 2 * MARGIN - REPLY_GAP * (REPLY_COLUMNS as i16 - 1))
+            / REPLY_COLUMNS as i16;
+        let reply_rows = QUICK_REPLIES.len().div_ceil(REPLY_COLUMNS) as i16;
+        let replies_y =
+            area.y + area.height as i16 - MARGIN - reply_rows * (BUTTON_HEIGHT as i16 + REPLY_GAP)
+                + REPLY_GAP;
+        let replies = QUICK_REPLIES
+            .iter()
+            .enumerate()
+            .map(|(i, reply)| {
+                let column = (i % REPLY_COLUMNS) as i16;
+                let row = (i / REPLY_COLUMNS) as i16;
+                Button::new(
+                    area.x + MARGIN + column * (reply_width + REPLY_GAP),
+                    replies_y + row * (BUTTON_HEIGHT as i16 + REPLY_GAP),
+                    reply_width as u16,
+                    BUTTON_HEIGHT,
+                    reply.to_string(),
+                    FONT_SIZE,
+                    true,
+                )
+            })
+            .collect();
+        Self {
+            area,
+            log: ChatLog::default(),
+            room: ChatRoom::Player,
+            open: false,
+            player_tab: tab(0, "players"),
+            spectator_tab: tab(1, "spectators"),
+            close_button: Button::new(
+                area.x + area.width as i16 - MARGIN - CLOSE_WIDTH as i16,
+                area.y + MARGIN,
+                CLOSE_WIDTH,
+                BUTTON_HEIGHT,
+                "close".to_string(),
+                36.0,
+                true,
+            ),
+            replies,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    /// A new line from the stream. Returns whether the player hasn't seen
+    /// it, the overlay being closed.
+    pub fn push(&mut self, message: ChatMessage) -> bool {
+        self.log.push(message);
+        !self.open
+    }
+
+    /// The player room's history, fetched when the game is opened.
+    pub fn load(&mut self, history: Vec<ChatMessage>) {
+        self.log.load(ChatRoom::Player, history);
+    }
+
+    /// Switching rooms is handled here and just needs a redraw; closing and
+    /// replying go to the screen.
+    pub fn handle_touch(&mut self, touch: &TouchEvent) -> Option<AppEvent> {
+        if !self.open || touch.kind != TouchKind::Up {
+            return None;
+        }
+        if self.close_button.rect.contains(touch.x, touch.y) {
+            return Some(AppEvent::CloseChat);
+        }
+        if self.player_tab.rect.contains(touch.x, touch.y) {
+            self.room = ChatRoom::Player;
+        } else if self.spectator_tab.rect.contains(touch.x, touch.y) {
+            self.room = ChatRoom::Spectator;
+        } else if let Some(reply) = self
+            .replies
+            .iter()
+            .find(|b| b.rect.contains(touch.x, touch.y))
+        {
+            info!(
+                "Quick reply '{}' to the {} room",
+                reply.label,
+                self.room.as_str()
+            );
+            return Some(AppEvent::SendChat(self.room, reply.label.clone()));
+        }
+        None
+    }
+
+    pub fn render(&self, renderer: &mut Renderer) -> Result<(), Box<dyn std::error::Error>> {
+        renderer.draw_rectangle(self.area, DrawColor::White, true)?;
+        renderer.draw_rectangle(self.area, DrawColor::Black, false)?;
+
+        self.player_tab.draw(renderer)?;
+        self.spectator_tab.draw(renderer)?;
+        self.close_button.draw(renderer)?;
+        // The open room's tab gets a heavy frame, like the current move in
+        // the sidebar.
+        let tab = match self.room {
+            ChatRoom::Player => self.player_tab.rect,
+            ChatRoom::Spectator => self.spectator_tab.rect,
+        };
+        for inset in 1..4 {
+            let frame = Rectangle::new(
+                tab.x + inset,
+                tab.y + inset,
+                tab.width - 2 * inset as u16,
+                tab.height - 2 * inset as u16,
+            );
+            renderer.draw_rectangle(frame, DrawColor::Black, false)?;
+        }
+
+        for reply in &self.replies {
+            reply.draw(renderer)?;
+        }
+
+        self.render_messages(renderer)
+    }
+
+    // Word-wrapped, newest at the bottom; whatever doesn't fit above is
+    // cut off.
+    fn render_messages(&self, renderer: &mut Renderer) -> Result<(), Box<dyn std::error::Error>> {
+        let top = self.player_tab.rect.y + BUTTON_HEIGHT as i16 + MARGIN;
+        let bottom = self
+            .replies
+            .first()
+            .map_or(self.area.y + self.area.height as i16, |b| b.rect.y)
+            - MARGIN;
+        let width = self.area.width as u32 - 2 * MARGIN as u32;
+
+        let messages = self.log.room(self.room);
+        if messages.is_empty() {
+            let empty = "No messages yet";
+            let (tw, _) = renderer.measure_text(empty, FONT_SIZE);
+            let x = self.area.x + (self.area.width as i16 - tw as i16) / 2;
+            return renderer.draw_text(x, top, empty, FONT_SIZE, DrawColor::Black);
+        }
+
+        let capacity = ((bottom - top) / LINE_HEIGHT).max(0) as usize;
+        let mut lines: Vec<String> = Vec::new();
+        for message in messages.iter().rev() {
+            let text = format!("{}: {}", message.username, message.text);
+            let wrapped = wrap(renderer, &text, width);
+            lines.splice(0..0, wrapped);
+            if lines.len() >= capacity {
+                break;
+            }
+        }
+        let skip = lines.len().saturating_sub(capacity);
+        for (row, line) in lines[skip..].iter().enumerate() {
+            let y = top + row as i16 * LINE_HEIGHT;
+            renderer.draw_text(self.area.x + MARGIN, y, line, FONT_SIZE, DrawColor::Black)?;
+        }
+        Ok(())
+    }
+}
+
+// Greedy word wrap to `width` pixels. A single word wider than that gets a
+// line of its own and is clipped.
+fn wrap(renderer: &Renderer, text: &str, width: u32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", line, word)
+        };
+        if renderer.measure_text(&candidate, FONT_SIZE).0 <= width || line.is_empty() {
+            line = candidate;
+        } else {
+            lines.push(std::mem::replace(&mut line, word.to_string()));
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use crate::app::clock::format_clock;
+use crate::models::bitboard::Color;
+use crate::models::clock::GameClock;
+use crate::ui::events::{Rectangle, RectangleExt};
+use crate::ui::renderer::{DrawColor, Renderer};
+
+const FONT_SIZE: f32 = 36.0;
+const LOW_TIME_DOT_RADIUS: u16 = 9;
+
+/// One side's clock, in a player bar. Only whole seconds are shown, and
+/// `render` skips the repaint unless what's on screen would change, so a
+/// running clock costs one small e-ink update a second.
+pub struct ClockWidget {
+    area: Rectangle,
+    color: Color,
+    /// What to show: (whole seconds, running, low). `None` hides the clock
+    /// (untimed or offline games).
+    state: Option<(u64, bool, bool)>,
+    last_drawn: Option<(u64, bool, bool)>,
+}
+
+impl ClockWidget {
+    pub fn new(area: Rectangle, color: Color) -> Self {
+        Self {
+            area,
+            color,
+            state: None,
+            last_drawn: None,
+        }
+    }
+
+    /// Which side's time to show; the bars swap sides when the board flips.
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    pub fn set_clock(&mut self, clock: Option<&GameClock>) {
+        self.state = clock.map(|c| {
+            (
+                c.remaining(self.color).as_secs(),
+                c.running == Some(self.color),
+                c.is_low(self.color),
+            )
+        });
+    }
+
+    /// Paint the clock if it changed since the last paint, or always with
+    /// `force` (after something else drew over its rectangle).
+    pub fn render(
+        &mut self,
+        renderer: &mut Renderer,
+        force: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if !force && self.state == self.last_drawn {
+            return Ok(());
+        }
+        self.last_drawn = self.state;
+        renderer.draw_rectangle(self.area, DrawColor::White, true)?;
+        let Some((secs, running, low)) = self.state else {
+            return Ok(());
+        };
+
+        // The running clock gets a heavy frame, the other a thin one.
+        renderer.draw_rectangle(self.area, DrawColor::Black, false)?;
+        if running {
+            for inset in 1..4 {
+                let frame = Rectangle::new(
+                    self.area.x + inset,
+                    self.area.y + inset,
+                    self.area.width - 2 * inset as u16,
+                    self.area.height - 2 * inset as u16,
+                );
+                renderer.draw_rectangle(frame, DrawColor::Black, false)?;
+            }
+        }
+
+        let text = format_clock(Duration::from_secs(secs));
+        let (tw, th) = renderer.measure_text(&text, FONT_SIZE);
+        let tx = self.area.x + (self.area.width as i16 - tw as i16) / 2;
+        let ty = self.area.y + (self.area.height as i16 - th as i16) / 2;
+        renderer.draw_text(tx, ty, &text, FONT_SIZE, DrawColor::Black)?;
+
+        // Low on time: a dot before the text.
+        if low {
+            let radius = LOW_TIME_DOT_RADIUS as i16;
+            renderer.fill_circle(
+                tx - 2 * radius,
+                self.area.y + self.area.height as i16 / 2,
+                LOW_TIME_DOT_RADIUS,
+                DrawColor::Black,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use crate::ui::events::{AppEvent, Rectangle, RectangleExt, TouchEvent, TouchKind};
+use crate::ui::renderer::Renderer;
+use crate::ui::widgets::Button;
+
+// Enough for Lichess usernames: letters, digits, `_` and `-`.
+const ROWS: [&str; 4] = ["1234567890", "qwertyuiop", "asdfghjkl_", "zxcvbnm-"];
+const KEY_GAP: i16 = 6;
+const FONT_SIZE: f32 = 40.0;
+
+/// An on-screen keyboard, since the device has none. Taps come back as
+/// `KeyTyped` and `KeyErased`.
+pub struct KeyboardWidget {
+    area: Rectangle,
+    keys: Vec<(char, Button)>,
+    erase_button: Button,
+}
+
+impl KeyboardWidget {
+    pub fn new(area: Rectangle) -> Self {
+        let columns = ROWS.iter().map(|r| r.len()).max().unwrap_or(1) as i16;
+        let key_width = (area.width as i16 - KEY_GAP * (columns - 1)) / columns;
+        let key_height =
+            (area.height as i16 - KEY_GAP * (ROWS.len() as i16 - 1)) / ROWS.len() as i16;
+        let key_rect = |row: usize, column: usize| {
+            Rectangle::new(
+                area.x + column as i16 * (key_width + KEY_GAP),
+                area.y + row as i16 * (key_height + KEY_GAP),
+                key_width as u16,
+                key_height as u16,
+            )
+        };
+        let mut keys = Vec::new();
+        for (row, letters) in ROWS.iter().enumerate() {
+            for (column, key) in letters.chars().enumerate() {
+                let rect = key_rect(row, column);
+                let button = Button::new(
+                    rect.x,
+                    rect.y,
+                    rect.width,
+                    rect.height,
+                    key.to_string(),
+                    FONT_SIZE,
+                    true,
+                );
+                keys.push((key, button));
+            }
+        }
+        // Erase takes the rest of the last row.
+        let last = ROWS.len() - 1;
+        let erase = key_rect(last, ROWS[last].len());
+        let erase_width = area.x + area.width as i16 - erase.x;
+        Self {
+            area,
+            keys,
+            erase_button: Button::new(
+                erase.x,
+                erase.y,
+                erase_width as u16,
+                erase.height,
+                "erase".to_string(),
+                36.0,
+                true,
+            ),
+        }
+    }
+
+    pub fn handle_touch(&self, touch: &TouchEvent) -> Option<AppEvent> {
+        if touch.kind != TouchKind::Up || !self.area.contains(touch.x, touch.y) {
+            return None;
+        }
+        if self.erase_button.rect.contains(touch.x, touch.y) {
+            return Some(AppEvent::KeyErased);
+        }
+        self.keys
+            .iter()
+            .find(|(_, button)| button.rect.contains(touch.x, touch.y))
+            .map(|(key, _)| AppEvent::KeyTyped(*key))
+    }
+
+    pub fn render(&self, renderer: &mut Renderer) -> Result<(), Box<dyn std::error::Error>> {
+        for (_, button) in &self.keys {
+            button.draw(renderer)?;
+        }
+        self.erase_button.draw(renderer)
+    }
+}
@@ -0,0 +1,113 @@
+use crate::models::bitboard::{Bitboards, Color, Piece};
+use crate::models::clock::GameClock;
+use crate::ui::events::{Rectangle, RectangleExt};
+use crate::ui::renderer::{DrawColor, Renderer};
+use crate::ui::widgets::ClockWidget;
+use crate::ui::widgets::board::PieceSprites;
+
+const FONT_SIZE: f32 = 32.0;
+const TRAY_PIECE_SIZE: u16 = 36;
+// Captured pieces of one kind overlap; a new kind starts a little further on.
+const TRAY_STEP: i16 = 22;
+const TRAY_GROUP_GAP: i16 = 14;
+const CLOCK_WIDTH: u16 = 200;
+
+/// A strip above or below the board for the player whose pieces are on
+/// that edge: name, the pieces they've taken with their material lead, and
+/// their clock. The screen swaps the bars' sides when the board flips.
+pub struct PlayerBarWidget {
+    area: Rectangle,
+    color: Color,
+    label: String,
+    /// Opponent pieces this side has captured, cheapest first.
+    captured: Vec<Piece>,
+    /// Material ahead of the opponent, in pawns; shown when positive.
+    advantage: i32,
+    clock: ClockWidget,
+    sprites: PieceSprites,
+}
+
+impl PlayerBarWidget {
+    pub fn new(area: Rectangle, color: Color) -> Self {
+        let clock_area = Rectangle::new(
+            area.x + area.width as i16 - CLOCK_WIDTH as i16 - 10,
+            area.y + 5,
+            CLOCK_WIDTH,
+            area.height - 10,
+        );
+        Self {
+            area,
+            color,
+            label: String::new(),
+            captured: Vec::new(),
+            advantage: 0,
+            clock: ClockWidget::new(clock_area, color),
+            sprites: PieceSprites::load(),
+        }
+    }
+
+    /// Show `color`'s player, called `label`.
+    pub fn set_player(&mut self, color: Color, label: String) {
+        self.color = color;
+        self.label = label;
+        self.clock.set_color(color);
+    }
+
+    pub fn set_material(&mut self, board: &Bitboards) {
+        self.captured = board.captured(self.color.flip());
+        self.advantage = match self.color {
+            Color::White => board.material_balance(),
+            Color::Black => -board.material_balance(),
+        };
+    }
+
+    pub fn set_clock(&mut self, clock: Option<&GameClock>) {
+        self.clock.set_clock(clock);
+    }
+
+    pub fn render(&mut self, renderer: &mut Renderer) -> Result<(), Box<dyn std::error::Error>> {
+        renderer.draw_rectangle(self.area, DrawColor::White, true)?;
+
+        let (lw, lh) = renderer.measure_text(&self.label, FONT_SIZE);
+        let text_y = self.area.y + (self.area.height as i16 - lh as i16) / 2;
+        let mut x = self.area.x + 20;
+        renderer.draw_text(x, text_y, &self.label, FONT_SIZE, DrawColor::Black)?;
+        x += lw as i16 + 30;
+
+        // The captured pieces are drawn in the opponent's colour.
+        let tray_y = self.area.y + (self.area.height as i16 - TRAY_PIECE_SIZE as i16) / 2;
+        let mut previous = None;
+        for &piece in &self.captured {
+            if previous.is_some_and(|p| p != piece) {
+                x += TRAY_GROUP_GAP;
+            }
+            previous = Some(piece);
+            if let Some(sprite) = self.sprites.get(self.color.flip(), piece) {
+                renderer.draw_image_alpha(
+                    x,
+                    tray_y,
+                    TRAY_PIECE_SIZE,
+                    TRAY_PIECE_SIZE,
+                    sprite,
+                    DrawColor::White,
+                )?;
+            }
+            x += TRAY_STEP;
+        }
+        if self.advantage > 0 {
+            x += TRAY_PIECE_SIZE as i16 - TRAY_STEP + 8;
+            let lead = format!("+{}", self.advantage);
+            renderer.draw_text(x, text_y, &lead, FONT_SIZE, DrawColor::Black)?;
+        }
+
+        self.clock.render(renderer, true)
+    }
+
+    /// Repaint just the clock, if what it shows changed.
+    pub fn render_clock(
+        &mut self,
+        renderer: &mut Renderer,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.clock.render(renderer, false)
+    }
+}
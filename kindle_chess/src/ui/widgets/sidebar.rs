@@ -1,58 +1,340 @@
-use crate::models::board_api::Turn;
+use crate::models::bitboard::Color;
+use crate::models::board_api::{Offer, Turn};
 use crate::ui::events::{AppEvent, Rectangle, RectangleExt, TouchEvent, TouchKind};
 use crate::ui::renderer::{DrawColor, Renderer};
 use crate::ui::widgets::Button;
 use log::info;
 
+// Move list: numbered pairs in the left part of the sidebar, under the
+// status line. The buttons take a column on the right, three rows of them.
+const MOVE_LIST_X: i16 = 20;
+const MOVE_LIST_Y: i16 = 60;
+const MOVE_ROW_HEIGHT: i16 = 38;
+const MOVE_ROWS: usize = 5;
+const BUTTON_ROW_GAP: i16 = 8;
+const MOVE_NUMBER_WIDTH: i16 = 90;
+const MOVE_CELL_WIDTH: i16 = 260;
+const MOVE_FONT_SIZE: f32 = 30.0;
+// A prompt (an offer to answer, a win to claim) takes the move list's place
+// until it's answered.
+const PROMPT_BUTTON_Y: i16 = 120;
+const PROMPT_BUTTON_WIDTH: u16 = 300;
+
 pub struct SidebarWidget {
     area: Rectangle,
+    chat_button: Button,
     menu_button: Button,
     back_button: Button,
+    prev_button: Button,
+    live_button: Button,
+    next_button: Button,
     event_count: u32,
     // Driven by `set_turn` from the game-state stream events arriving on
     // ChessGameScreen. Read by `render` to draw the status line.
     turn_status: String,
+    // While the game-state stream is down, what the status line shows
+    // instead (`set_connection`).
+    connection: Option<String>,
+    // Offline engine games: the engine's latest search report and its
+    // depth, after the turn on the status line (`set_analysis`).
+    analysis: Option<(u8, String)>,
+    // A game or challenge waiting for this game to be left
+    // (`set_notice`). Shown after the turn, ahead of the analysis.
+    notice: Option<String>,
+    // The game's moves in SAN, from `set_moves`, and the number of the first
+    // one. A game set up with Black to move leaves White's half of the first
+    // row empty.
+    moves: Vec<String>,
+    first_number: u32,
+    black_first: bool,
+    // Plies into the game the board is showing while browsing; `None` when
+    // it shows the live position. Mirrors `BoardWidget::browsing`.
+    viewing: Option<usize>,
+    // First move-pair row in view. Follows the highlighted move.
+    scroll: usize,
+    // The opponent's open offer, and whether their absence lets us claim
+    // the win. An offer is prompted for first.
+    offer: Option<Offer>,
+    claimable: bool,
+    accept_button: Button,
+    decline_button: Button,
+    // Online games only. The chat button is starred while there are lines
+    // not yet seen (`set_chat_unread`).
+    chat_enabled: bool,
 }
 
 impl SidebarWidget {
     pub fn new(area: Rectangle) -> Self {
-        let button_width = 200 as i16;
+        let button_width = 290 as i16;
         let button_height = 75 as i16;
+        let column_x = area.x + area.width as i16 - button_width - 30;
+        let step_width: i16 = 90;
+        let row_y = |row: i16| area.y + 10 + row * (button_height + BUTTON_ROW_GAP);
+        let step_y = row_y(0);
+        let chat_width: i16 = 140;
         Self {
             area,
             back_button: Button::new(
-                area.x + area.width as i16 / 2 - button_width / 2,
-                area.y + area.height as i16 - (button_height + 20),
+                column_x,
+                row_y(2),
                 button_width as u16,
                 button_height as u16,
                 "back".to_string(),
                 40.0,
                 true,
             ),
+            chat_button: Button::new(
+                column_x,
+                row_y(1),
+                chat_width as u16,
+                button_height as u16,
+                "chat".to_string(),
+                40.0,
+                true,
+            ),
             menu_button: Button::new(
-                area.x + area.width as i16 / 2 - button_width / 2,
-                area.y + area.height as i16 - 2 * (button_height + 15),
+                column_x,
+                row_y(1),
                 button_width as u16,
                 button_height as u16,
                 "menu".to_string(),
                 40.0,
                 true,
             ),
+            prev_button: Button::new(
+                column_x,
+                step_y,
+                step_width as u16,
+                button_height as u16,
+                "<".to_string(),
+                40.0,
+                true,
+            ),
+            live_button: Button::new(
+                column_x + step_width + 5,
+                step_y,
+                (button_width - 2 * (step_width + 5)) as u16,
+                button_height as u16,
+                "live".to_string(),
+                36.0,
+                true,
+            ),
+            next_button: Button::new(
+                column_x + button_width - step_width,
+                step_y,
+                step_width as u16,
+                button_height as u16,
+                ">".to_string(),
+                40.0,
+                true,
+            ),
             event_count: 0,
             turn_status: String::from("Loading…"),
+            connection: None,
+            analysis: None,
+            notice: None,
+            moves: Vec::new(),
+            first_number: 1,
+            black_first: false,
+            viewing: None,
+            scroll: 0,
+            offer: None,
+            claimable: false,
+            accept_button: Button::new(
+                area.x + MOVE_LIST_X + 20,
+                area.y + PROMPT_BUTTON_Y,
+                PROMPT_BUTTON_WIDTH,
+                button_height as u16,
+                String::new(),
+                36.0,
+                true,
+            ),
+            decline_button: Button::new(
+                area.x + MOVE_LIST_X + 40 + PROMPT_BUTTON_WIDTH as i16,
+                area.y + PROMPT_BUTTON_Y,
+                PROMPT_BUTTON_WIDTH,
+                button_height as u16,
+                String::new(),
+                36.0,
+                true,
+            ),
+            chat_enabled: false,
+        }
+    }
+
+    /// Give the chat half of the menu row, for online games.
+    pub fn enable_chat(&mut self) {
+        if self.chat_enabled {
+            return;
+        }
+        self.chat_enabled = true;
+        let row = self.menu_button.rect;
+        let chat_width = self.chat_button.rect.width;
+        self.menu_button.rect = Rectangle::new(
+            row.x + chat_width as i16 + 5,
+            row.y,
+            row.width - chat_width - 5,
+            row.height,
+        );
+    }
+
+    pub fn set_chat_unread(&mut self, unread: bool) {
+        self.chat_button.label = if unread { "chat *" } else { "chat" }.to_string();
+    }
+
+    pub fn offer(&self) -> Option<Offer> {
+        self.offer
+    }
+
+    pub fn set_offer(&mut self, offer: Option<Offer>) {
+        self.offer = offer;
+        self.label_prompt();
+    }
+
+    pub fn set_claimable(&mut self, claimable: bool) {
+        self.claimable = claimable;
+        self.label_prompt();
+    }
+
+    // Question and answers of the prompt on show, if any.
+    fn prompt(&self) -> Option<(&'static str, &'static str, &'static str)> {
+        match (self.offer, self.claimable) {
+            (Some(Offer::Draw), _) => Some(("Your opponent offers a draw", "accept", "decline")),
+            (Some(Offer::Takeback), _) => {
+                Some(("Your opponent asks for a takeback", "accept", "decline"))
+            }
+            (None, true) => Some(("Your opponent left the game", "claim win", "wait")),
+            (None, false) => None,
+        }
+    }
+
+    fn label_prompt(&mut self) {
+        if let Some((_, accept, decline)) = self.prompt() {
+            self.accept_button.label = accept.to_string();
+            self.decline_button.label = decline.to_string();
+        }
+    }
+
+    /// The moves made since the position the game was set up with, where
+    /// `first_mover` was to move on move `first_number`.
+    pub fn set_moves(&mut self, moves: Vec<String>, first_mover: Color, first_number: u32) {
+        self.moves = moves;
+        self.first_number = first_number;
+        self.black_first = first_mover == Color::Black;
+        self.follow();
+    }
+
+    pub fn set_browsing(&mut self, ply: Option<usize>) {
+        self.viewing = ply;
+        self.follow();
+    }
+
+    // Plies into the game of the position on the board.
+    fn shown_ply(&self) -> usize {
+        self.viewing.unwrap_or(self.moves.len())
+    }
+
+    // Scroll so the highlighted move's row is in view.
+    fn follow(&mut self) {
+        let Some(index) = self.shown_ply().checked_sub(1) else {
+            self.scroll = 0;
+            return;
+        };
+        let row = self.slot(index) / 2;
+        if row < self.scroll {
+            self.scroll = row;
+        } else if row >= self.scroll + MOVE_ROWS {
+            self.scroll = row + 1 - MOVE_ROWS;
         }
     }
 
+    // Move `index`'s place in the list counting White's empty half of the
+    // first row, so that even slots are White's moves.
+    fn slot(&self, index: usize) -> usize {
+        index + self.black_first as usize
+    }
+
+    // The number drawn in front of move `index`: on White's moves, and on
+    // Black's when it opens the list ("12…").
+    fn move_number(&self, index: usize) -> Option<String> {
+        let slot = self.slot(index);
+        let number = self.first_number as usize + slot / 2;
+        if slot.is_multiple_of(2) {
+            Some(format!("{}.", number))
+        } else if index == 0 {
+            Some(format!("{}…", number))
+        } else {
+            None
+        }
+    }
+
+    // Where move `index` (0 = the first on the list) is drawn, if its row is
+    // in view.
+    fn move_cell(&self, index: usize) -> Option<Rectangle> {
+        let slot = self.slot(index);
+        let row = (slot / 2).checked_sub(self.scroll)?;
+        if row >= MOVE_ROWS {
+            return None;
+        }
+        Some(Rectangle::new(
+            self.area.x + MOVE_LIST_X + MOVE_NUMBER_WIDTH + (slot % 2) as i16 * MOVE_CELL_WIDTH,
+            self.area.y + MOVE_LIST_Y + row as i16 * MOVE_ROW_HEIGHT,
+            (MOVE_CELL_WIDTH - 10) as u16,
+            MOVE_ROW_HEIGHT as u16,
+        ))
+    }
+
     pub fn increment_event_count(&mut self) {
         self.event_count += 1;
     }
 
+    /// Free-form status line, for games `Turn` doesn't describe.
+    pub fn set_status(&mut self, status: String) {
+        self.turn_status = status;
+    }
+
+    /// `Some` while the stream is reconnecting or lost; `None` once it's
+    /// back.
+    pub fn set_connection(&mut self, connection: Option<String>) {
+        self.connection = connection;
+    }
+
+    /// Keep the latest report; returns whether it reached a new depth and
+    /// so is worth a redraw (a search sends many lines per depth).
+    pub fn set_analysis(&mut self, depth: u8, line: String) -> bool {
+        let deeper = self.analysis.as_ref().is_none_or(|(d, _)| *d != depth);
+        self.analysis = Some((depth, line));
+        deeper
+    }
+
+    pub fn set_notice(&mut self, notice: String) {
+        self.notice = Some(notice);
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connection.is_none()
+    }
+
     pub fn set_turn(&mut self, turn: Turn) {
         self.turn_status = match turn {
             Turn::Playing => "Your turn".to_string(),
             Turn::Waiting => "Waiting for opponent".to_string(),
-            Turn::Over { winner: Some(w) } => format!("{} won!", w),
-            Turn::Over { winner: None } => "Game over".to_string(),
+            Turn::Over {
+                winner: Some(w),
+                reason: Some(r),
+            } => format!("{}: {} won!", r, w),
+            Turn::Over {
+                winner: Some(w),
+                reason: None,
+            } => format!("{} won!", w),
+            Turn::Over {
+                winner: None,
+                reason: Some(r),
+            } => format!("Game over: {}", r),
+            Turn::Over {
+                winner: None,
+                reason: None,
+            } => "Game over".to_string(),
         };
     }
 
@@ -62,6 +344,11 @@ impl SidebarWidget {
         }
 
         if touch.kind == TouchKind::Up {
+            if self.chat_enabled && self.chat_button.rect.contains(touch.x, touch.y) {
+                info!("Chat button pressed");
+                return Some(AppEvent::OpenChat);
+            }
+
             if self.menu_button.rect.contains(touch.x, touch.y) {
                 info!("Menu button pressed");
                 return Some(AppEvent::ShowMenu);
@@ -71,11 +358,51 @@ impl SidebarWidget {
                 info!("Back button pressed");
                 return Some(AppEvent::ExitToMenu);
             }
+
+            // Browsing: the board widget clamps a ply at or past the end of
+            // the game back to live.
+            let shown = self.shown_ply();
+            if self.prev_button.rect.contains(touch.x, touch.y) {
+                return shown.checked_sub(1).map(|ply| AppEvent::BrowseTo(Some(ply)));
+            }
+            if self.next_button.rect.contains(touch.x, touch.y) {
+                return self.viewing.map(|_| AppEvent::BrowseTo(Some(shown + 1)));
+            }
+            if self.live_button.rect.contains(touch.x, touch.y) {
+                return self.viewing.map(|_| AppEvent::BrowseTo(None));
+            }
+            // The prompt covers the move list while it's up.
+            if self.prompt().is_some() {
+                let accept = self.accept_button.rect.contains(touch.x, touch.y);
+                let decline = self.decline_button.rect.contains(touch.x, touch.y);
+                if accept || decline {
+                    return self.answer_prompt(accept);
+                }
+                return None;
+            }
+            if let Some(index) = (0..self.moves.len())
+                .find(|&i| self.move_cell(i).is_some_and(|r| r.contains(touch.x, touch.y)))
+            {
+                info!("Browsing to {}", self.moves[index]);
+                return Some(AppEvent::BrowseTo(Some(index + 1)));
+            }
         }
 
         None
     }
 
+    // The prompt is closed straight away; the server's next game state
+    // brings it back if the answer didn't go through. Waiting on a claim
+    // just hides it.
+    fn answer_prompt(&mut self, accept: bool) -> Option<AppEvent> {
+        if let Some(offer) = self.offer.take() {
+            info!("Answering {:?} offer: {}", offer, accept);
+            return Some(AppEvent::AnswerOffer(offer, accept));
+        }
+        self.claimable = false;
+        accept.then_some(AppEvent::ClaimVictory)
+    }
+
     pub fn render(&self, renderer: &mut Renderer) -> Result<(), Box<dyn std::error::Error>> {
         // Clear sidebar area
         renderer.draw_rectangle(self.area, DrawColor::White, true)?;
@@ -83,17 +410,117 @@ impl SidebarWidget {
         // Draw border
         renderer.draw_rectangle(self.area, DrawColor::Black, false)?;
 
-        // Turn status text, centred near the top of the sidebar.
+        // Turn status text, centred over the move list. A connection
+        // problem takes its place: the turn may be stale.
         let size_px = 32.0;
-        let (tw, _th) = renderer.measure_text(&self.turn_status, size_px);
-        let tx = self.area.x + (self.area.width as i16 - tw as i16) / 2;
-        let ty = self.area.y + 50;
-        renderer.draw_text(tx, ty, &self.turn_status, size_px, DrawColor::Black)?;
+        let aside = self
+            .notice
+            .as_ref()
+            .or(self.analysis.as_ref().map(|(_, a)| a));
+        let status = match (&self.connection, aside) {
+            (Some(connection), _) => connection.clone(),
+            (None, Some(aside)) => format!("{} · {}", self.turn_status, aside),
+            (None, None) => self.turn_status.clone(),
+        };
+        let (tw, _th) = renderer.measure_text(&status, size_px);
+        let column_width = self.prev_button.rect.x - self.area.x;
+        let tx = self.area.x + (column_width - tw as i16) / 2;
+        let ty = self.area.y + 12;
+        renderer.draw_text(tx, ty, &status, size_px, DrawColor::Black)?;
+
+        match self.prompt() {
+            Some((question, _, _)) => {
+                let (qw, _) = renderer.measure_text(question, size_px);
+                let qx = self.area.x + (column_width - qw as i16) / 2;
+                let qy = self.area.y + MOVE_LIST_Y;
+                renderer.draw_text(qx, qy, question, size_px, DrawColor::Black)?;
+                self.accept_button.draw(renderer)?;
+                self.decline_button.draw(renderer)?;
+            }
+            None => self.render_moves(renderer)?,
+        }
 
         // Draw buttons
+        self.prev_button.draw(renderer)?;
+        self.live_button.draw(renderer)?;
+        self.next_button.draw(renderer)?;
+        if self.chat_enabled {
+            self.chat_button.draw(renderer)?;
+        }
         self.menu_button.draw(renderer)?;
         self.back_button.draw(renderer)?;
 
         Ok(())
     }
+
+    // The visible move-pair rows, with the move that led to the shown
+    // position framed.
+    fn render_moves(&self, renderer: &mut Renderer) -> Result<(), Box<dyn std::error::Error>> {
+        let current = self.shown_ply().checked_sub(1);
+        let offset = self.black_first as usize;
+        let first = (self.scroll * 2).saturating_sub(offset);
+        let last = ((self.scroll + MOVE_ROWS) * 2 - offset).min(self.moves.len());
+        for index in first..last {
+            let Some(cell) = self.move_cell(index) else {
+                continue;
+            };
+            if let Some(number) = self.move_number(index) {
+                renderer.draw_text(
+                    self.area.x + MOVE_LIST_X,
+                    cell.y + 2,
+                    &number,
+                    MOVE_FONT_SIZE,
+                    DrawColor::Black,
+                )?;
+            }
+            if current == Some(index) {
+                // Text is drawn on its own white box, so the highlight is a
+                // heavy frame rather than an inverted cell.
+                let inner = Rectangle::new(cell.x + 1, cell.y + 1, cell.width - 2, cell.height - 2);
+                renderer.draw_rectangle(cell, DrawColor::Black, false)?;
+                renderer.draw_rectangle(inner, DrawColor::Black, false)?;
+            }
+            renderer.draw_text(
+                cell.x + 8,
+                cell.y + 2,
+                &self.moves[index],
+                MOVE_FONT_SIZE,
+                DrawColor::Black,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn san(moves: &[&str]) -> Vec<String> {
+        moves.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn numbering_starts_from_the_root_position() {
+        let mut sidebar = SidebarWidget::new(Rectangle::new(0, 0, 1000, 300));
+        sidebar.set_moves(san(&["e4", "e5", "Nf3"]), Color::White, 1);
+        let numbers: Vec<_> = (0..3).map(|i| sidebar.move_number(i)).collect();
+        assert_eq!(
+            numbers,
+            [Some("1.".to_string()), None, Some("2.".to_string())]
+        );
+
+        // Set up with Black to move on move 12: Black's reply opens the
+        // list on the right of its row.
+        sidebar.set_moves(san(&["Nc6", "Bb5", "a6"]), Color::Black, 12);
+        let numbers: Vec<_> = (0..3).map(|i| sidebar.move_number(i)).collect();
+        assert_eq!(
+            numbers,
+            [Some("12…".to_string()), Some("13.".to_string()), None]
+        );
+        let cell = |i| sidebar.move_cell(i).unwrap();
+        assert_eq!(cell(0).y, cell(1).y - MOVE_ROW_HEIGHT);
+        assert_eq!(cell(0).x, cell(1).x + MOVE_CELL_WIDTH);
+        assert_eq!(cell(2).x, cell(0).x);
+    }
 }